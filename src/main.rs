@@ -4,41 +4,254 @@
 #![feature(iter_intersperse)]
 
 use std::{
-    collections::{HashMap, hash_map::Entry},
+    cmp::Reverse,
+    collections::{HashMap, HashSet, VecDeque, hash_map::Entry},
     f32,
     fmt::Debug,
+    ops::Range,
+    path::{Path, PathBuf},
 };
 
 use arrayvec::{ArrayString, ArrayVec};
 use raylib::prelude::*;
 
-const NODE_LINE_LENGTH: usize = 18;
-const NODE_LINES: usize = 15;
-const NODE_TEXT_BUFFER_SIZE: usize = (NODE_LINE_LENGTH + 1) * NODE_LINES;
+mod num;
+
+use num::{Arithmetic, Num};
+
+/// the largest node a `Layout` can describe, the text buffers are sized for it
+const MAX_NODE_LINE_LENGTH: usize = 36;
+const MAX_NODE_LINES: usize = 30;
+const NODE_TEXT_BUFFER_SIZE: usize = (MAX_NODE_LINE_LENGTH + 1) * MAX_NODE_LINES;
+const NODE_NAME_LENGTH: usize = 18;
 const NODE_FONT_SIZE: f32 = 20.;
 const NODE_LINE_HEIGHT: f32 = 20.;
+/// the advance of every glyph, which is why only monospace fonts can be used
 const NODE_CHAR_WIDTH: f32 = (9. / 20.) * NODE_FONT_SIZE + NODE_FONT_SPACING;
 const NODE_FONT_SPACING: f32 = 3.;
-const NODE_INSIDE_SIDE_LENGTH: f32 = NODE_LINES as f32 * NODE_FONT_SIZE;
 const NODE_INSIDE_PADDING: f32 = 10.;
 const NODE_OUTSIDE_PADDING: f32 = 100.;
-const NODE_OUTSIDE_SIDE_LENGTH: f32 = NODE_INSIDE_SIDE_LENGTH + 2. * NODE_INSIDE_PADDING;
+/// room for the widest register value, `-999`, with a little to spare
+const MIN_GIZMO_WIDTH: f32 = 5. * NODE_CHAR_WIDTH;
+/// a gizmo's label over its value
+const MIN_GIZMO_HEIGHT: f32 = 2. * NODE_LINE_HEIGHT;
 const GHOST_NODE_DASHES: usize = 8;
+/// what a tab turns into, since the node text is always drawn one cell per character
+const INDENT: &str = "  ";
 const LINE_THICKNESS: f32 = 2.0;
-const GIZMO_HEIGHT: f32 = NODE_OUTSIDE_SIDE_LENGTH / 4.0;
-const GIZMO_WIDTH: f32 = NODE_OUTSIDE_SIDE_LENGTH - NODE_TEXT_BOX_OUTSIDE_WIDTH;
-const NODE_TEXT_BOX_INSIDE_WIDTH: f32 = (NODE_LINE_LENGTH as f32 + 0.5) * NODE_CHAR_WIDTH;
-const NODE_TEXT_BOX_OUTSIDE_WIDTH: f32 = NODE_TEXT_BOX_INSIDE_WIDTH + 2.0 * NODE_INSIDE_PADDING;
-const KEY_REPEAT_DELAY_S: f32 = 0.5;
-const KEY_REPEAT_INTERVAL_S: f32 = 1.0 / 30.0;
-
-const GHOST_COLOR: Color = Color::GRAY;
+/// unless it's changed with `--repeat-delay=<seconds>`
+const DEFAULT_KEY_REPEAT_DELAY_S: f32 = 0.5;
+/// unless it's changed with `--repeat-interval=<seconds>`
+const DEFAULT_KEY_REPEAT_INTERVAL_S: f32 = 1.0 / 30.0;
+const DEFAULT_ZOOM: f32 = 0.85;
+const MIN_ZOOM: f32 = 0.5;
+const MAX_ZOOM: f32 = 4.0;
+/// how much of the way to the highlighted node the camera moves each frame,
+/// unless it's changed with `--camera-follow=<factor>` or `--no-smoothing`
+const DEFAULT_CAMERA_FOLLOW: f32 = 0.7;
+/// any slower and the camera would take ages to catch up
+const MIN_CAMERA_FOLLOW: f32 = 0.1;
+/// how many cycles fast-forwarding runs at most, it stops early once nothing is happening
+const FAST_FORWARD_CYCLES: u32 = 1000;
+/// how many cycles can be stepped back through, the oldest ones are dropped past that
+const STEP_HISTORY_DEPTH: usize = 100;
 
 type Nodes = HashMap<NodeCoord, Node>;
 
 struct State {
     camera: Camera2D,
     model: Model,
+    keymap: Keymap,
+    theme: Theme,
+    layout: Layout,
+    /// see `DEFAULT_CAMERA_FOLLOW`, at 1 the camera snaps straight to the highlighted node
+    camera_follow: f32,
+    key_repeat: KeyRepeat,
+    /// what new exec nodes start out with, set by `--template path.txt`. empty unless that's given
+    exec_template: NodeText,
+    show_stats: bool,
+    show_grid: bool,
+}
+
+fn camera_follow_from_args() -> f32 {
+    if std::env::args().any(|arg| arg == "--no-smoothing") {
+        return 1.0;
+    }
+
+    std::env::args()
+        .find_map(|arg| arg.strip_prefix("--camera-follow=")?.parse::<f32>().ok())
+        .filter(|follow| follow.is_finite())
+        .map_or(DEFAULT_CAMERA_FOLLOW, |follow| {
+            follow.clamp(MIN_CAMERA_FOLLOW, 1.0)
+        })
+}
+
+/// the shape of every node, picked at startup with `--node-size=<line length>x<lines>`.
+/// the geometry is derived from it, so nodes are always square, as tall as their text,
+/// and wide enough for it next to the gizmos
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct Layout {
+    line_length: usize,
+    lines: usize,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self {
+            line_length: 18,
+            lines: 15,
+        }
+    }
+}
+
+impl Layout {
+    /// what loaded text is checked against, so a workspace saved with a bigger `--node-size=`
+    /// still opens. whatever doesn't fit the actual layout is scrolled
+    const LARGEST: Layout = Layout {
+        line_length: MAX_NODE_LINE_LENGTH,
+        lines: MAX_NODE_LINES,
+    };
+
+    /// anything bigger wouldn't fit in the text buffers
+    fn new(line_length: usize, lines: usize) -> Option<Self> {
+        ((1..=MAX_NODE_LINE_LENGTH).contains(&line_length) && (1..=MAX_NODE_LINES).contains(&lines))
+            .then_some(Self { line_length, lines })
+    }
+
+    /// `<line length>x<lines>`, as given to `--node-size=`
+    fn parse(size: &str) -> Option<Self> {
+        let (line_length, lines) = size.split_once('x')?;
+
+        Layout::new(line_length.parse().ok()?, lines.parse().ok()?)
+    }
+
+    fn from_args() -> Self {
+        let Some(size) =
+            std::env::args().find_map(|arg| arg.strip_prefix("--node-size=").map(str::to_owned))
+        else {
+            return Self::default();
+        };
+
+        Layout::parse(&size).unwrap_or_else(|| {
+            println!(
+                "--node-size={size} isn't <line length>x<lines> within \
+                 {MAX_NODE_LINE_LENGTH}x{MAX_NODE_LINES}, using the default"
+            );
+            Self::default()
+        })
+    }
+
+    /// the text's height, unless the text is too wide to leave room for the gizmos beside it,
+    /// or too short for them to stack up next to it
+    fn inside_side_length(&self) -> f32 {
+        let text_height = self.lines as f32 * NODE_FONT_SIZE;
+        let text_and_gizmos_width =
+            self.text_box_outside_width() + MIN_GIZMO_WIDTH - 2. * NODE_INSIDE_PADDING;
+        let gizmos_height = 4. * MIN_GIZMO_HEIGHT - 2. * NODE_INSIDE_PADDING;
+
+        text_height.max(text_and_gizmos_width).max(gizmos_height)
+    }
+
+    fn outside_side_length(&self) -> f32 {
+        self.inside_side_length() + 2. * NODE_INSIDE_PADDING
+    }
+
+    fn cell_size(&self) -> f32 {
+        self.outside_side_length() + NODE_OUTSIDE_PADDING
+    }
+
+    fn text_box_inside_width(&self) -> f32 {
+        (self.line_length as f32 + 0.5) * NODE_CHAR_WIDTH
+    }
+
+    fn text_box_outside_width(&self) -> f32 {
+        self.text_box_inside_width() + 2.0 * NODE_INSIDE_PADDING
+    }
+
+    fn gizmo_width(&self) -> f32 {
+        self.outside_side_length() - self.text_box_outside_width()
+    }
+
+    fn gizmo_height(&self) -> f32 {
+        self.outside_side_length() / 4.0
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Theme {
+    background: Color,
+    text: Color,
+    /// secondary text, like the gizmo labels
+    dim_text: Color,
+    border: Color,
+    highlighted_border: Color,
+    /// the border of a node that's part of the multi-node selection
+    selected_border: Color,
+    /// the border of a node that's stuck waiting on IO
+    blocked_border: Color,
+    comment: Color,
+    error: Color,
+    /// code that runs, but probably doesn't do what was meant
+    warning: Color,
+    executing_line: Color,
+    blocked_line: Color,
+    /// text drawn on top of the executing line highlight
+    executing_text: Color,
+    selection: Color,
+    ghost: Color,
+    /// the lines between cells, which should be barely there
+    grid: Color,
+    /// set by `--error-marks`: errors get a second outline on top of their color,
+    /// for anyone who can't pick the error color out from the rest
+    error_marks: bool,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            background: Color::BLACK,
+            text: Color::WHITE,
+            dim_text: Color::GRAY,
+            border: Color::GRAY,
+            highlighted_border: Color::WHITE,
+            selected_border: Color::SKYBLUE,
+            blocked_border: Color::DARKGRAY,
+            comment: Color::GRAY,
+            error: Color::RED,
+            warning: Color::GOLD,
+            executing_line: Color::WHITE,
+            blocked_line: Color::GRAY,
+            executing_text: Color::BLACK,
+            selection: Color::GRAY,
+            ghost: Color::GRAY,
+            grid: Color::new(30, 30, 30, 255),
+            error_marks: false,
+        }
+    }
+}
+
+impl Theme {
+    fn high_contrast() -> Self {
+        Self {
+            background: Color::BLACK,
+            text: Color::WHITE,
+            dim_text: Color::LIGHTGRAY,
+            border: Color::LIGHTGRAY,
+            highlighted_border: Color::YELLOW,
+            selected_border: Color::GREEN,
+            blocked_border: Color::PINK,
+            comment: Color::SKYBLUE,
+            error: Color::ORANGE,
+            warning: Color::YELLOW,
+            executing_line: Color::YELLOW,
+            blocked_line: Color::LIGHTGRAY,
+            executing_text: Color::BLACK,
+            selection: Color::BLUE,
+            ghost: Color::LIGHTGRAY,
+            grid: Color::DARKGRAY,
+            error_marks: false,
+        }
+    }
 }
 
 struct Model {
@@ -46,14 +259,274 @@ struct Model {
     highlighted_node: NodeCoord,
     ghosts: Ghosts,
     node_clipboard: Option<Node>,
+    prompt: Option<Prompt>,
+    view: View,
+    /// whether there are changes that haven't been saved to `current_path`
+    dirty: bool,
+    current_path: Option<PathBuf>,
+    show_help: bool,
+    show_io_log: bool,
+    /// marks the sides that exec nodes read from or write to with no node there
+    show_dangling_io: bool,
+    /// nodes that move together, picked out with Shift+click or by dragging a rubber band
+    selection: HashSet<NodeCoord>,
+    /// the corners of the rubber band being dragged out, in world space
+    rubber_band: Option<(Vector2, Vector2)>,
+    /// cells pinned by a puzzle, which can't be edited, deleted or moved
+    locked: HashSet<NodeCoord>,
+    /// set by a puzzle, to join the edges of the network up with each other
+    torus: Option<Torus>,
+    /// set by `--view`, which treats every cell as locked and can't add nodes either.
+    /// the network can still be run, saved, and replaced by opening another file
+    read_only: bool,
+    /// how many cycles have been run since the network was last stopped
+    cycle: u32,
+    /// the network before each of the last few cycles, newest last
+    history: VecDeque<Snapshot>,
+    /// how many cycles the last step until the highlighted node changed took,
+    /// until the network is stepped some other way
+    watch_cycles: Option<u32>,
+    /// the values passed between nodes over the last few cycles, with the cycle they arrived in.
+    /// newest last
+    io_log: VecDeque<(u32, Transfer)>,
+    /// how many entries the IO log panel is scrolled back from the newest one
+    io_log_scroll: usize,
+    /// the node that's being typed into, which is the last one whose text changed
+    /// as long as it stays highlighted. Tab indents it instead of stepping the network
+    editing: Option<NodeCoord>,
+}
+
+/// everything needed to put the network back the way it was, including the registers of
+/// running nodes. only the parts running it can change are kept, since there's one for every
+/// cycle that's stepped
+#[derive(Clone, Debug)]
+struct Snapshot {
+    runtimes: HashMap<NodeCoord, NodeRuntime>,
+    highlighted_node: NodeCoord,
+}
+
+/// the parts of a node that running the network changes, along with what they depend on,
+/// so they're only put back into the same node. a running node's code stands in for its text
+#[derive(Clone, Debug)]
+enum NodeRuntime {
+    Exec(Option<NodeExec>),
+    Input(ArrayVec<Num, INPUT_NODE_CAP>, Option<usize>),
+    Output(Option<ArrayVec<Num, OUTPUT_NODE_CAP>>),
+    Random(u32, Option<u32>),
+}
+
+fn runtimes(nodes: &Nodes) -> HashMap<NodeCoord, NodeRuntime> {
+    nodes
+        .iter()
+        .map(|(&node_loc, node)| {
+            let runtime = match node {
+                Node::Exec(exec_node) => NodeRuntime::Exec(exec_node.exec.clone()),
+                Node::Input(input_node) => {
+                    NodeRuntime::Input(input_node.data.clone(), input_node.index)
+                }
+                Node::Output(output_node) => NodeRuntime::Output(output_node.received.clone()),
+                Node::Random(random_node) => {
+                    NodeRuntime::Random(random_node.seed, random_node.state)
+                }
+            };
+
+            (node_loc, runtime)
+        })
+        .collect()
+}
+
+impl Model {
+    /// a blank workspace, with nothing in it and nothing to save
+    fn empty() -> Model {
+        Model {
+            nodes: Nodes::new(),
+            highlighted_node: NodeCoord::at(0, 0),
+            ghosts: Ghosts::None,
+            node_clipboard: None,
+            prompt: None,
+            view: View::FollowHighlight,
+            dirty: false,
+            current_path: None,
+            show_help: false,
+            show_dangling_io: false,
+            show_io_log: false,
+            selection: HashSet::new(),
+            rubber_band: None,
+            locked: HashSet::new(),
+            torus: None,
+            read_only: false,
+            cycle: 0,
+            history: VecDeque::new(),
+            watch_cycles: None,
+            io_log: VecDeque::new(),
+            io_log_scroll: 0,
+            editing: None,
+        }
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            runtimes: runtimes(&self.nodes),
+            highlighted_node: self.highlighted_node,
+        }
+    }
+
+    fn restore(self, snapshot: Snapshot) -> Model {
+        let mut nodes = self.nodes;
+
+        restore_runtime(&mut nodes, snapshot.runtimes);
+
+        Model {
+            nodes,
+            highlighted_node: snapshot.highlighted_node,
+            ..self
+        }
+    }
+}
+
+/// steps the network until it stops doing anything, `stop` says so after a cycle,
+/// or `FAST_FORWARD_CYCLES` have gone by. returns how many cycles ran.
+/// `cycle` is how many had been run before, for the IO log
+fn run_until(
+    nodes: &mut Nodes,
+    history: &mut VecDeque<Snapshot>,
+    io_log: &mut VecDeque<(u32, Transfer)>,
+    cycle: u32,
+    starting_node: NodeCoord,
+    torus: Option<Torus>,
+    mut stop: impl FnMut(&Nodes) -> bool,
+) -> u32 {
+    let mut elapsed = 0;
+
+    while elapsed < FAST_FORWARD_CYCLES {
+        let Some((updated_nodes, report)) = step_execution(nodes, starting_node, torus) else {
+            break;
+        };
+
+        push_history(
+            history,
+            Snapshot {
+                runtimes: runtimes(nodes),
+                highlighted_node: starting_node,
+            },
+        );
+
+        nodes.extend(updated_nodes);
+        elapsed += 1;
+
+        log_transfers(io_log, cycle + elapsed, &report.transfers);
+
+        if report.status != NetworkStatus::Running || stop(nodes) {
+            break;
+        }
+    }
+
+    elapsed
+}
+
+/// what running the network can change about an exec node: its registers and what it's sending
+fn watched_state(nodes: &Nodes, node_loc: NodeCoord) -> Option<((Num, Num, u8), NodeIO)> {
+    match nodes.get(&node_loc) {
+        Some(Node::Exec(exec_node)) => {
+            let io = exec_node.exec.as_ref()?.io.clone();
+
+            Some((exec_node.registers()?, io))
+        }
+        Some(Node::Input(_) | Node::Output(_) | Node::Random(_)) | None => None,
+    }
+}
+
+const IO_LOG_DEPTH: usize = 200;
+
+fn log_transfers(io_log: &mut VecDeque<(u32, Transfer)>, cycle: u32, transfers: &[Transfer]) {
+    for &transfer in transfers {
+        if io_log.len() == IO_LOG_DEPTH {
+            io_log.pop_front();
+        }
+
+        io_log.push_back((cycle, transfer));
+    }
+}
+
+/// puts back what running the network changed since `snapshot`, for the nodes that are
+/// still the same ones in the same places. whatever has been edited, added or moved since stays
+fn restore_runtime(nodes: &mut Nodes, mut snapshot: HashMap<NodeCoord, NodeRuntime>) {
+    for (node_loc, node) in nodes.iter_mut() {
+        match (node, snapshot.remove(node_loc)) {
+            (Node::Exec(now), Some(NodeRuntime::Exec(exec)))
+                if now.exec.as_ref().map(|exec| &exec.code)
+                    == exec.as_ref().map(|exec| &exec.code) =>
+            {
+                now.exec = exec;
+            }
+            (Node::Input(now), Some(NodeRuntime::Input(data, index))) if now.data == data => {
+                now.index = index;
+            }
+            (Node::Output(now), Some(NodeRuntime::Output(received))) => now.received = received,
+            (Node::Random(now), Some(NodeRuntime::Random(seed, state))) if now.seed == seed => {
+                now.state = state;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn push_history(history: &mut VecDeque<Snapshot>, snapshot: Snapshot) {
+    if history.len() == STEP_HISTORY_DEPTH {
+        history.pop_front();
+    }
+
+    history.push_back(snapshot);
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum View {
+    FollowHighlight,
+    /// keeps every node on screen, until the highlight moves or the user zooms
+    FitAll,
+    /// stays wherever zooming with the mouse left it, until the highlight moves
+    ZoomedToCursor,
+    /// puts the camera back the way it starts out, then follows the highlight from there
+    Reset,
+}
+
+/// A modal text entry that captures all typing until it's confirmed with enter or cancelled with escape
+#[derive(Clone, Debug)]
+enum Prompt {
+    RenameNode(NodeName),
+    FindReplace {
+        find: PromptText,
+        replacement: PromptText,
+        editing_replacement: bool,
+    },
+    /// `invalid` is set when enter was pressed on something that isn't a coordinate,
+    /// until the text is changed again
+    GoTo {
+        text: PromptText,
+        invalid: bool,
+    },
+    /// a value and a direction to hand to the highlighted node, like `5 LEFT`.
+    /// `invalid` works like it does for `GoTo`
+    Poke {
+        text: PromptText,
+        invalid: bool,
+    },
+    /// asks before removing every node connected to the highlighted one
+    DeleteCluster,
+    /// asks before throwing away unsaved changes for a blank workspace
+    NewWorkspace,
+}
+
+type PromptText = ArrayString<NODE_NAME_LENGTH>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 enum Modifiers {
     None,
     Ctrl,
     Shift,
     CtrlShift,
+    Alt,
+    AltShift,
 }
 
 #[derive(PartialEq, Eq)]
@@ -64,11 +537,14 @@ enum Ghosts {
 }
 
 type NodeText = ArrayString<NODE_TEXT_BUFFER_SIZE>;
+type NodeName = ArrayString<NODE_NAME_LENGTH>;
 
 #[derive(Clone, Debug)]
 enum Node {
     Exec(ExecNode),
     Input(InputNode),
+    Output(OutputNode),
+    Random(RandomNode),
     // Stack,
 }
 
@@ -77,91 +553,275 @@ impl Node {
         Self::Exec(ExecNode::empty())
     }
 
-    fn exec_with_text(text: &str) -> Option<Self> {
-        let text = ArrayString::from(text).ok()?;
+    fn exec_with_text(text: &str, layout: &Layout) -> Result<Self, TextOverflow> {
+        validate(text, layout)?;
 
-        if !validate(&text) {
-            return None;
-        }
+        // multi-byte characters can outgrow the buffer even when every line is short enough
+        let text = ArrayString::from(text).map_err(|_| TextOverflow::LineTooLong)?;
 
         let mut exec_node = ExecNode {
             text,
-            cursor: 0,
-            select_cursor: 0,
-            error: None,
-            exec: None,
+            ..ExecNode::empty()
         };
 
-        exec_node.update_error();
+        exec_node.reparse();
 
-        Some(Node::Exec(exec_node))
+        Ok(Node::Exec(exec_node))
     }
 
-    fn exec_with_lines<'str>(lines: impl IntoIterator<Item = &'str str>) -> Option<Self> {
-        let text =
-            ArrayString::from(&lines.into_iter().intersperse("\n").collect::<String>()).ok()?;
+    fn exec_with_lines<'str>(
+        lines: impl IntoIterator<Item = &'str str>,
+        layout: &Layout,
+    ) -> Result<Self, TextOverflow> {
+        Node::exec_with_text(
+            &lines.into_iter().intersperse("\n").collect::<String>(),
+            layout,
+        )
+    }
 
-        if !validate(&text) {
-            return None;
-        }
+    fn empty_input() -> Self {
+        Self::Input(InputNode::with_data(ArrayVec::new()))
+    }
 
-        let mut exec_node = ExecNode {
-            text,
-            cursor: 0,
-            select_cursor: 0,
-            error: None,
-            exec: None,
-        };
+    fn empty_output() -> Self {
+        Self::Output(OutputNode::empty())
+    }
 
-        exec_node.update_error();
+    fn random(seed: u32) -> Self {
+        Self::Random(RandomNode::with_seed(seed))
+    }
 
-        Some(Node::Exec(exec_node))
+    fn name(&self) -> Option<&NodeName> {
+        match self {
+            Node::Exec(exec_node) => exec_node.name.as_ref(),
+            Node::Input(input_node) => input_node.name.as_ref(),
+            Node::Output(output_node) => output_node.name.as_ref(),
+            Node::Random(random_node) => random_node.name.as_ref(),
+        }
     }
 
-    fn empty_input() -> Self {
-        Self::Input(InputNode {
-            data: ArrayVec::new(),
-            index: None,
-        })
+    fn set_name(&mut self, name: Option<NodeName>) {
+        match self {
+            Node::Exec(exec_node) => exec_node.name = name,
+            Node::Input(input_node) => input_node.name = name,
+            Node::Output(output_node) => output_node.name = name,
+            Node::Random(random_node) => random_node.name = name,
+        }
     }
 }
 
 #[derive(Clone, Debug)]
 struct ExecNode {
+    name: Option<NodeName>,
     text: NodeText,
     cursor: usize,
     select_cursor: usize,
-    error: Option<ParseErr>,
+    /// the text parsed as of its last change, so it's never parsed more than once per edit
+    parsed: Result<NodeCode, ParseErr>,
+    /// only looked for once the code parses
+    warnings: Vec<Warning>,
     exec: Option<NodeExec>,
+    /// lines that stop a fast-forward once an instruction on them is about to run
+    breakpoints: HashSet<usize>,
+    /// why the last edit was refused, until an edit goes through
+    overflow: Option<TextOverflow>,
+    /// how ADD and SUB handle going out of range
+    arithmetic: Arithmetic,
+    /// the line and column shown in the top left corner, for text that doesn't fit the layout
+    scroll: (usize, usize),
 }
 
 impl ExecNode {
     fn empty() -> Self {
         Self {
+            name: None,
             text: ArrayString::new(),
             cursor: 0,
             select_cursor: 0,
-            error: None,
+            parsed: Ok(NodeCode::new()),
+            warnings: Vec::new(),
             exec: None,
+            breakpoints: HashSet::new(),
+            overflow: None,
+            arithmetic: Arithmetic::Saturating,
+            scroll: (0, 0),
+        }
+    }
+
+    /// ACC, BAK and the instruction pointer, while the node is running
+    fn registers(&self) -> Option<(Num, Num, u8)> {
+        self.exec.as_ref().map(|exec| (exec.acc, exec.bak, exec.ip))
+    }
+
+    /// how many cycles the node has spent running instructions since it started,
+    /// as opposed to waiting on IO or halted
+    fn steps(&self) -> Option<u32> {
+        self.exec.as_ref().map(|exec| exec.steps)
+    }
+
+    fn error(&self) -> Option<&ParseErr> {
+        self.parsed.as_ref().err()
+    }
+
+    /// not counting labels, comments or blank lines. code that doesn't parse has none
+    fn instruction_count(&self) -> usize {
+        self.parsed.as_ref().map_or(0, |code| code.len())
+    }
+
+    /// the breakpoints are dropped if lines were added or removed,
+    /// since they would end up next to the wrong code
+    fn set_text(&mut self, new_text: NodeText) {
+        if new_text.split('\n').count() != self.text.split('\n').count() {
+            self.breakpoints.clear();
+        }
+
+        self.text = new_text;
+        self.overflow = None;
+        self.clamp_cursors();
+        self.reparse();
+    }
+
+    /// applies the edit if it fits, otherwise remembers why it didn't
+    fn try_set_text(&mut self, new_text: NodeText, layout: &Layout) -> bool {
+        match validate(&new_text, &self.room(layout)) {
+            Ok(()) => {
+                self.set_text(new_text);
+                true
+            }
+            Err(overflow) => {
+                self.overflow = Some(overflow);
+                false
+            }
+        }
+    }
+
+    /// keeps both cursors inside the text and on char boundaries, so slicing at them can't panic
+    fn clamp_cursors(&mut self) {
+        let clamp = |text: &NodeText, mut index: usize| {
+            index = index.min(text.len());
+
+            while !text.is_char_boundary(index) {
+                index -= 1;
+            }
+
+            index
+        };
+
+        self.cursor = clamp(&self.text, self.cursor);
+        self.select_cursor = clamp(&self.text, self.select_cursor);
+    }
+
+    /// the layout, stretched to fit text that was loaded with a bigger one. edits to that text
+    /// aren't refused for it being too big already, as long as they don't make it any bigger
+    fn room(&self, layout: &Layout) -> Layout {
+        let lines = self.text.split('\n');
+
+        Layout {
+            line_length: lines
+                .clone()
+                .map(|line| line.chars().count())
+                .fold(layout.line_length, usize::max),
+            lines: layout.lines.max(lines.count()),
+        }
+    }
+
+    /// moves the text as little as it takes to bring the cursor into view
+    fn scroll_to_cursor(&mut self, layout: &Layout) {
+        let (line, column) = line_column(&self.text, self.cursor);
+        let room = self.room(layout);
+        let (top, left) = self.scroll;
+
+        self.scroll = (
+            top.clamp((line + 1).saturating_sub(layout.lines), line)
+                .min(room.lines - layout.lines),
+            left.clamp(column.saturating_sub(layout.line_length), column)
+                .min(room.line_length - layout.line_length),
+        );
+    }
+
+    /// the lines and columns that are scrolled into view
+    fn shown(&self, layout: &Layout) -> (Range<usize>, Range<usize>) {
+        let (top, left) = self.scroll;
+
+        (top..top + layout.lines, left..left + layout.line_length)
+    }
+
+    /// down by `lines`, or up if it's negative, no further than it takes to show the last line
+    fn scroll_by(&mut self, lines: isize, layout: &Layout) {
+        let room = self.room(layout);
+
+        self.scroll.0 = self
+            .scroll
+            .0
+            .saturating_add_signed(lines)
+            .min(room.lines - layout.lines);
+    }
+
+    fn toggle_breakpoint(&mut self) {
+        let (line, _) = line_column(&self.text, self.cursor);
+
+        if !self.breakpoints.remove(&line) {
+            self.breakpoints.insert(line);
         }
     }
 
+    /// whether the instruction that's about to run is on a breakpoint
+    fn at_breakpoint(&self) -> bool {
+        self.exec
+            .as_ref()
+            .and_then(|exec| exec.code.get(exec.ip as usize))
+            .is_some_and(|instruction| self.breakpoints.contains(&(instruction.src_line as usize)))
+    }
+
     fn is_in_edit_mode(&self) -> bool {
         self.exec.is_none()
     }
 
-    fn backspace(&mut self) {
+    /// starts a running node over from its first instruction, with empty registers and no IO.
+    /// it keeps running, and a node that isn't running is left alone
+    fn reset_runtime(&mut self) {
+        if let Some(exec) = &mut self.exec {
+            *exec = NodeExec::init(std::mem::take(&mut exec.code));
+        }
+    }
+
+    fn backspace(&mut self, layout: &Layout) {
+        self.clamp_cursors();
+
         if self.text_selected() {
-            self.insert("");
+            self.insert("", layout);
         } else {
-            let Some(index) = self.cursor.checked_sub(1) else {
+            let Some(prev_char) = self.text[..self.cursor].chars().next_back() else {
                 return;
             };
 
-            self.text.remove(index);
-            self.cursor = index;
-            self.select_cursor = index;
-            self.update_error();
+            let index = self.cursor - prev_char.len_utf8();
+            let mut new_text = self.text;
+
+            new_text.remove(index);
+
+            // deleting a newline joins two lines, which might not fit on one
+            if self.try_set_text(new_text, layout) {
+                self.cursor = index;
+                self.select_cursor = index;
+            }
+        }
+    }
+
+    fn delete_forward(&mut self, layout: &Layout) {
+        self.clamp_cursors();
+
+        if self.text_selected() {
+            self.insert("", layout);
+        } else if self.cursor < self.text.len() {
+            let mut new_text = self.text;
+
+            new_text.remove(self.cursor);
+
+            // deleting a newline joins two lines, which might not fit on one
+            if self.try_set_text(new_text, layout) {
+                self.deselect();
+            }
         }
     }
 
@@ -177,8 +837,14 @@ impl ExecNode {
         }
     }
 
-    /// if text is selected, this replaces it
-    fn insert(&mut self, txt: &str) {
+    /// if text is selected, this replaces it.
+    /// Windows line endings and tabs are normalized first, as they tend to come from pasting
+    fn insert(&mut self, txt: &str, layout: &Layout) {
+        let txt = txt.replace('\r', "").replace('\t', INDENT);
+        let txt = txt.as_str();
+
+        self.clamp_cursors();
+
         let (select_start, select_end) = self.selection_range();
 
         let mut new_text = ArrayString::new();
@@ -189,11 +855,12 @@ impl ExecNode {
             new_text.try_push_str(&self.text[select_end..]),
         ];
 
-        if push_results.iter().all(Result::is_ok) && validate(&new_text) {
-            self.text = new_text;
+        if push_results.iter().any(Result::is_err) {
+            // reported the same way as `Node::exec_with_text` does
+            self.overflow = Some(TextOverflow::LineTooLong);
+        } else if self.try_set_text(new_text, layout) {
             self.cursor = select_start + txt.len();
             self.deselect();
-            self.update_error();
         }
     }
 
@@ -203,16 +870,60 @@ impl ExecNode {
         &self.text[select_start..select_end]
     }
 
-    fn enter(&mut self, select: bool) {
-        self.insert("\n");
+    fn enter(&mut self, select: bool, layout: &Layout) {
+        self.insert("\n", layout);
 
         if !select {
             self.deselect();
         }
     }
 
+    /// starts an empty line after the cursor's line, and moves there
+    fn open_line_below(&mut self, layout: &Layout) {
+        self.clamp_cursors();
+
+        let line_end = self.text[self.cursor..]
+            .find('\n')
+            .map_or(self.text.len(), |newline| self.cursor + newline);
+
+        self.open_line_at(line_end, line_end + 1, layout);
+    }
+
+    /// starts an empty line before the cursor's line, and moves there
+    fn open_line_above(&mut self, layout: &Layout) {
+        self.clamp_cursors();
+
+        let line_start = self.text[..self.cursor]
+            .rfind('\n')
+            .map_or(0, |newline| newline + 1);
+
+        self.open_line_at(line_start, line_start, layout);
+    }
+
+    fn open_line_at(&mut self, index: usize, cursor: usize, layout: &Layout) {
+        let mut new_text = NodeText::new();
+
+        let push_results = [
+            new_text.try_push_str(&self.text[..index]),
+            new_text.try_push_str("\n"),
+            new_text.try_push_str(&self.text[index..]),
+        ];
+
+        if push_results.iter().any(Result::is_err) {
+            self.overflow = Some(TextOverflow::LineTooLong);
+        } else if self.try_set_text(new_text, layout) {
+            self.cursor = cursor;
+            self.deselect();
+        }
+    }
+
+    /// steps over a whole char, however many bytes it takes up
     fn right(&mut self, select: bool) {
-        self.cursor = usize::min(self.cursor + 1, self.text.len());
+        self.clamp_cursors();
+
+        if let Some(next_char) = self.text[self.cursor..].chars().next() {
+            self.cursor += next_char.len_utf8();
+        }
 
         if !select {
             self.deselect();
@@ -220,7 +931,11 @@ impl ExecNode {
     }
 
     fn left(&mut self, select: bool) {
-        self.cursor = self.cursor.saturating_sub(1);
+        self.clamp_cursors();
+
+        if let Some(prev_char) = self.text[..self.cursor].chars().next_back() {
+            self.cursor -= prev_char.len_utf8();
+        }
 
         if !select {
             self.deselect();
@@ -239,7 +954,7 @@ impl ExecNode {
             if char == '\n' {
                 line += 1;
             }
-            cursor += 1;
+            cursor += char.len_utf8();
         }
 
         while column < target_column
@@ -248,7 +963,7 @@ impl ExecNode {
             if char == '\n' {
                 break;
             } else {
-                cursor += 1;
+                cursor += char.len_utf8();
                 column += 1;
             }
         }
@@ -257,6 +972,8 @@ impl ExecNode {
     }
 
     fn up(&mut self, select: bool) {
+        self.clamp_cursors();
+
         let (line, target_column) = line_column(&self.text, self.cursor);
 
         self.cursor = line
@@ -270,6 +987,8 @@ impl ExecNode {
     }
 
     fn down(&mut self, select: bool) {
+        self.clamp_cursors();
+
         let (line, target_column) = line_column(&self.text, self.cursor);
 
         let target_line = line + 1;
@@ -281,18 +1000,27 @@ impl ExecNode {
         }
     }
 
+    /// moves to the first non-whitespace character of the line,
+    /// or to the very start of the line if the cursor is already there
     fn home(&mut self, select: bool) {
-        let mut cursor = self.cursor;
+        self.clamp_cursors();
 
-        for char in self.text.chars().rev().skip(self.text.len() - self.cursor) {
-            if char == '\n' {
-                break;
-            } else {
-                cursor -= 1;
-            }
-        }
+        let line_start = self.text[..self.cursor]
+            .rfind('\n')
+            .map_or(0, |newline| newline + 1);
 
-        self.cursor = cursor;
+        let indent = self.text[line_start..]
+            .chars()
+            .take_while(|&char| char != '\n' && char.is_whitespace())
+            .count();
+
+        let code_start = line_start + indent;
+
+        self.cursor = if self.cursor == code_start {
+            line_start
+        } else {
+            code_start
+        };
 
         if !select {
             self.deselect();
@@ -300,13 +1028,15 @@ impl ExecNode {
     }
 
     fn end(&mut self, select: bool) {
+        self.clamp_cursors();
+
         let mut cursor = self.cursor;
 
-        for char in self.text.chars().skip(self.cursor) {
+        for char in self.text[self.cursor..].chars() {
             if char == '\n' {
                 break;
             } else {
-                cursor += 1;
+                cursor += char.len_utf8();
             }
         }
 
@@ -317,16 +1047,184 @@ impl ExecNode {
         }
     }
 
-    fn deselect(&mut self) {
-        self.select_cursor = self.cursor;
-    }
+    /// moves to the start of the current or previous word
+    fn word_left(&mut self, select: bool) {
+        self.clamp_cursors();
 
-    fn update_error(&mut self) {
-        self.error = if let Err(parse_err) = parse_node_text(&self.text) {
-            Some(parse_err)
-        } else {
-            None
+        let before_cursor = &self.text[..self.cursor];
+
+        let word_end = before_cursor.trim_end_matches(|char| !is_word_char(char));
+
+        self.cursor = word_end.trim_end_matches(is_word_char).len();
+
+        if !select {
+            self.deselect();
+        }
+    }
+
+    /// moves to the end of the current or next word
+    fn word_right(&mut self, select: bool) {
+        self.clamp_cursors();
+
+        let after_cursor = &self.text[self.cursor..];
+
+        let word_start = after_cursor.trim_start_matches(|char| !is_word_char(char));
+
+        self.cursor = self.text.len() - word_start.trim_start_matches(is_word_char).len();
+
+        if !select {
+            self.deselect();
+        }
+    }
+
+    fn deselect(&mut self) {
+        self.select_cursor = self.cursor;
+    }
+
+    fn reparse(&mut self) {
+        self.parsed = parse_node_text(&self.text);
+
+        self.warnings = match &self.parsed {
+            Ok(code) => lint(code),
+            Err(_) => Vec::new(),
+        };
+    }
+
+    /// literally replaces every occurrence of `find`, unless the result wouldn't fit in the node
+    fn replace_all(&mut self, find: &str, replacement: &str, layout: &Layout) {
+        if find.is_empty() {
+            return;
+        }
+
+        let Ok(new_text) = NodeText::from(&self.text.replace(find, replacement)) else {
+            self.overflow = Some(TextOverflow::LineTooLong);
+            return;
+        };
+
+        if self.try_set_text(new_text, layout) {
+            self.cursor = self.cursor.min(self.text.len());
+            self.deselect();
+        }
+    }
+
+    /// comments out every line touched by the selection,
+    /// or uncomments them if they're all commented already
+    fn toggle_comment(&mut self, layout: &Layout) {
+        let (select_start, select_end) = self.selection_range();
+        let (start_line, _) = line_column(&self.text, select_start);
+        let (end_line, _) = line_column(&self.text, select_end);
+
+        let uncommenting = self
+            .text
+            .split('\n')
+            .skip(start_line)
+            .take(end_line - start_line + 1)
+            // blank lines are left as they are either way
+            .filter(|line| !line.trim().is_empty())
+            .all(|line| line.starts_with('#'));
+
+        self.edit_touched_lines(layout, |line| {
+            if line.trim().is_empty() {
+                line.to_string()
+            } else if uncommenting {
+                line.strip_prefix("# ")
+                    .or_else(|| line.strip_prefix('#'))
+                    .unwrap_or(line)
+                    .to_string()
+            } else {
+                format!("# {line}")
+            }
+        });
+    }
+
+    /// indents every line touched by a selection that spans lines.
+    /// otherwise spaces are inserted up to the next multiple of the indent width
+    fn indent(&mut self, layout: &Layout) {
+        self.clamp_cursors();
+
+        let (select_start, select_end) = self.selection_range();
+        let (start_line, column) = line_column(&self.text, select_start);
+        let (end_line, _) = line_column(&self.text, select_end);
+
+        if start_line != end_line {
+            self.edit_touched_lines(layout, |line| format!("{INDENT}{line}"));
+        } else {
+            let width = INDENT.len() - column % INDENT.len();
+
+            self.insert(&" ".repeat(width), layout);
+        }
+    }
+
+    /// takes up to one indent's worth of leading spaces off every line touched by the selection
+    fn dedent(&mut self, layout: &Layout) {
+        self.clamp_cursors();
+
+        self.edit_touched_lines(layout, |line| {
+            let spaces = line
+                .bytes()
+                .take(INDENT.len())
+                .take_while(|&byte| byte == b' ')
+                .count();
+
+            line[spaces..].to_string()
+        });
+    }
+
+    /// replaces every line touched by the selection with what `edit` makes of it,
+    /// unless the result wouldn't fit
+    fn edit_touched_lines(&mut self, layout: &Layout, mut edit: impl FnMut(&str) -> String) {
+        let (select_start, select_end) = self.selection_range();
+        let (start_line, _) = line_column(&self.text, select_start);
+        let (end_line, _) = line_column(&self.text, select_end);
+
+        let touched = start_line..=end_line;
+
+        let mut new_text = NodeText::new();
+        // how many characters each touched line gained (or lost)
+        let mut shifts = Vec::new();
+
+        for (i, line) in self.text.split('\n').enumerate() {
+            if i > 0 && new_text.try_push('\n').is_err() {
+                return;
+            }
+
+            let new_line = if touched.contains(&i) {
+                let new_line = edit(line);
+
+                shifts.push(new_line.len() as isize - line.len() as isize);
+                new_line
+            } else {
+                line.to_string()
+            };
+
+            if new_text.try_push_str(&new_line).is_err() {
+                return;
+            }
+        }
+
+        if let Err(overflow) = validate(&new_text, &self.room(layout)) {
+            self.overflow = Some(overflow);
+            return;
         }
+
+        // keeps both ends of the selection on the same lines, next to the same code
+        let shifted = |index: usize| {
+            let (line, column) = line_column(&self.text, index);
+            let shift = shifts[line - start_line];
+
+            if column == 0 {
+                (line, 0)
+            } else {
+                (line, column.saturating_add_signed(shift))
+            }
+        };
+
+        let cursor = shifted(self.cursor);
+        let select_cursor = shifted(self.select_cursor);
+
+        self.set_text(new_text);
+        self.cursor = self.target(cursor.0, cursor.1);
+        self.select_cursor = self.target(select_cursor.0, select_cursor.1);
     }
 
     fn select_all(&mut self) {
@@ -335,11 +1233,49 @@ impl ExecNode {
     }
 }
 
-fn validate(node_text: &NodeText) -> bool {
-    node_text
+/// as much of `text` as there is in `columns`, which count chars rather than bytes
+fn char_range(text: &str, columns: Range<usize>) -> &str {
+    let byte = |column| {
+        text.char_indices()
+            .nth(column)
+            .map_or(text.len(), |(index, _)| index)
+    };
+
+    &text[byte(columns.start)..byte(columns.end)]
+}
+
+/// words are runs of letters and digits, anything else (whitespace, `#`, `:`, ...) separates them
+fn is_word_char(char: char) -> bool {
+    char.is_ascii_alphanumeric()
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum TextOverflow {
+    TooManyLines,
+    LineTooLong,
+}
+
+impl TextOverflow {
+    fn to_str(self) -> &'static str {
+        match self {
+            TextOverflow::TooManyLines => "TOO MANY LINES",
+            TextOverflow::LineTooLong => "LINE TOO LONG",
+        }
+    }
+}
+
+fn validate(node_text: &str, layout: &Layout) -> Result<(), TextOverflow> {
+    if node_text.split('\n').count() > layout.lines {
+        Err(TextOverflow::TooManyLines)
+    } else if node_text
         .split('\n')
-        .all(|line| line.len() <= NODE_LINE_LENGTH)
-        && node_text.split('\n').count() <= NODE_LINES
+        // counting bytes would make non-ASCII lines look longer than they're drawn
+        .any(|line| line.chars().count() > layout.line_length)
+    {
+        Err(TextOverflow::LineTooLong)
+    } else {
+        Ok(())
+    }
 }
 
 fn line_column(str: &str, index: usize) -> (usize, usize) {
@@ -348,7 +1284,8 @@ fn line_column(str: &str, index: usize) -> (usize, usize) {
     let mut line = 0;
     let mut column = 0;
 
-    for char in str.chars().take(index) {
+    // `index` is in bytes, while the column is in chars, the way the text is drawn
+    for (_, char) in str.char_indices().take_while(|&(i, _)| i < index) {
         if char == '\n' {
             line += 1;
             column = 0;
@@ -360,22 +1297,257 @@ fn line_column(str: &str, index: usize) -> (usize, usize) {
     (line, column)
 }
 
+/// formats numbers as a comma-separated list, like `1, 3, 2`
+fn fmt_nums(nums: &[Num]) -> String {
+    nums.iter()
+        .map(Num::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 const INPUT_NODE_CAP: usize = 39;
 
 #[derive(Clone, Debug)]
 struct InputNode {
+    name: Option<NodeName>,
     data: ArrayVec<Num, INPUT_NODE_CAP>,
     index: Option<usize>,
+    /// the side the values come out of, only the neighbor there can read them
+    dir: Dir,
 }
 
 impl InputNode {
     fn with_data(data: ArrayVec<Num, INPUT_NODE_CAP>) -> InputNode {
-        InputNode { data, index: None }
+        InputNode {
+            name: None,
+            data,
+            index: None,
+            dir: Dir::Down,
+        }
     }
 
     fn current(&self) -> Option<Num> {
         self.data.get(self.index?).copied()
     }
+
+    /// hands out the current value and moves on to the next one,
+    /// starting from the first if the node hasn't been started yet.
+    /// once the data runs out, the reader is left blocked
+    fn read(&mut self) -> Option<Num> {
+        let index = self.index.get_or_insert(0);
+        let num = self.data.get(*index).copied()?;
+
+        *index += 1;
+
+        Some(num)
+    }
+
+    /// returns whether the node was running
+    fn stop(&mut self) -> bool {
+        self.index.take().is_some()
+    }
+
+    fn data(&self) -> &[Num] {
+        &self.data
+    }
+
+    /// reordering the data starts it over, since the current value could be anywhere now
+    fn sort(&mut self, ascending: bool) {
+        if ascending {
+            self.data.sort();
+        } else {
+            self.data.sort_by(|a, b| b.cmp(a));
+        }
+
+        self.index = None;
+    }
+
+    fn reverse(&mut self) {
+        self.data.reverse();
+        self.index = None;
+    }
+
+    /// the inverse of `fmt_nums`, though newlines are accepted as separators too
+    fn from_data_str(str: &str) -> Option<Self> {
+        let data = str
+            .split([',', '\n'])
+            .map(str::trim)
+            .filter(|num| !num.is_empty())
+            .map(|num| num.parse().ok())
+            .collect::<Option<Vec<Num>>>()?;
+
+        if data.is_empty() {
+            return None;
+        }
+
+        Some(Self::with_data(ArrayVec::try_from(data.as_slice()).ok()?))
+    }
+}
+
+const OUTPUT_NODE_CAP: usize = 39;
+
+/// receives values from the node above it
+#[derive(Clone, Debug)]
+struct OutputNode {
+    name: Option<NodeName>,
+    received: Option<ArrayVec<Num, OUTPUT_NODE_CAP>>,
+    /// what the node should receive, if it's being graded
+    expected: ArrayVec<Num, OUTPUT_NODE_CAP>,
+}
+
+impl OutputNode {
+    fn empty() -> Self {
+        OutputNode {
+            name: None,
+            received: None,
+            expected: ArrayVec::new(),
+        }
+    }
+
+    fn received(&self) -> &[Num] {
+        self.received.as_deref().unwrap_or_default()
+    }
+
+    /// whether each value received so far is the one that was expected
+    fn matches(&self) -> impl Iterator<Item = bool> {
+        self.received()
+            .iter()
+            .enumerate()
+            .map(|(i, received)| self.expected.get(i) == Some(received))
+    }
+
+    fn check(&self) -> OutputCheck {
+        OutputCheck {
+            first_mismatch: self.matches().position(|matched| !matched),
+            complete: self.received().len() >= self.expected.len(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct OutputCheck {
+    first_mismatch: Option<usize>,
+    /// whether every expected value has been received, right or wrong
+    complete: bool,
+}
+
+impl OutputCheck {
+    fn passed(&self) -> bool {
+        self.complete && self.first_mismatch.is_none()
+    }
+}
+
+/// the check of every output node that has expected values, ordered by coordinate
+#[derive(Debug)]
+struct VerifyResult {
+    checks: Vec<(NodeCoord, OutputCheck)>,
+}
+
+impl VerifyResult {
+    fn passed(&self) -> bool {
+        self.checks.iter().all(|(_, check)| check.passed())
+    }
+}
+
+fn verify(nodes: &Nodes) -> VerifyResult {
+    let mut checks: Vec<_> = nodes
+        .iter()
+        .filter_map(|(node_loc, node)| match node {
+            Node::Output(output_node) if !output_node.expected.is_empty() => {
+                Some((*node_loc, output_node.check()))
+            }
+            Node::Exec(_) | Node::Input(_) | Node::Output(_) | Node::Random(_) => None,
+        })
+        .collect();
+
+    checks.sort_by_key(|&(node_loc, _)| node_loc);
+
+    VerifyResult { checks }
+}
+
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+struct NetworkStats {
+    exec_nodes: usize,
+    /// only counted for nodes whose code parses
+    instructions: usize,
+    input_nodes: usize,
+    output_nodes: usize,
+}
+
+/// the running exec node that has executed the most instructions, and how many.
+/// ties go to the first in reading order
+fn busiest_node(nodes: &Nodes) -> Option<(NodeCoord, u32)> {
+    nodes
+        .iter()
+        .filter_map(|(node_loc, node)| match node {
+            Node::Exec(exec_node) => Some((*node_loc, exec_node.steps()?)),
+            Node::Input(_) | Node::Output(_) | Node::Random(_) => None,
+        })
+        .min_by_key(|&(node_loc, steps)| (Reverse(steps), node_loc))
+}
+
+fn network_stats(nodes: &Nodes) -> NetworkStats {
+    let mut stats = NetworkStats::default();
+
+    for node in nodes.values() {
+        match node {
+            Node::Exec(exec_node) => {
+                stats.exec_nodes += 1;
+                stats.instructions += exec_node.instruction_count();
+            }
+            // random nodes are inputs too, just endless ones
+            Node::Input(_) | Node::Random(_) => stats.input_nodes += 1,
+            Node::Output(_) => stats.output_nodes += 1,
+        }
+    }
+
+    stats
+}
+
+/// an endless input node, sending pseudo-random values from 0 to 99.
+/// the same seed always gives the same sequence, so solutions can be tested against it
+#[derive(Clone, Debug)]
+struct RandomNode {
+    name: Option<NodeName>,
+    seed: u32,
+    /// the state of the generator, while running
+    state: Option<u32>,
+}
+
+impl RandomNode {
+    fn with_seed(seed: u32) -> Self {
+        RandomNode {
+            name: None,
+            seed,
+            state: None,
+        }
+    }
+
+    fn start(&mut self) {
+        // xorshift gets stuck on zero, so that seed is swapped out for another
+        let seed = if self.seed == 0 {
+            0x9E37_79B9
+        } else {
+            self.seed
+        };
+
+        self.state = Some(xorshift32(seed));
+    }
+
+    fn current(&self) -> Option<Num> {
+        Some(Num::from((self.state? % 100) as i8))
+    }
+
+    fn advance(&mut self) {
+        self.state = self.state.map(xorshift32);
+    }
+}
+
+fn xorshift32(mut state: u32) -> u32 {
+    state ^= state << 13;
+    state ^= state >> 17;
+    state ^= state << 5;
+    state
 }
 
 #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
@@ -384,72 +1556,93 @@ struct NodeCoord {
     y: isize,
 }
 
+/// reading order: top to bottom, then left to right
+impl Ord for NodeCoord {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.y, self.x).cmp(&(other.y, other.x))
+    }
+}
+
+impl PartialOrd for NodeCoord {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl NodeCoord {
     fn at(x: isize, y: isize) -> Self {
         Self { x, y }
     }
 
-    fn top_left_corner(&self) -> Vector2 {
+    /// the node whose cell, including the padding around it, contains `pos`
+    fn at_world_pos(pos: Vector2, layout: &Layout) -> Self {
+        let cell_size = layout.cell_size();
+
+        Self::at(
+            (pos.x / cell_size).floor() as isize,
+            (pos.y / cell_size).floor() as isize,
+        )
+    }
+
+    fn top_left_corner(&self, layout: &Layout) -> Vector2 {
         Vector2 {
             x: self.x as f32,
             y: self.y as f32,
         }
-        .scale_by(NODE_OUTSIDE_SIDE_LENGTH + NODE_OUTSIDE_PADDING)
+        .scale_by(layout.cell_size())
     }
 
-    fn top_right_corner(&self) -> Vector2 {
-        self.top_left_corner()
+    fn top_right_corner(&self, layout: &Layout) -> Vector2 {
+        self.top_left_corner(layout)
             + Vector2 {
-                x: NODE_OUTSIDE_SIDE_LENGTH,
+                x: layout.outside_side_length(),
                 y: 0.,
             }
     }
 
-    fn bottom_left_corner(&self) -> Vector2 {
-        self.top_left_corner()
+    fn bottom_left_corner(&self, layout: &Layout) -> Vector2 {
+        self.top_left_corner(layout)
             + Vector2 {
                 x: 0.,
-                y: NODE_OUTSIDE_SIDE_LENGTH,
+                y: layout.outside_side_length(),
             }
     }
 
-    fn bottom_right_corner(&self) -> Vector2 {
-        self.top_left_corner()
+    fn bottom_right_corner(&self, layout: &Layout) -> Vector2 {
+        self.top_left_corner(layout)
             + Vector2 {
-                x: NODE_OUTSIDE_SIDE_LENGTH,
-                y: NODE_OUTSIDE_SIDE_LENGTH,
+                x: layout.outside_side_length(),
+                y: layout.outside_side_length(),
             }
     }
 
-    fn text_loc(&self) -> Vector2 {
-        self.top_left_corner() + Vector2::one().scale_by(NODE_INSIDE_PADDING)
+    fn text_loc(&self, layout: &Layout) -> Vector2 {
+        self.top_left_corner(layout) + Vector2::one().scale_by(NODE_INSIDE_PADDING)
     }
 
-    fn line_pos(&self, line_number: usize) -> Vector2 {
-        self.text_loc() + Vector2::new(0., line_number as f32 * NODE_LINE_HEIGHT)
+    fn line_pos(&self, line_number: usize, layout: &Layout) -> Vector2 {
+        self.text_loc(layout) + Vector2::new(0., line_number as f32 * NODE_LINE_HEIGHT)
     }
 
-    fn char_pos(&self, line: usize, column: usize) -> Vector2 {
-        self.text_loc()
+    fn char_pos(&self, line: usize, column: usize, layout: &Layout) -> Vector2 {
+        self.text_loc(layout)
             + Vector2::new(
                 column as f32 * NODE_CHAR_WIDTH,
                 line as f32 * NODE_LINE_HEIGHT,
             )
     }
 
-    fn center(&self) -> Vector2 {
-        self.top_left_corner() + Vector2::one().scale_by(NODE_OUTSIDE_SIDE_LENGTH / 2.)
+    fn center(&self, layout: &Layout) -> Vector2 {
+        self.top_left_corner(layout) + Vector2::one().scale_by(layout.outside_side_length() / 2.)
     }
 
-    fn io_indicator(&self, dir: Dir) -> Vector2 {
-        self.center()
-            + dir
-                .normalized()
-                .scale_by((NODE_OUTSIDE_SIDE_LENGTH + NODE_OUTSIDE_PADDING) / 2.0)
+    fn io_indicator(&self, dir: Dir, layout: &Layout) -> Vector2 {
+        self.center(layout)
+            + dir.normalized().scale_by(layout.cell_size() / 2.0)
             + dir
                 .rotate_right()
                 .normalized()
-                .scale_by(NODE_OUTSIDE_SIDE_LENGTH / 4.0)
+                .scale_by(layout.outside_side_length() / 4.0)
     }
 
     fn neighbor(self, direction: Dir) -> Self {
@@ -462,37 +1655,171 @@ impl NodeCoord {
             Dir::Right => NodeCoord { x: x + 1, y },
         }
     }
+
+    /// how many arrow presses it takes to get from one to the other
+    fn distance(self, other: NodeCoord) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// the neighbor nodes send to and read from. that's just `neighbor`,
+    /// unless going off the edge of a torus brings it back in on the opposite side.
+    /// none when stepping into a torus through one of its wrapped sides,
+    /// since the cell on that edge is connected to the opposite edge instead
+    fn io_neighbor(self, direction: Dir, torus: Option<Torus>) -> Option<Self> {
+        let neighbor = self.neighbor(direction);
+
+        match torus {
+            Some(torus) if torus.contains(self) => Some(torus.wrap(neighbor)),
+            Some(torus) if torus.contains(neighbor) => None,
+            _ => Some(neighbor),
+        }
+    }
+}
+
+/// the cells a puzzle's network wraps around in, corners included:
+/// the top row's UP neighbor is the bottom row, and the left column's LEFT is the right column.
+/// nodes outside of it don't wrap, and can't reach the cells on its edges either
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Torus {
+    min: NodeCoord,
+    max: NodeCoord,
+}
+
+impl Torus {
+    /// the box with opposite corners at `a` and `b`, whichever way round they are
+    fn spanning(a: NodeCoord, b: NodeCoord) -> Torus {
+        Torus {
+            min: NodeCoord::at(a.x.min(b.x), a.y.min(b.y)),
+            max: NodeCoord::at(a.x.max(b.x), a.y.max(b.y)),
+        }
+    }
+
+    fn contains(self, node_loc: NodeCoord) -> bool {
+        (self.min.x..=self.max.x).contains(&node_loc.x)
+            && (self.min.y..=self.max.y).contains(&node_loc.y)
+    }
+
+    /// brings a cell just past one edge back in at the opposite one
+    fn wrap(self, node_loc: NodeCoord) -> NodeCoord {
+        let wrap_axis = |value: isize, min: isize, max: isize| {
+            if value < min {
+                max
+            } else if value > max {
+                min
+            } else {
+                value
+            }
+        };
+
+        NodeCoord::at(
+            wrap_axis(node_loc.x, self.min.x, self.max.x),
+            wrap_axis(node_loc.y, self.min.y, self.max.y),
+        )
+    }
 }
 
 fn main() {
+    // `--check path.toml` reports what doesn't parse without ever opening a window, for CI
+    if let Some(path) = std::env::args().skip_while(|arg| arg != "--check").nth(1) {
+        std::process::exit(check(&path));
+    }
+
+    // `--bench` times the scheduler on a few fixed networks, also without a window
+    if std::env::args().any(|arg| arg == "--bench") {
+        bench();
+        return;
+    }
+
+    // raylib adds this to the flags the builder sets, so it has to come first.
+    // the framebuffer then gets the monitor's physical resolution,
+    // while everything (including `LINE_THICKNESS`) is still laid out in logical pixels
+    unsafe {
+        raylib::ffi::SetConfigFlags(raylib::ffi::ConfigFlags::FLAG_WINDOW_HIGHDPI as u32);
+    }
+
     let (mut rl, thread) = raylib::init().resizable().title("TIS-NET").build();
 
+    // glyphs are rasterized at twice the node font size, times the content scale,
+    // so the bilinear filter only ever shrinks them
+    let dpi_scale = rl.get_window_scale_dpi().x.max(1.0);
+
     rl.set_target_fps(60);
     rl.set_text_line_spacing(NODE_LINE_HEIGHT as _);
     rl.set_exit_key(None);
 
-    let font = rl
-        .load_font_from_memory(
-            &thread,
-            ".ttf",
-            include_bytes!("RobotoMono-Medium.ttf"),
-            40,
-            None,
-        )
-        .unwrap();
+    let font_load_size = (2.0 * NODE_FONT_SIZE * dpi_scale).round() as i32;
+
+    // `--font path.ttf` swaps the embedded font out. it has to be monospace,
+    // since the cursor and selection are placed with the fixed `NODE_CHAR_WIDTH`
+    let custom_font = std::env::args()
+        .skip_while(|arg| arg != "--font")
+        .nth(1)
+        .and_then(
+            |path| match rl.load_font_ex(&thread, &path, font_load_size, None) {
+                Ok(font) => Some(font),
+                Err(err) => {
+                    println!("couldn't load font {path:?}, using the default: {err}");
+                    None
+                }
+            },
+        );
+
+    let font = match custom_font {
+        Some(font) => font,
+        None => rl
+            .load_font_from_memory(
+                &thread,
+                ".ttf",
+                include_bytes!("RobotoMono-Medium.ttf"),
+                font_load_size,
+                None,
+            )
+            .unwrap(),
+    };
 
     font.texture()
         .set_texture_filter(&thread, TextureFilter::TEXTURE_FILTER_BILINEAR);
 
-    let mut state = init();
+    let theme = if std::env::args().any(|arg| arg == "--high-contrast") {
+        Theme::high_contrast()
+    } else {
+        Theme::default()
+    };
+
+    let theme = Theme {
+        error_marks: std::env::args().any(|arg| arg == "--error-marks"),
+        ..theme
+    };
+
+    let mut state = State {
+        keymap: Keymap::load(),
+        theme,
+        layout: Layout::from_args(),
+        camera_follow: camera_follow_from_args(),
+        key_repeat: KeyRepeat::from_args(),
+        ..init()
+    };
+
+    state.exec_template = template_from_args(&state.layout);
+
+    // a workspace that's only being shown off has no unsaved changes to recover
+    state.model = match std::env::args().skip_while(|arg| arg != "--view").nth(1) {
+        Some(path) => view(state.model, &path),
+        None => recover(state.model),
+    };
+
     let mut repeat_key = RepeatKey::None;
+    let mut drag_start = None;
+    let mut title = String::new();
+    let mut last_autosave = rl.get_time();
+    let mut recovery_written = recovery_path().exists();
 
     loop {
         if rl.window_should_close() {
             break;
         }
 
-        let input = get_input(&mut rl, &mut repeat_key);
+        let input = get_input(&mut rl, &mut repeat_key, state.key_repeat, &mut drag_start);
 
         let output;
         (state, output) = match update(state, input) {
@@ -505,782 +1832,2247 @@ fn main() {
                 .expect("this shouldn't be possible");
         }
 
-        render(&mut rl, &thread, &state, &font);
-    }
-}
+        let new_title = window_title(&state.model);
 
-fn init() -> State {
-    let camera = Camera2D {
-        offset: Default::default(),
-        target: Default::default(),
-        rotation: Default::default(),
-        zoom: 0.85,
-    };
+        if new_title != title {
+            rl.set_window_title(&thread, &new_title);
+            title = new_title;
+        }
 
-    let (nodes, highlighted_node) = parse_toml(include_str!("default.toml")).unwrap();
+        if rl.get_time() - last_autosave >= AUTOSAVE_INTERVAL_S {
+            last_autosave = rl.get_time();
 
-    State {
-        camera,
-        model: Model {
-            nodes,
-            highlighted_node,
-            ghosts: Ghosts::None,
-            node_clipboard: None,
-        },
-    }
-}
-
-fn render(rl: &mut RaylibHandle, thread: &RaylibThread, state: &State, font: &Font) {
-    let mut d = rl.begin_drawing(&thread);
-    let mut d = d.begin_mode2D(state.camera);
-    let d = &mut d;
-
-    let model = &state.model;
-
-    d.clear_background(Color::BLACK);
-
-    render_nodes(d, model, font);
-
-    render_ghosts(d, model);
-
-    let highlighted = model.nodes.get(&model.highlighted_node);
+            // a half-executed network isn't worth recovering
+            if state.model.dirty && !is_running(&state.model.nodes) {
+                let toml = recovery_toml(&state.model);
 
-    match highlighted {
-        Some(Node::Exec(exec_node)) => {
-            if exec_node.is_in_edit_mode() {
-                render_cursor(d, model.highlighted_node, exec_node);
+                match std::fs::write(recovery_path(), toml) {
+                    Ok(()) => recovery_written = true,
+                    Err(err) => println!("io error while autosaving: {:?}", err),
+                }
             }
         }
 
-        Some(Node::Input(_)) => {}
+        // everything made it into a real save, so there's nothing left to recover
+        if recovery_written && !state.model.dirty {
+            let _ = std::fs::remove_file(recovery_path());
+            recovery_written = false;
+        }
 
-        None => {
-            render_dashed_node_border(d, model.highlighted_node, Color::GRAY);
+        render(&mut rl, &thread, &state, &font);
+
+        if let Some(path) = output.screenshot {
+            let screen = rl.load_image_from_screen(&thread);
 
-            render_plus(d, model.highlighted_node.center(), Color::GRAY);
+            if !screen.export_image(&path.to_string_lossy()) {
+                println!("couldn't save screenshot to {path:?}");
+            }
         }
     }
 }
 
-fn render_ghosts(d: &mut impl RaylibDraw, model: &Model) {
-    match model.ghosts {
-        Ghosts::MoveView => {
-            for dir in Dir::ALL {
-                let neighbor_loc = model.highlighted_node.neighbor(dir);
-                if !model.nodes.contains_key(&neighbor_loc) {
-                    render_dashed_node_border(d, neighbor_loc, GHOST_COLOR);
+const AUTOSAVE_INTERVAL_S: f64 = 30.0;
 
-                    render_arrow(d, neighbor_loc.center(), dir, GHOST_COLOR);
-                }
-            }
-        }
+fn recovery_path() -> PathBuf {
+    std::env::temp_dir().join("tis-net-recovery.toml")
+}
 
-        Ghosts::MoveNode => {
-            for dir in Dir::ALL {
-                let neighbor_loc = model.highlighted_node.neighbor(dir);
-                if !model.nodes.contains_key(&neighbor_loc) {
-                    render_dashed_node_border(d, neighbor_loc, GHOST_COLOR);
+/// starts the first line of an autosave whose workspace had been saved somewhere,
+/// followed by that path. it's a comment, so the rest still parses as usual
+const RECOVERY_SOURCE_PREFIX: &str = "# unsaved changes to ";
 
-                    render_double_arrow(d, neighbor_loc.center(), dir, GHOST_COLOR);
-                }
-            }
-        }
+fn recovery_toml(model: &Model) -> String {
+    let toml = serialize_toml(
+        &model.nodes,
+        Some(model.highlighted_node),
+        &model.locked,
+        model.torus,
+    );
 
-        Ghosts::None => {}
+    match &model.current_path {
+        Some(path) => format!("{RECOVERY_SOURCE_PREFIX}{}\n{toml}", path.display()),
+        None => toml,
     }
 }
 
-fn render_nodes(d: &mut impl RaylibDraw, model: &Model, font: &Font) {
-    for (node_loc, node) in model.nodes.iter() {
-        let line_color = if node_loc == &model.highlighted_node {
-            Color::WHITE
-        } else {
-            Color::GRAY
-        };
-
-        match node {
-            Node::Exec(exec_node) => {
-                render_node_border(d, *node_loc, line_color);
+/// the file an autosave was made from, if it was made from one
+fn recovery_source(toml: &str) -> Option<PathBuf> {
+    let (first_line, _) = toml.split_once('\n')?;
 
-                render_node_gizmos(d, *node_loc, &exec_node.exec, font, line_color, Color::GRAY);
+    first_line
+        .strip_prefix(RECOVERY_SOURCE_PREFIX)
+        .map(PathBuf::from)
+}
 
-                render_node_text(d, exec_node, node_loc, font);
+/// whether `source` was saved after the autosave of it was written,
+/// which makes the autosave out of date
+fn saved_since(source: &Path, recovery: &Path) -> bool {
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|metadata| metadata.modified());
 
-                // the below two things should not be true at the same time if I did my homework
-                // (because a node with an error should not be able to begin executing)
-                // but this isn't reflected in the type system. If it were to happen though, it means there's a bug
-                debug_assert!(!(exec_node.error.is_some() && exec_node.exec.is_some()));
+    match (modified(source), modified(recovery)) {
+        (Ok(source), Ok(recovery)) => source > recovery,
+        _ => false,
+    }
+}
 
-                if let Some(error) = &exec_node.error
-                    && show_error(node_loc, exec_node, &model.highlighted_node, error.line)
-                {
-                    render_error_squiggle(d, *node_loc, &exec_node.text, error.line);
-                }
+/// offers to restore the network that was autosaved before the app last closed with unsaved changes,
+/// unless the file it came from has been saved since
+fn recover(model: Model) -> Model {
+    let Ok(toml) = std::fs::read_to_string(recovery_path()) else {
+        return model;
+    };
 
-                if let Some(exec) = &exec_node.exec
-                    && !exec.code.is_empty()
-                {
-                    if let NodeIO::Outbound(dir, value) = exec.io {
-                        render_io_arrow(d, node_loc, dir, &value.to_string(), font);
-                    } else if let NodeIO::Inbound(io_dir) = exec.io
-                        && !neighbor_sending_io(&model.nodes, node_loc, io_dir)
-                    {
-                        render_io_arrow(d, &node_loc.neighbor(io_dir), io_dir.inverse(), "?", font);
-                    }
-                }
-            }
+    let source = recovery_source(&toml);
 
-            Node::Input(input_node) => {
-                render_node_border(d, *node_loc, line_color);
+    if source
+        .as_ref()
+        .is_some_and(|source| saved_since(source, &recovery_path()))
+    {
+        let _ = std::fs::remove_file(recovery_path());
+        return model;
+    }
 
-                let str;
-                let label = if let Some(i) = input_node.index {
-                    str = i.to_string();
-                    &str
-                } else {
-                    "INPUT NODE"
-                };
+    let Ok(puzzle) = parse_puzzle(&toml, source.as_deref().and_then(Path::parent)) else {
+        return model;
+    };
 
-                render_centered_text(d, label, node_loc.center(), font, Color::WHITE);
+    let answer = rfd::MessageDialog::new()
+        .set_title("Recover unsaved changes?")
+        .set_description("TIS-NET closed with unsaved changes. Do you want to restore them?")
+        .set_buttons(rfd::MessageButtons::YesNo)
+        .show();
 
-                if let Some(num) = input_node.current() {
-                    render_io_arrow(d, node_loc, Dir::Down, &num.to_string(), font);
-                }
-            }
-        }
+    if answer != rfd::MessageDialogResult::Yes {
+        let _ = std::fs::remove_file(recovery_path());
+        return model;
     }
 
-    // error boxes are rendered in a second pass because they need to be rendered over top of everything else
-    for (node_loc, node) in model.nodes.iter() {
-        if let Node::Exec(
-            exec_node @ ExecNode {
-                error: Some(error), ..
-            },
-        ) = &node
-            && show_error(node_loc, exec_node, &model.highlighted_node, error.line)
-        {
-            render_error_msg(d, node_loc, &error.problem, font);
-        };
+    Model {
+        nodes: puzzle.nodes,
+        highlighted_node: puzzle.highlighted_node,
+        locked: puzzle.locked,
+        torus: puzzle.torus,
+        dirty: true,
+        current_path: source,
+        ..model
     }
 }
 
-fn render_node_text(d: &mut impl RaylibDraw, node: &ExecNode, node_loc: &NodeCoord, font: &Font) {
-    let highlight = if let Some(ref exec) = node.exec
-        && let Some(instr) = exec.code.get(exec.ip as usize)
-    {
-        Highlight::Executing {
-            line: instr.src_line as usize,
-            blocked: !matches!(exec.io, NodeIO::None),
+/// opens a workspace that can be run but not edited, or exits if it can't be opened
+fn view(model: Model, path: &str) -> Model {
+    let puzzle = match std::fs::read_to_string(path) {
+        Ok(toml) => parse_puzzle(&toml, Path::new(path).parent()).unwrap_or_else(|import_err| {
+            println!("{path}: {}", import_err.to_str());
+            std::process::exit(1);
+        }),
+        Err(err) => {
+            println!("{path}: {err}");
+            std::process::exit(1);
         }
-    } else if node.text_selected() {
-        let (start, end) = node.selection_range();
+    };
 
-        let (start_line, start_col) = line_column(&node.text, start);
-        let (end_line, end_col) = line_column(&node.text, end);
+    Model {
+        nodes: puzzle.nodes,
+        highlighted_node: puzzle.highlighted_node,
+        locked: puzzle.locked,
+        torus: puzzle.torus,
+        read_only: true,
+        dirty: false,
+        current_path: Some(PathBuf::from(path)),
+        ..model
+    }
+}
 
-        Highlight::Selected {
-            start_line,
-            start_col,
-            end_line,
-            end_col,
-        }
-    } else {
-        Highlight::None
+/// reads the file given with `--template`, for new exec nodes to start out with.
+/// one that can't be read or doesn't fit in a node is left out, so new nodes start empty
+fn template_from_args(layout: &Layout) -> NodeText {
+    let Some(path) = std::env::args()
+        .skip_while(|arg| arg != "--template")
+        .nth(1)
+    else {
+        return NodeText::new();
     };
 
-    for (line_no, line_text) in node.text.split('\n').enumerate() {
-        let line_loc = node_loc.line_pos(line_no);
+    match std::fs::read_to_string(&path) {
+        Ok(text) => parse_template(&text, layout).unwrap_or_else(|overflow| {
+            println!(
+                "template {path:?} doesn't fit in a node, new nodes start empty: {}",
+                overflow.to_str()
+            );
+            NodeText::new()
+        }),
+        Err(err) => {
+            println!("couldn't read template {path:?}, new nodes start empty: {err}");
+            NodeText::new()
+        }
+    }
+}
 
-        match highlight {
-            Highlight::Executing { line, blocked } if line == line_no => {
-                let highlight_color = if blocked { Color::GRAY } else { Color::WHITE };
+/// the template has to fit in a node like any other text.
+/// it's uppercased like pasted text, and the newline at the end of the file isn't a line of its own
+fn parse_template(text: &str, layout: &Layout) -> Result<NodeText, TextOverflow> {
+    let text = text.strip_suffix('\n').unwrap_or(text).to_ascii_uppercase();
 
-                let highlight_pos = line_loc
-                    - Vector2 {
-                        x: NODE_INSIDE_PADDING * 0.25,
-                        y: 0.0,
-                    };
+    validate(&text, layout)?;
 
-                const HIGHLIGHT_SIZE: Vector2 = Vector2 {
-                    x: NODE_TEXT_BOX_INSIDE_WIDTH + NODE_INSIDE_PADDING * 0.5,
-                    y: NODE_LINE_HEIGHT,
-                };
+    NodeText::from(&text).map_err(|_| TextOverflow::LineTooLong)
+}
 
-                d.draw_rectangle_v(highlight_pos, HIGHLIGHT_SIZE, highlight_color);
+fn is_running(nodes: &Nodes) -> bool {
+    nodes.values().any(|node| match node {
+        Node::Exec(exec_node) => exec_node.exec.is_some(),
+        Node::Input(input_node) => input_node.index.is_some(),
+        Node::Output(output_node) => output_node.received.is_some(),
+        Node::Random(random_node) => random_node.state.is_some(),
+    })
+}
 
-                d.draw_text_ex(
-                    font,
-                    line_text,
-                    line_loc,
-                    NODE_FONT_SIZE,
-                    NODE_FONT_SPACING,
-                    Color::BLACK,
-                );
-            }
+/// like `TIS-NET — workspace.toml*`, where the `*` means there are unsaved changes
+fn window_title(model: &Model) -> String {
+    let file_name = model
+        .current_path
+        .as_ref()
+        .and_then(|path| path.file_name())
+        .map_or("untitled".into(), |file_name| file_name.to_string_lossy());
 
-            Highlight::Selected {
-                start_line,
-                start_col,
-                end_line,
-                end_col,
-            } if start_line <= line_no && line_no <= end_line => {
-                if let Some(comment_start) = line_text.find('#') {
-                    let char_offset = Vector2::new(NODE_CHAR_WIDTH, 0.0);
-                    let comment_offset = char_offset.scale_by(comment_start as f32);
+    let unsaved = if model.dirty { "*" } else { "" };
+    let read_only = if model.read_only { " (read only)" } else { "" };
 
-                    d.draw_text_ex(
-                        font,
-                        &line_text[..comment_start],
-                        line_loc,
-                        NODE_FONT_SIZE,
-                        NODE_FONT_SPACING,
-                        Color::WHITE,
-                    );
-                    d.draw_text_ex(
-                        font,
-                        &line_text[comment_start..],
-                        line_loc + comment_offset,
-                        NODE_FONT_SIZE,
-                        NODE_FONT_SPACING,
-                        Color::GRAY,
-                    );
-                } else {
-                    d.draw_text_ex(
-                        font,
-                        line_text,
-                        line_loc,
-                        NODE_FONT_SIZE,
-                        NODE_FONT_SPACING,
-                        Color::WHITE,
-                    );
-                }
+    format!("TIS-NET — {file_name}{unsaved}{read_only}")
+}
 
-                let selection_start = if start_line == line_no { start_col } else { 0 };
+fn init_camera() -> Camera2D {
+    Camera2D {
+        offset: Default::default(),
+        target: Default::default(),
+        rotation: Default::default(),
+        zoom: DEFAULT_ZOOM,
+    }
+}
 
-                let selection_end = if end_line == line_no {
-                    end_col
-                } else {
-                    line_text.len() + 1
-                };
+fn init() -> State {
+    let layout = Layout::default();
 
-                let selection_len = selection_end - selection_start;
+    let (nodes, highlighted_node) = parse_toml(include_str!("default.toml")).unwrap();
 
-                let select_highlight_pos = node_loc.char_pos(line_no, selection_start);
+    State {
+        camera: init_camera(),
+        model: Model {
+            nodes,
+            highlighted_node,
+            ..Model::empty()
+        },
+        keymap: Keymap::default(),
+        theme: Theme::default(),
+        layout,
+        camera_follow: DEFAULT_CAMERA_FOLLOW,
+        key_repeat: KeyRepeat::default(),
+        exec_template: NodeText::new(),
+        show_stats: false,
+        show_grid: true,
+    }
+}
 
-                let selection_box_size = Vector2 {
-                    x: selection_len as f32 * NODE_CHAR_WIDTH,
-                    y: NODE_LINE_HEIGHT,
-                };
+fn render(rl: &mut RaylibHandle, thread: &RaylibThread, state: &State, font: &Font) {
+    let window_dimensions = (rl.get_screen_width(), rl.get_screen_height());
 
-                d.draw_rectangle_v(select_highlight_pos, selection_box_size, Color::GRAY);
+    let mut d = rl.begin_drawing(&thread);
 
-                d.draw_text_ex(
-                    font,
-                    line_text,
-                    line_loc,
-                    NODE_FONT_SIZE,
-                    NODE_FONT_SPACING,
-                    Color::WHITE,
-                );
-            }
+    d.clear_background(state.theme.background);
 
-            Highlight::None | Highlight::Executing { .. } | Highlight::Selected { .. } => {
-                if let Some(comment_start) = line_text.find('#') {
-                    let char_offset = Vector2::new(NODE_CHAR_WIDTH, 0.0);
-                    let comment_offset = char_offset.scale_by(comment_start as f32);
+    {
+        let mut d = d.begin_mode2D(state.camera);
 
-                    d.draw_text_ex(
-                        font,
-                        &line_text[..comment_start],
-                        line_loc,
-                        NODE_FONT_SIZE,
-                        NODE_FONT_SPACING,
-                        Color::WHITE,
-                    );
-                    d.draw_text_ex(
-                        font,
-                        &line_text[comment_start..],
-                        line_loc + comment_offset,
-                        NODE_FONT_SIZE,
-                        NODE_FONT_SPACING,
-                        Color::GRAY,
-                    );
-                } else {
-                    d.draw_text_ex(
-                        font,
-                        line_text,
-                        line_loc,
-                        NODE_FONT_SIZE,
-                        NODE_FONT_SPACING,
-                        Color::WHITE,
-                    );
-                }
-            }
+        if state.show_grid {
+            render_grid(
+                &mut d,
+                state.camera,
+                window_dimensions,
+                &state.theme,
+                &state.layout,
+            );
         }
+
+        render_world(&mut d, state, font);
     }
-}
 
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
-enum Highlight {
-    None,
-    Executing {
-        line: usize,
-        blocked: bool,
-    },
-    Selected {
-        start_line: usize,
-        start_col: usize,
-        end_line: usize,
-        end_col: usize,
-    },
-}
+    // everything past this point is drawn in screen space, over top of the world
+    render_minimap(
+        &mut d,
+        &state.model,
+        state.camera,
+        window_dimensions,
+        &state.theme,
+        &state.layout,
+    );
 
-fn show_error(
-    node_loc: &NodeCoord,
-    node: &ExecNode,
-    highlighted_node: &NodeCoord,
-    error_line: u8,
-) -> bool {
-    let (select_start, select_end) = node.selection_range();
-    let select_start_line = line_column(&node.text, select_start).0;
-    let select_end_line = line_column(&node.text, select_end).0;
+    render_verify_status(&mut d, &state.model.nodes, font, &state.theme);
 
-    let error_line = error_line as usize;
+    render_cycle_count(
+        &mut d,
+        state.model.cycle,
+        state.model.watch_cycles,
+        window_dimensions,
+        font,
+        &state.theme,
+    );
 
-    let node_is_highlighted = node_loc == highlighted_node;
-    let cursor_at_error_line = select_start_line <= error_line && error_line <= select_end_line;
+    render_highlighted_coord(
+        &mut d,
+        &state.model.highlighted_node,
+        window_dimensions,
+        font,
+        &state.theme,
+    );
 
-    !node_is_highlighted || !cursor_at_error_line
+    if state.show_stats {
+        render_stats(
+            &mut d,
+            &state.model.nodes,
+            window_dimensions,
+            font,
+            &state.theme,
+        );
+    }
+
+    if state.model.show_io_log {
+        render_io_log(
+            &mut d,
+            &state.model.io_log,
+            state.model.io_log_scroll,
+            window_dimensions,
+            font,
+            &state.theme,
+        );
+    }
+
+    if state.model.show_help {
+        render_help(&mut d, &state.keymap, window_dimensions, font, &state.theme);
+    }
 }
 
-fn render_error_msg(
+/// lines through the middle of the gaps between nodes, so every cell gets a square of its own.
+/// only the visible ones are drawn, and they stay the same width on screen at any zoom
+fn render_grid(
     d: &mut impl RaylibDraw,
-    node_loc: &NodeCoord,
-    problem: &ParseProblem,
-    font: &Font,
+    camera: Camera2D,
+    window_dimensions: (i32, i32),
+    theme: &Theme,
+    layout: &Layout,
 ) {
-    const BOX_HEIGHT: f32 = NODE_LINE_HEIGHT + 2.0 * NODE_INSIDE_PADDING;
-
-    const BOX_NODE_PADDING: f32 = 0.25 * (NODE_OUTSIDE_PADDING - BOX_HEIGHT);
-
-    let bottom_left = node_loc.top_left_corner() - Vector2::new(0.0, BOX_NODE_PADDING);
-
-    let top_left = bottom_left - Vector2::new(0.0, BOX_HEIGHT);
-
-    let top_right = top_left + Vector2::new(NODE_OUTSIDE_SIDE_LENGTH, 0.0);
-    let bottom_right = bottom_left + Vector2::new(NODE_OUTSIDE_SIDE_LENGTH, 0.0);
-
-    let center = top_left + Vector2::new(0.5 * NODE_OUTSIDE_SIDE_LENGTH, 0.5 * BOX_HEIGHT);
+    let cell_size = layout.cell_size();
+    let offset = -NODE_OUTSIDE_PADDING / 2.0;
 
-    d.draw_rectangle_v(top_left, bottom_right - top_left, Color::BLACK);
-
-    d.draw_line_ex(top_left, top_right, LINE_THICKNESS, Color::RED);
-    d.draw_line_ex(top_left, bottom_left, LINE_THICKNESS, Color::RED);
-    d.draw_line_ex(bottom_left, bottom_right, LINE_THICKNESS, Color::RED);
-    d.draw_line_ex(top_right, bottom_right, LINE_THICKNESS, Color::RED);
+    let top_left = screen_to_world(Vector2::zero(), camera);
+    let bottom_right = screen_to_world(
+        Vector2::new(window_dimensions.0 as f32, window_dimensions.1 as f32),
+        camera,
+    );
 
-    render_centered_text(d, problem.to_str(), center, font, Color::RED);
-}
+    let thickness = 1.0 / camera.zoom;
 
-fn neighbor_sending_io(nodes: &Nodes, node_loc: &NodeCoord, io_dir: Dir) -> bool {
-    let Some(neighbor) = nodes.get(&node_loc.neighbor(io_dir)) else {
-        return false;
+    // the indices of the first and last line that can be seen, along one axis
+    let visible = |from: f32, to: f32| {
+        ((from - offset) / cell_size).ceil() as isize..=((to - offset) / cell_size).floor() as isize
     };
 
-    match neighbor {
-        Node::Exec(exec_node) => {
-            let Some(neighbor_exec) = &exec_node.exec else {
-                return false;
-            };
+    for i in visible(top_left.x, bottom_right.x) {
+        let x = i as f32 * cell_size + offset;
 
-            let NodeIO::Outbound(neighbor_io_dir, _) = neighbor_exec.io else {
-                return false;
-            };
+        d.draw_line_ex(
+            Vector2::new(x, top_left.y),
+            Vector2::new(x, bottom_right.y),
+            thickness,
+            theme.grid,
+        );
+    }
 
-            neighbor_io_dir == io_dir.inverse()
-        }
+    for i in visible(top_left.y, bottom_right.y) {
+        let y = i as f32 * cell_size + offset;
 
-        Node::Input(input_node) => io_dir == Dir::Up && input_node.current().is_some(),
+        d.draw_line_ex(
+            Vector2::new(top_left.x, y),
+            Vector2::new(bottom_right.x, y),
+            thickness,
+            theme.grid,
+        );
     }
 }
 
-fn render_node_gizmos(
+/// `watch_cycles` is how many of those cycles the last step until the highlighted node changed took
+fn render_cycle_count(
     d: &mut impl RaylibDraw,
-    node_loc: NodeCoord,
-    exec: &Option<NodeExec>,
+    cycle: u32,
+    watch_cycles: Option<u32>,
+    window_dimensions: (i32, i32),
     font: &Font,
-    primary: Color,
-    secondary: Color,
+    theme: &Theme,
 ) {
-    let (acc_string, bak_string);
-
-    let (acc, bak, mode) = if let Some(exec) = exec {
-        acc_string = exec.acc.to_string();
+    if cycle == 0 {
+        return;
+    }
 
-        bak_string = if exec.bak < -99 {
-            exec.bak.to_string()
-        } else {
-            format!("({})", exec.bak)
-        };
+    let pos = Vector2::new(
+        MINIMAP_MARGIN,
+        window_dimensions.1 as f32 - MINIMAP_MARGIN - NODE_LINE_HEIGHT,
+    );
 
-        let mode_str = match exec.io {
-            NodeIO::None => "EXEC",
-            NodeIO::Inbound(_) => "READ",
-            NodeIO::Outbound(_, _) => "WRTE",
-        };
+    d.draw_text_ex(
+        font,
+        &match watch_cycles {
+            Some(elapsed) => format!("CYCLE {cycle} (+{elapsed})"),
+            None => format!("CYCLE {cycle}"),
+        },
+        pos,
+        NODE_FONT_SIZE,
+        NODE_FONT_SPACING,
+        theme.dim_text,
+    );
+}
 
-        (acc_string.as_str(), bak_string.as_str(), mode_str)
-    } else {
-        ("0", "(0)", "EDIT")
-    };
+/// how many IO log entries fit on screen at once
+const IO_LOG_LINES: usize = 16;
+const IO_LOG_WIDTH: f32 = 40. * NODE_CHAR_WIDTH;
 
-    let placeholder_gizmos = [("ACC", acc), ("BAK", bak), ("LAST", "N/A"), ("MODE", mode)];
+/// along the left edge, just above the cycle count
+fn io_log_rect(window_dimensions: (i32, i32)) -> Rectangle {
+    let bottom = window_dimensions.1 as f32 - MINIMAP_MARGIN - 2. * NODE_LINE_HEIGHT;
+    let height = IO_LOG_LINES as f32 * NODE_LINE_HEIGHT;
 
-    for (i, (top, bottom)) in placeholder_gizmos.into_iter().enumerate() {
-        let gizmos_top_left =
-            node_loc.top_right_corner() - Vector2::new(GIZMO_WIDTH, i as f32 * -GIZMO_HEIGHT);
+    Rectangle::new(MINIMAP_MARGIN, bottom - height, IO_LOG_WIDTH, height)
+}
 
-        let left_right = Vector2::new(GIZMO_WIDTH, 0.0);
-        let top_down = Vector2::new(0.0, GIZMO_HEIGHT);
+/// `io_log_scroll` moved back by `lines` (forward if negative), without scrolling past either
+/// end of the log
+fn scroll_io_log(io_log_scroll: usize, lines: isize, io_log_len: usize) -> usize {
+    io_log_scroll
+        .saturating_add_signed(lines)
+        .min(io_log_len.saturating_sub(IO_LOG_LINES))
+}
 
-        // draws a rectangle out of individual lines
-        // doing this makes the lines centered, rather than aligned to the outside
-        d.draw_line_ex(
-            gizmos_top_left,
-            gizmos_top_left + left_right,
-            LINE_THICKNESS,
-            primary,
-        );
-        d.draw_line_ex(
-            gizmos_top_left,
-            gizmos_top_left + top_down,
-            LINE_THICKNESS,
-            primary,
-        );
-        d.draw_line_ex(
-            gizmos_top_left + left_right,
-            gizmos_top_left + left_right + top_down,
-            LINE_THICKNESS,
-            primary,
+fn fmt_transfer(cycle: u32, transfer: &Transfer) -> String {
+    format!(
+        "CYCLE {cycle}: ({}) -> ({}): {}",
+        fmt_coord(&transfer.from),
+        fmt_coord(&transfer.to),
+        transfer.value
+    )
+}
+
+/// newest entry at the bottom, unless it's been scrolled back
+fn render_io_log(
+    d: &mut impl RaylibDraw,
+    io_log: &VecDeque<(u32, Transfer)>,
+    io_log_scroll: usize,
+    window_dimensions: (i32, i32),
+    font: &Font,
+    theme: &Theme,
+) {
+    let rect = io_log_rect(window_dimensions);
+    let shown = io_log.len().min(IO_LOG_LINES);
+    let scroll = scroll_io_log(io_log_scroll, 0, io_log.len());
+    let skipped = io_log.len() - shown - scroll;
+
+    for (i, (cycle, transfer)) in io_log.iter().skip(skipped).take(shown).enumerate() {
+        let pos = Vector2::new(
+            rect.x,
+            rect.y + (IO_LOG_LINES - shown + i) as f32 * NODE_LINE_HEIGHT,
         );
-        d.draw_line_ex(
-            gizmos_top_left + top_down,
-            gizmos_top_left + top_down + left_right,
-            LINE_THICKNESS,
-            primary,
+
+        d.draw_text_ex(
+            font,
+            &fmt_transfer(*cycle, transfer),
+            pos,
+            NODE_FONT_SIZE,
+            NODE_FONT_SPACING,
+            theme.dim_text,
         );
+    }
+}
 
-        let text_center = gizmos_top_left + Vector2::new(GIZMO_WIDTH / 2., GIZMO_HEIGHT / 2.);
-        let text_offset = Vector2::new(0.0, NODE_LINE_HEIGHT / 2.0);
-        let top_text = text_center - text_offset;
-        let bottom_text = text_center + text_offset;
+/// in the top right corner, the other ones are taken
+fn render_highlighted_coord(
+    d: &mut impl RaylibDraw,
+    highlighted_node: &NodeCoord,
+    window_dimensions: (i32, i32),
+    font: &Font,
+    theme: &Theme,
+) {
+    let text = format!("({})", fmt_coord(highlighted_node));
+    let text_width = font
+        .measure_text(&text, NODE_FONT_SIZE, NODE_FONT_SPACING)
+        .x;
+
+    let pos = Vector2::new(
+        window_dimensions.0 as f32 - MINIMAP_MARGIN - text_width,
+        MINIMAP_MARGIN,
+    );
 
-        render_centered_text(d, top, top_text, font, secondary);
-        render_centered_text(d, bottom, bottom_text, font, Color::WHITE);
-    }
+    d.draw_text_ex(
+        font,
+        &text,
+        pos,
+        NODE_FONT_SIZE,
+        NODE_FONT_SPACING,
+        theme.dim_text,
+    );
 }
 
-fn render_cursor(d: &mut impl RaylibDraw, node_loc: NodeCoord, node: &ExecNode) {
-    let (line, column) = line_column(&node.text, node.cursor);
+/// right under the highlighted node's coordinate
+fn render_stats(
+    d: &mut impl RaylibDraw,
+    nodes: &Nodes,
+    window_dimensions: (i32, i32),
+    font: &Font,
+    theme: &Theme,
+) {
+    let stats = network_stats(nodes);
 
-    let x_offset = column as f32 * NODE_CHAR_WIDTH;
+    let mut lines = vec![
+        format!("EXEC NODES {}", stats.exec_nodes),
+        format!("INSTRUCTIONS {}", stats.instructions),
+        format!("INPUTS {}", stats.input_nodes),
+        format!("OUTPUTS {}", stats.output_nodes),
+    ];
 
-    let cursor_top = node_loc.line_pos(line) + Vector2::new(x_offset, 0.);
-    let cursor_bottom = cursor_top + Vector2::new(0., NODE_LINE_HEIGHT);
+    if let Some((node_loc, steps)) = busiest_node(nodes) {
+        lines.push(format!("BUSIEST {} {steps} STEPS", fmt_coord(&node_loc)));
+    }
+
+    for (i, line) in lines.iter().enumerate() {
+        let text_width = font.measure_text(line, NODE_FONT_SIZE, NODE_FONT_SPACING).x;
+
+        let pos = Vector2::new(
+            window_dimensions.0 as f32 - MINIMAP_MARGIN - text_width,
+            MINIMAP_MARGIN + (i + 2) as f32 * NODE_LINE_HEIGHT,
+        );
 
-    d.draw_line_ex(cursor_top, cursor_bottom, LINE_THICKNESS, Color::WHITE);
+        d.draw_text_ex(
+            font,
+            line,
+            pos,
+            NODE_FONT_SIZE,
+            NODE_FONT_SPACING,
+            theme.dim_text,
+        );
+    }
 }
 
-fn render_error_squiggle(
-    d: &mut impl RaylibDraw,
-    node_loc: NodeCoord,
-    node_text: &NodeText,
-    line_no: u8,
-) {
-    let Some(line_len) = node_text.lines().nth(line_no as usize).map(str::len) else {
+fn render_verify_status(d: &mut impl RaylibDraw, nodes: &Nodes, font: &Font, theme: &Theme) {
+    let result = verify(nodes);
+
+    if result.checks.is_empty() {
         return;
-    };
+    }
+
+    let (status, color) = if result.passed() {
+        ("ALL OUTPUTS CORRECT".to_string(), theme.text)
+    } else if let Some((node_loc, check)) = result
+        .checks
+        .iter()
+        .find(|(_, check)| check.first_mismatch.is_some())
+    {
+        let status = format!(
+            "OUTPUT {} WRONG AT #{}",
+            fmt_coord(node_loc),
+            check.first_mismatch.unwrap() + 1
+        );
 
-    let squiggle_start = node_loc.line_pos(line_no as usize) + Vector2::new(0.0, NODE_LINE_HEIGHT);
-    let squiggle_end = squiggle_start + Vector2::new(line_len as f32 * NODE_CHAR_WIDTH, 0.0);
+        (status, theme.error)
+    } else {
+        ("OUTPUTS INCOMPLETE".to_string(), theme.dim_text)
+    };
 
-    d.draw_line_ex(squiggle_start, squiggle_end, LINE_THICKNESS, Color::RED);
+    d.draw_text_ex(
+        font,
+        &status,
+        Vector2::new(MINIMAP_MARGIN, MINIMAP_MARGIN),
+        NODE_FONT_SIZE,
+        NODE_FONT_SPACING,
+        color,
+    );
 }
 
-fn render_io_arrow(
+const HELP_PADDING: f32 = 20.;
+const HELP_COMBO_COLUMN_WIDTH: f32 = 20. * NODE_CHAR_WIDTH;
+const HELP_DESCRIPTION_COLUMN_WIDTH: f32 = 40. * NODE_CHAR_WIDTH;
+
+fn render_help(
     d: &mut impl RaylibDraw,
-    node_loc: &NodeCoord,
-    dir: Dir,
-    label: &str,
+    keymap: &Keymap,
+    window_dimensions: (i32, i32),
     font: &Font,
+    theme: &Theme,
 ) {
-    let indicator_center = node_loc.io_indicator(dir);
+    // these are handled directly rather than through the keymap
+    let fixed_lines = [
+        ("E / I / O / R", "new exec / input / output / random node"),
+        ("Arrows", "move the cursor"),
+        ("Shift+Arrows", "select text"),
+        ("< / > / R", "sort / reverse input data"),
+        (
+            "Tab / Shift+Tab",
+            "indent / dedent, while typing into a node",
+        ),
+        ("PageUp / PageDown", "scroll the IO log, while it's shown"),
+    ]
+    .map(|(combo, description)| (combo.to_string(), description.to_string()));
+
+    let lines: Vec<_> = keymap.help_lines().into_iter().chain(fixed_lines).collect();
+
+    let size = Vector2::new(
+        HELP_COMBO_COLUMN_WIDTH + HELP_DESCRIPTION_COLUMN_WIDTH + 2. * HELP_PADDING,
+        lines.len() as f32 * NODE_LINE_HEIGHT + 2. * HELP_PADDING,
+    );
 
-    let component_offset = dir
-        .rotate_right()
-        .normalized()
-        .scale_by(1. / 3. * NODE_OUTSIDE_PADDING);
+    let window_size = Vector2::new(window_dimensions.0 as f32, window_dimensions.1 as f32);
+    let pos = (window_size - size) / 2.;
 
-    let arrow_center = indicator_center - component_offset;
-    let text_center = indicator_center + component_offset;
+    d.draw_rectangle_v(
+        pos,
+        size,
+        Color {
+            a: 220,
+            ..theme.background
+        },
+    );
+    d.draw_rectangle_lines_ex(
+        Rectangle::new(pos.x, pos.y, size.x, size.y),
+        LINE_THICKNESS,
+        theme.border,
+    );
 
-    render_arrow(d, arrow_center, dir, Color::WHITE);
+    for (i, (combo, description)) in lines.iter().enumerate() {
+        let line_pos = pos + Vector2::new(HELP_PADDING, HELP_PADDING + i as f32 * NODE_LINE_HEIGHT);
 
-    render_centered_text(d, label, text_center, font, Color::WHITE);
+        d.draw_text_ex(
+            font,
+            combo,
+            line_pos,
+            NODE_FONT_SIZE,
+            NODE_FONT_SPACING,
+            theme.text,
+        );
+        d.draw_text_ex(
+            font,
+            description,
+            line_pos + Vector2::new(HELP_COMBO_COLUMN_WIDTH, 0.),
+            NODE_FONT_SIZE,
+            NODE_FONT_SPACING,
+            theme.dim_text,
+        );
+    }
 }
 
-fn render_dashed_line(
-    d: &mut impl RaylibDraw,
-    start_pos: Vector2,
-    end_pos: Vector2,
-    color: Color,
-    dashes: usize,
-) {
-    let dash_len = NODE_OUTSIDE_SIDE_LENGTH / (2 * GHOST_NODE_DASHES + 1) as f32;
+fn render_world(d: &mut impl RaylibDraw, state: &State, font: &Font) {
+    let model = &state.model;
+    let theme = &state.theme;
+    let layout = &state.layout;
 
-    let dash_tail = (end_pos - start_pos).normalized().scale_by(dash_len);
+    render_nodes(d, model, font, theme, layout);
 
-    for dash_no in 0..=dashes {
-        let dash_start = start_pos + dash_tail.scale_by(2.0 * dash_no as f32);
-        d.draw_line_ex(dash_start, dash_start + dash_tail, LINE_THICKNESS, color);
+    render_ports(d, model, theme, layout);
+
+    if model.show_dangling_io {
+        render_dangling_io(d, model, theme, layout);
     }
-}
 
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
-enum Dir {
-    Up,
-    Down,
-    Left,
-    Right,
-}
+    render_ghosts(d, model, theme, layout);
 
-impl Dir {
-    const ALL: [Self; 4] = [Dir::Up, Dir::Down, Dir::Left, Dir::Right];
+    if let Some((start, end)) = model.rubber_band {
+        render_rubber_band(d, start, end, theme);
+    }
 
-    fn normalized(&self) -> Vector2 {
-        match self {
-            Dir::Up => Vector2::new(0.0, -1.0),
-            Dir::Down => Vector2::new(0.0, 1.0),
-            Dir::Left => Vector2::new(-1.0, 0.0),
-            Dir::Right => Vector2::new(1.0, 0.0),
-        }
+    if let Some(Prompt::FindReplace {
+        find,
+        replacement,
+        editing_replacement,
+    }) = &model.prompt
+    {
+        render_find_replace(
+            d,
+            model.highlighted_node,
+            [find, replacement],
+            *editing_replacement,
+            font,
+            theme,
+            layout,
+        );
     }
 
-    fn inverse(&self) -> Self {
-        match self {
-            Dir::Up => Dir::Down,
-            Dir::Down => Dir::Up,
-            Dir::Left => Dir::Right,
-            Dir::Right => Dir::Left,
-        }
+    if let Some(Prompt::DeleteCluster) = &model.prompt {
+        let count = removable_cluster(model).len();
+
+        render_confirm(
+            d,
+            model.highlighted_node,
+            &format!("DELETE {count} NODES? ENTER"),
+            font,
+            theme,
+            layout,
+        );
     }
 
-    fn rotate_right(&self) -> Self {
-        match self {
-            Dir::Left => Dir::Up,
-            Dir::Up => Dir::Right,
-            Dir::Right => Dir::Down,
-            Dir::Down => Dir::Left,
+    if let Some(Prompt::NewWorkspace) = &model.prompt {
+        render_confirm(
+            d,
+            model.highlighted_node,
+            "UNSAVED CHANGES! S TO SAVE, ENTER TO DISCARD, ESC TO CANCEL",
+            font,
+            theme,
+            layout,
+        );
+    }
+
+    if let Some(Prompt::GoTo { text, invalid }) = &model.prompt {
+        render_text_prompt(
+            d,
+            model.highlighted_node,
+            &format!("GO TO: {text}_"),
+            invalid.then_some("INVALID COORD"),
+            font,
+            theme,
+            layout,
+        );
+    }
+
+    if let Some(Prompt::Poke { text, invalid }) = &model.prompt {
+        render_text_prompt(
+            d,
+            model.highlighted_node,
+            &format!("POKE: {text}_"),
+            invalid.then_some("NOT READING THERE"),
+            font,
+            theme,
+            layout,
+        );
+    }
+
+    let highlighted = model.nodes.get(&model.highlighted_node);
+
+    match highlighted {
+        Some(Node::Exec(exec_node)) => {
+            if exec_node.is_in_edit_mode() {
+                render_cursor(d, model.highlighted_node, exec_node, theme, layout);
+            }
+        }
+
+        Some(Node::Input(_) | Node::Output(_) | Node::Random(_)) => {}
+
+        None => {
+            render_dashed_node_border(d, model.highlighted_node, theme.ghost, layout);
+
+            render_plus(d, model.highlighted_node.center(layout), theme.ghost);
         }
     }
 }
 
-fn render_plus(d: &mut impl RaylibDraw, center: Vector2, color: Color) {
-    d.draw_line_ex(
-        center + Vector2::new(-NODE_LINE_HEIGHT, 0.0),
-        center + Vector2::new(NODE_LINE_HEIGHT, 0.0),
-        LINE_THICKNESS,
-        color,
-    );
-    d.draw_line_ex(
-        center + Vector2::new(0.0, -NODE_LINE_HEIGHT),
-        center + Vector2::new(0.0, NODE_LINE_HEIGHT),
-        LINE_THICKNESS,
-        color,
-    );
+/// a warning marker on each side an exec node reads from or writes to that has no node there
+fn render_dangling_io(d: &mut impl RaylibDraw, model: &Model, theme: &Theme, layout: &Layout) {
+    for (node_loc, dir) in dangling_io(&model.nodes, model.torus) {
+        let edge = node_loc.center(layout)
+            + dir
+                .normalized()
+                .scale_by(layout.outside_side_length() / 2.0);
+
+        d.draw_circle_v(edge, PENDING_PORT_RADIUS, theme.warning);
+    }
 }
 
-fn render_arrow(d: &mut impl RaylibDraw, center: Vector2, direction: Dir, color: Color) {
-    let dir_vec = direction.normalized();
+const PORT_RADIUS: f32 = 3.;
+const PENDING_PORT_RADIUS: f32 = 5.;
 
-    let arrow_tip = center + dir_vec.scale_by(NODE_LINE_HEIGHT);
-    let arrow_base = center - dir_vec.scale_by(NODE_LINE_HEIGHT);
+/// marks the sides of the node that have a neighbor, more brightly if a value is waiting there
+fn render_ports(d: &mut impl RaylibDraw, model: &Model, theme: &Theme, layout: &Layout) {
+    let (nodes, node_loc, torus) = (&model.nodes, model.highlighted_node, model.torus);
 
-    let arrow_left_wing = center
-        + dir_vec
-            .scale_by(NODE_LINE_HEIGHT)
-            .rotated((1.0 / 4.0) * f32::consts::TAU);
+    if !nodes.contains_key(&node_loc) {
+        return;
+    }
 
-    let arrow_right_wing = center
-        + dir_vec
-            .scale_by(NODE_LINE_HEIGHT)
-            .rotated(-(1.0 / 4.0) * f32::consts::TAU);
+    for dir in Dir::ALL {
+        let neighbor_loc = node_loc.neighbor(dir);
 
-    d.draw_line_ex(arrow_base, arrow_tip, LINE_THICKNESS, color);
-    d.draw_line_ex(arrow_tip, arrow_left_wing, LINE_THICKNESS, color);
-    d.draw_line_ex(arrow_tip, arrow_right_wing, LINE_THICKNESS, color);
+        // a torus edge and the cell just outside it are side by side, but not connected
+        if !nodes.contains_key(&neighbor_loc)
+            || node_loc.io_neighbor(dir, torus) != Some(neighbor_loc)
+        {
+            continue;
+        }
+
+        // halfway between the two nodes' io indicators, so it doesn't cover their arrows
+        let center = (node_loc.io_indicator(dir, layout)
+            + neighbor_loc.io_indicator(dir.inverse(), layout))
+        .scale_by(0.5);
+
+        let pending = port_pending(nodes, node_loc, dir, torus)
+            || port_pending(nodes, neighbor_loc, dir.inverse(), torus);
+
+        if pending {
+            d.draw_circle_v(center, PENDING_PORT_RADIUS, theme.highlighted_border);
+        } else {
+            d.draw_circle_v(center, PORT_RADIUS, theme.dim_text);
+        }
+    }
 }
 
-fn render_double_arrow(d: &mut impl RaylibDraw, center: Vector2, direction: Dir, color: Color) {
-    let dir_vec = direction.normalized();
+/// whether the node is waiting to send a value in `dir`, or to receive one from it
+fn port_pending(nodes: &Nodes, node_loc: NodeCoord, dir: Dir, torus: Option<Torus>) -> bool {
+    let Some(Node::Exec(ExecNode {
+        exec: Some(exec), ..
+    })) = nodes.get(&node_loc)
+    else {
+        return false;
+    };
 
-    let half_arrow_stem = dir_vec.scale_by(NODE_LINE_HEIGHT);
+    match exec.io {
+        NodeIO::Outbound(io_dir, _) | NodeIO::Inbound(io_dir) => io_dir == dir,
+        NodeIO::OutboundAny(_) => any_write_recipient(nodes, node_loc, torus) == Some(dir),
+        NodeIO::InboundAny => neighbor_sending_io(nodes, &node_loc, dir, torus),
+        NodeIO::None => false,
+    }
+}
 
-    let arrow_tip = center + half_arrow_stem;
-    let arrow_base = center - half_arrow_stem;
+const MINIMAP_SIZE: f32 = 160.;
+const MINIMAP_MARGIN: f32 = 16.;
 
-    let arrow_left_wing = center
-        + dir_vec
-            .scale_by(NODE_LINE_HEIGHT)
-            .rotated((1.0 / 4.0) * f32::consts::TAU);
+fn render_minimap(
+    d: &mut impl RaylibDraw,
+    model: &Model,
+    camera: Camera2D,
+    window_dimensions: (i32, i32),
+    theme: &Theme,
+    layout: &Layout,
+) {
+    if model.nodes.is_empty() {
+        return;
+    }
 
-    let arrow_right_wing = center
-        + dir_vec
-            .scale_by(NODE_LINE_HEIGHT)
-            .rotated(-(1.0 / 4.0) * f32::consts::TAU);
+    let window_size = Vector2::new(window_dimensions.0 as f32, window_dimensions.1 as f32);
 
-    d.draw_line_ex(arrow_base, arrow_tip, LINE_THICKNESS, color);
-    d.draw_line_ex(arrow_tip, arrow_left_wing, LINE_THICKNESS, color);
-    d.draw_line_ex(arrow_tip, arrow_right_wing, LINE_THICKNESS, color);
+    // the part of the world that's currently on screen
+    let viewport_min = camera.target - camera.offset / camera.zoom;
+    let viewport_max = viewport_min + window_size / camera.zoom;
 
-    d.draw_line_ex(
-        arrow_tip,
-        arrow_left_wing + half_arrow_stem,
-        LINE_THICKNESS,
-        color,
-    );
-    d.draw_line_ex(
-        arrow_tip,
-        arrow_right_wing + half_arrow_stem,
-        LINE_THICKNESS,
-        color,
-    );
-}
+    let (min, max) =
+        model
+            .nodes
+            .keys()
+            .fold((viewport_min, viewport_max), |(min, max), node_loc| {
+                let top_left = node_loc.top_left_corner(layout);
+                let bottom_right = node_loc.bottom_right_corner(layout);
 
-fn render_node_border(d: &mut impl RaylibDraw, node_loc: NodeCoord, line_color: Color) {
-    d.draw_line_ex(
-        node_loc.top_left_corner(),
-        node_loc.top_right_corner(),
-        LINE_THICKNESS,
-        line_color,
-    );
-    d.draw_line_ex(
-        node_loc.top_left_corner(),
-        node_loc.bottom_left_corner(),
-        LINE_THICKNESS,
-        line_color,
-    );
-    d.draw_line_ex(
-        node_loc.bottom_left_corner(),
-        node_loc.bottom_right_corner(),
-        LINE_THICKNESS,
-        line_color,
-    );
-    d.draw_line_ex(
-        node_loc.top_right_corner(),
-        node_loc.bottom_right_corner(),
-        LINE_THICKNESS,
-        line_color,
+                (
+                    Vector2::new(min.x.min(top_left.x), min.y.min(top_left.y)),
+                    Vector2::new(max.x.max(bottom_right.x), max.y.max(bottom_right.y)),
+                )
+            });
+
+    let scale = MINIMAP_SIZE / f32::max(max.x - min.x, max.y - min.y);
+
+    let minimap_pos = window_size
+        - Vector2::new(MINIMAP_SIZE, MINIMAP_SIZE)
+        - Vector2::new(MINIMAP_MARGIN, MINIMAP_MARGIN);
+
+    let to_minimap = |world_pos: Vector2| minimap_pos + (world_pos - min) * scale;
+
+    let minimap_rect = Rectangle::new(minimap_pos.x, minimap_pos.y, MINIMAP_SIZE, MINIMAP_SIZE);
+
+    d.draw_rectangle_rec(minimap_rect, theme.background);
+    d.draw_rectangle_lines_ex(minimap_rect, LINE_THICKNESS, theme.border);
+
+    for node_loc in model.nodes.keys() {
+        if *node_loc != model.highlighted_node {
+            d.draw_circle_v(to_minimap(node_loc.center(layout)), 2.0, theme.border);
+        }
+    }
+
+    if model.nodes.contains_key(&model.highlighted_node) {
+        d.draw_circle_v(
+            to_minimap(model.highlighted_node.center(layout)),
+            3.5,
+            theme.highlighted_border,
+        );
+    }
+
+    let viewport_pos = to_minimap(viewport_min);
+    let viewport_size = (viewport_max - viewport_min) * scale;
+
+    d.draw_rectangle_lines_ex(
+        Rectangle::new(
+            viewport_pos.x,
+            viewport_pos.y,
+            viewport_size.x,
+            viewport_size.y,
+        ),
+        1.0,
+        theme.dim_text,
     );
 }
 
-fn render_centered_text(
+fn render_find_replace(
     d: &mut impl RaylibDraw,
-    text: &str,
-    center: Vector2,
+    node_loc: NodeCoord,
+    [find, replacement]: [&str; 2],
+    editing_replacement: bool,
     font: &Font,
-    color: Color,
+    theme: &Theme,
+    layout: &Layout,
 ) {
-    let text_size = font.measure_text(text, NODE_FONT_SIZE, NODE_FONT_SPACING);
+    let fields = [
+        ("FIND: ", find, !editing_replacement),
+        ("WITH: ", replacement, editing_replacement),
+    ];
 
-    let top_left = center - text_size.scale_by(0.5);
+    for (i, (label, text, active)) in fields.into_iter().enumerate() {
+        let pos = node_loc.bottom_left_corner(layout)
+            + Vector2::new(0.0, NODE_INSIDE_PADDING + i as f32 * NODE_LINE_HEIGHT);
+
+        let (text, color) = if active {
+            (format!("{label}{text}_"), theme.text)
+        } else {
+            (format!("{label}{text}"), theme.dim_text)
+        };
+
+        d.draw_text_ex(font, &text, pos, NODE_FONT_SIZE, NODE_FONT_SPACING, color);
+    }
+}
+
+/// a line of typing under the node, with what's wrong with it under that
+fn render_text_prompt(
+    d: &mut impl RaylibDraw,
+    node_loc: NodeCoord,
+    prompt: &str,
+    problem: Option<&str>,
+    font: &Font,
+    theme: &Theme,
+    layout: &Layout,
+) {
+    let pos = node_loc.bottom_left_corner(layout) + Vector2::new(0.0, NODE_INSIDE_PADDING);
 
     d.draw_text_ex(
         font,
-        text,
-        top_left,
+        prompt,
+        pos,
         NODE_FONT_SIZE,
         NODE_FONT_SPACING,
-        color,
+        theme.text,
     );
+
+    if let Some(problem) = problem {
+        d.draw_text_ex(
+            font,
+            problem,
+            pos + Vector2::new(0.0, NODE_LINE_HEIGHT),
+            NODE_FONT_SIZE,
+            NODE_FONT_SPACING,
+            theme.error,
+        );
+    }
 }
 
-fn render_dashed_node_border(d: &mut impl RaylibDraw, node_loc: NodeCoord, line_color: Color) {
-    render_dashed_line(
-        d,
-        node_loc.top_left_corner(),
-        node_loc.top_right_corner(),
-        line_color,
-        GHOST_NODE_DASHES,
-    );
-    render_dashed_line(
-        d,
-        node_loc.top_left_corner(),
-        node_loc.bottom_left_corner(),
-        line_color,
-        GHOST_NODE_DASHES,
+fn render_rubber_band(d: &mut impl RaylibDraw, a: Vector2, b: Vector2, theme: &Theme) {
+    let rect = Rectangle::new(
+        a.x.min(b.x),
+        a.y.min(b.y),
+        (a.x - b.x).abs(),
+        (a.y - b.y).abs(),
     );
-    render_dashed_line(
-        d,
-        node_loc.bottom_left_corner(),
-        node_loc.bottom_right_corner(),
-        line_color,
-        GHOST_NODE_DASHES,
+
+    d.draw_rectangle_rec(
+        rect,
+        Color {
+            a: 40,
+            ..theme.selection
+        },
     );
-    render_dashed_line(
-        d,
-        node_loc.top_right_corner(),
-        node_loc.bottom_right_corner(),
-        line_color,
-        GHOST_NODE_DASHES,
+    d.draw_rectangle_lines_ex(rect, LINE_THICKNESS, theme.selection);
+}
+
+fn render_confirm(
+    d: &mut impl RaylibDraw,
+    node_loc: NodeCoord,
+    message: &str,
+    font: &Font,
+    theme: &Theme,
+    layout: &Layout,
+) {
+    let pos = node_loc.bottom_left_corner(layout) + Vector2::new(0.0, NODE_INSIDE_PADDING);
+
+    d.draw_text_ex(
+        font,
+        message,
+        pos,
+        NODE_FONT_SIZE,
+        NODE_FONT_SPACING,
+        theme.error,
     );
 }
 
-#[derive(Clone, Debug)]
-struct Input {
-    mods: Modifiers,
-    pressed: Option<Key>,
-    window_dimensions: (i32, i32),
-    mouse_wheel_move: f32,
-    clipboard: String,
+fn render_ghosts(d: &mut impl RaylibDraw, model: &Model, theme: &Theme, layout: &Layout) {
+    match model.ghosts {
+        Ghosts::MoveView => {
+            for dir in Dir::ALL {
+                let neighbor_loc = model.highlighted_node.neighbor(dir);
+                if !model.nodes.contains_key(&neighbor_loc) {
+                    render_dashed_node_border(d, neighbor_loc, theme.ghost, layout);
+
+                    render_arrow(d, neighbor_loc.center(layout), dir, theme.ghost);
+                }
+            }
+        }
+
+        Ghosts::MoveNode => {
+            for dir in Dir::ALL {
+                let neighbor_loc = model.highlighted_node.neighbor(dir);
+                if !model.nodes.contains_key(&neighbor_loc) {
+                    render_dashed_node_border(d, neighbor_loc, theme.ghost, layout);
+
+                    render_double_arrow(d, neighbor_loc.center(layout), dir, theme.ghost);
+                }
+            }
+        }
+
+        Ghosts::None => {}
+    }
 }
 
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
-enum Key {
-    Esc,
-    Tab,
-    Backspace,
-    Enter,
-    Home,
-    End,
-    Delete,
-    Arrow(Dir),
-    Char(char),
+fn render_nodes(
+    d: &mut impl RaylibDraw,
+    model: &Model,
+    font: &Font,
+    theme: &Theme,
+    layout: &Layout,
+) {
+    for (node_loc, node) in model.nodes.iter() {
+        let blocked = match node {
+            Node::Exec(ExecNode {
+                exec: Some(exec), ..
+            }) => exec.is_blocked(),
+            Node::Exec(_) | Node::Input(_) | Node::Output(_) | Node::Random(_) => false,
+        };
+
+        let line_color = if node_loc == &model.highlighted_node {
+            theme.highlighted_border
+        } else if model.selection.contains(node_loc) {
+            theme.selected_border
+        } else if blocked {
+            theme.blocked_border
+        } else if model.locked.contains(node_loc) {
+            theme.dim_text
+        } else {
+            theme.border
+        };
+
+        let renaming = match &model.prompt {
+            Some(Prompt::RenameNode(name)) if node_loc == &model.highlighted_node => Some(name),
+            Some(
+                Prompt::RenameNode(_)
+                | Prompt::FindReplace { .. }
+                | Prompt::GoTo { .. }
+                | Prompt::Poke { .. }
+                | Prompt::DeleteCluster
+                | Prompt::NewWorkspace,
+            )
+            | None => None,
+        };
+
+        if let Some(name) = renaming {
+            render_node_name(d, *node_loc, &format!("{name}_"), font, theme.text, layout);
+        } else if let Some(name) = node.name() {
+            render_node_name(d, *node_loc, name, font, theme.dim_text, layout);
+        }
+
+        match node {
+            Node::Exec(exec_node) => {
+                render_node_border(d, *node_loc, line_color, layout);
+
+                if theme.error_marks && exec_node.error().is_some() {
+                    render_error_outline(
+                        d,
+                        node_loc.top_left_corner(layout),
+                        node_loc.bottom_right_corner(layout),
+                        theme.error,
+                    );
+                }
+
+                render_node_gizmos(
+                    d,
+                    *node_loc,
+                    &exec_node.exec,
+                    font,
+                    line_color,
+                    theme,
+                    layout,
+                );
+
+                render_node_text(d, exec_node, node_loc, font, theme, layout);
+
+                // the below two things should not be true at the same time if I did my homework
+                // (because a node with an error should not be able to begin executing)
+                // but this isn't reflected in the type system. If it were to happen though, it means there's a bug
+                debug_assert!(!(exec_node.error().is_some() && exec_node.exec.is_some()));
+
+                if let Some(error) = exec_node.error()
+                    && show_error(node_loc, exec_node, &model.highlighted_node, error.line)
+                {
+                    render_squiggle(
+                        d,
+                        *node_loc,
+                        exec_node,
+                        error.line,
+                        error.column,
+                        theme.error,
+                        layout,
+                    );
+                }
+
+                for warning in &exec_node.warnings {
+                    render_squiggle(
+                        d,
+                        *node_loc,
+                        exec_node,
+                        warning.line,
+                        None,
+                        theme.warning,
+                        layout,
+                    );
+                }
+
+                if let Some(exec) = &exec_node.exec
+                    && !exec.code.is_empty()
+                {
+                    if let NodeIO::Outbound(dir, value) = exec.io {
+                        render_io_arrow(d, node_loc, dir, &value.to_string(), font, theme, layout);
+                    } else if let NodeIO::OutboundAny(value) = exec.io
+                        && let Some(dir) = any_write_recipient(&model.nodes, *node_loc, model.torus)
+                    {
+                        render_io_arrow(d, node_loc, dir, &value.to_string(), font, theme, layout);
+                    } else if let NodeIO::Inbound(io_dir) = exec.io
+                        && !neighbor_sending_io(&model.nodes, node_loc, io_dir, model.torus)
+                        && let Some(neighbor_loc) = node_loc.io_neighbor(io_dir, model.torus)
+                    {
+                        render_io_arrow(
+                            d,
+                            &neighbor_loc,
+                            io_dir.inverse(),
+                            "?",
+                            font,
+                            theme,
+                            layout,
+                        );
+                    }
+                }
+            }
+
+            Node::Input(input_node) => {
+                render_node_border(d, *node_loc, line_color, layout);
+
+                let str;
+                let label = if let Some(i) = input_node.index {
+                    str = i.to_string();
+                    &str
+                } else {
+                    "INPUT NODE"
+                };
+
+                render_centered_text(d, label, node_loc.center(layout), font, theme.text);
+
+                if let Some(num) = input_node.current() {
+                    render_io_arrow(
+                        d,
+                        node_loc,
+                        input_node.dir,
+                        &num.to_string(),
+                        font,
+                        theme,
+                        layout,
+                    );
+                }
+            }
+
+            Node::Output(output_node) if !output_node.expected.is_empty() => {
+                render_node_border(d, *node_loc, line_color, layout);
+
+                render_output_comparison(d, *node_loc, output_node, font, theme, layout);
+            }
+
+            Node::Output(output_node) => {
+                render_node_border(d, *node_loc, line_color, layout);
+
+                let str;
+                let label = if let Some(&last) = output_node.received().last() {
+                    str = last.to_string();
+                    &str
+                } else {
+                    "OUTPUT NODE"
+                };
+
+                render_centered_text(d, label, node_loc.center(layout), font, theme.text);
+            }
+
+            Node::Random(random_node) => {
+                render_node_border(d, *node_loc, line_color, layout);
+
+                let label = format!("RANDOM {}", random_node.seed);
+
+                render_centered_text(d, &label, node_loc.center(layout), font, theme.text);
+
+                if let Some(num) = random_node.current() {
+                    render_io_arrow(
+                        d,
+                        node_loc,
+                        Dir::Down,
+                        &num.to_string(),
+                        font,
+                        theme,
+                        layout,
+                    );
+                }
+            }
+        }
+    }
+
+    // error boxes are rendered in a second pass because they need to be rendered over top of everything else
+    for (node_loc, node) in model.nodes.iter() {
+        if let Node::Exec(exec_node) = &node
+            && let Some(error) = exec_node.error()
+            && show_error(node_loc, exec_node, &model.highlighted_node, error.line)
+        {
+            render_error_msg(
+                d,
+                node_loc,
+                error.problem.to_str(),
+                font,
+                theme.error,
+                theme,
+                layout,
+            );
+        };
+    }
+
+    // a refused edit is only worth explaining while the node is being edited
+    if let Some(Node::Exec(ExecNode {
+        overflow: Some(overflow),
+        ..
+    })) = model.nodes.get(&model.highlighted_node)
+    {
+        render_error_msg(
+            d,
+            &model.highlighted_node,
+            overflow.to_str(),
+            font,
+            theme.error,
+            theme,
+            layout,
+        );
+    }
+
+    // warnings stay out of the way, they're only spelled out on the line being edited
+    if let Some(Node::Exec(exec_node)) = model.nodes.get(&model.highlighted_node)
+        && exec_node.overflow.is_none()
+        && let cursor_line = line_column(&exec_node.text, exec_node.cursor).0
+        && let Some(warning) = exec_node
+            .warnings
+            .iter()
+            .find(|warning| warning.line as usize == cursor_line)
+    {
+        render_error_msg(
+            d,
+            &model.highlighted_node,
+            warning.kind.to_str(),
+            font,
+            theme.warning,
+            theme,
+            layout,
+        );
+    }
 }
 
-impl Key {
-    fn from(raylib_key: KeyboardKey, shift_held: bool) -> Option<Self> {
-        let unbound = None;
-        let handled_elsewhere = None;
+/// lists the expected values next to the received ones, which are red if they're wrong
+fn render_output_comparison(
+    d: &mut impl RaylibDraw,
+    node_loc: NodeCoord,
+    output_node: &OutputNode,
+    font: &Font,
+    theme: &Theme,
+    layout: &Layout,
+) {
+    let column_offset = Vector2::new(6. * NODE_CHAR_WIDTH, 0.);
 
-        let case = if shift_held { 1 } else { 0 };
+    let header_pos = node_loc.line_pos(0, layout);
 
-        use KeyboardKey as RK;
+    for (text, pos) in [("EXP", header_pos), ("GOT", header_pos + column_offset)] {
+        d.draw_text_ex(
+            font,
+            text,
+            pos,
+            NODE_FONT_SIZE,
+            NODE_FONT_SPACING,
+            theme.dim_text,
+        );
+    }
 
-        Some(
-            match raylib_key {
-                // symbols
-                RK::KEY_NULL => return None,
-                RK::KEY_APOSTROPHE => [Key::Char('\''), Key::Char('"')],
-                RK::KEY_COMMA => [Key::Char(','), Key::Char('<')],
-                RK::KEY_MINUS => [Key::Char('-'), Key::Char('_')],
-                RK::KEY_PERIOD => [Key::Char('.'), Key::Char('>')],
-                RK::KEY_SLASH => [Key::Char('/'), Key::Char('?')],
-                RK::KEY_SEMICOLON => [Key::Char(';'), Key::Char(':')],
-                RK::KEY_EQUAL => [Key::Char('='), Key::Char('+')],
-                // number keys
-                RK::KEY_ZERO => [Key::Char('0'), Key::Char(')')],
-                RK::KEY_ONE => [Key::Char('1'), Key::Char('!')],
+    // keeps the latest values in view, once there are more than fit
+    let rows = layout.lines - 1;
+    let first_row = output_node.received().len().saturating_sub(rows - 1);
+
+    let matches: Vec<_> = output_node.matches().collect();
+
+    for (line, i) in (first_row..first_row + rows).enumerate() {
+        let pos = node_loc.line_pos(line + 1, layout);
+
+        if let Some(expected) = output_node.expected.get(i) {
+            d.draw_text_ex(
+                font,
+                &expected.to_string(),
+                pos,
+                NODE_FONT_SIZE,
+                NODE_FONT_SPACING,
+                theme.text,
+            );
+        }
+
+        if let Some(received) = output_node.received().get(i) {
+            let color = if matches[i] { theme.text } else { theme.error };
+
+            d.draw_text_ex(
+                font,
+                &received.to_string(),
+                pos + column_offset,
+                NODE_FONT_SIZE,
+                NODE_FONT_SPACING,
+                color,
+            );
+        }
+    }
+}
+
+fn render_node_name(
+    d: &mut impl RaylibDraw,
+    node_loc: NodeCoord,
+    name: &str,
+    font: &Font,
+    color: Color,
+    layout: &Layout,
+) {
+    let name_pos = node_loc.top_left_corner(layout) - Vector2::new(0.0, NODE_LINE_HEIGHT * 1.5);
+
+    d.draw_text_ex(
+        font,
+        name,
+        name_pos,
+        NODE_FONT_SIZE,
+        NODE_FONT_SPACING,
+        color,
+    );
+}
+
+fn render_node_text(
+    d: &mut impl RaylibDraw,
+    node: &ExecNode,
+    node_loc: &NodeCoord,
+    font: &Font,
+    theme: &Theme,
+    layout: &Layout,
+) {
+    let highlight = if let Some(ref exec) = node.exec
+        && let Some(instr) = exec.code.get(exec.ip as usize)
+    {
+        Highlight::Executing {
+            line: instr.src_line as usize,
+            blocked: exec.is_blocked(),
+        }
+    } else if node.text_selected() {
+        let (start, end) = node.selection_range();
+
+        let (start_line, start_col) = line_column(&node.text, start);
+        let (end_line, end_col) = line_column(&node.text, end);
+
+        Highlight::Selected {
+            start_line,
+            start_col,
+            end_line,
+            end_col,
+        }
+    } else {
+        Highlight::None
+    };
+
+    let (shown_lines, shown_columns) = node.shown(layout);
+    let (top, left) = node.scroll;
+
+    for (line_no, line_text) in node
+        .text
+        .split('\n')
+        .enumerate()
+        .skip(top)
+        .take(layout.lines)
+    {
+        let line_loc = node_loc.line_pos(line_no - top, layout);
+
+        if node.breakpoints.contains(&line_no) {
+            let gutter_center =
+                line_loc + Vector2::new(-NODE_INSIDE_PADDING * 0.5, NODE_LINE_HEIGHT * 0.5);
+
+            d.draw_circle_v(gutter_center, NODE_INSIDE_PADDING * 0.3, theme.error);
+        }
+
+        match (highlight, highlight.selected_columns(line_no, line_text)) {
+            (Highlight::Executing { line, blocked }, _) if line == line_no => {
+                let highlight_color = if blocked {
+                    theme.blocked_line
+                } else {
+                    theme.executing_line
+                };
+
+                let highlight_pos = line_loc
+                    - Vector2 {
+                        x: NODE_INSIDE_PADDING * 0.25,
+                        y: 0.0,
+                    };
+
+                let highlight_size = Vector2 {
+                    x: layout.text_box_inside_width() + NODE_INSIDE_PADDING * 0.5,
+                    y: NODE_LINE_HEIGHT,
+                };
+
+                d.draw_rectangle_v(highlight_pos, highlight_size, highlight_color);
+
+                render_code_line(
+                    d,
+                    line_text,
+                    shown_columns.clone(),
+                    line_loc,
+                    font,
+                    theme.executing_text,
+                    theme,
+                );
+            }
+
+            (Highlight::Selected { .. }, Some((selection_start, selection_end))) => {
+                let clip = |column: usize| column.clamp(left, shown_columns.end) - left;
+                let selection_len = clip(selection_end) - clip(selection_start);
+
+                let select_highlight_pos =
+                    node_loc.char_pos(line_no - top, clip(selection_start), layout);
+
+                let selection_box_size = Vector2 {
+                    x: selection_len as f32 * NODE_CHAR_WIDTH,
+                    y: NODE_LINE_HEIGHT,
+                };
+
+                d.draw_rectangle_v(select_highlight_pos, selection_box_size, theme.selection);
+
+                render_code_line(
+                    d,
+                    line_text,
+                    shown_columns.clone(),
+                    line_loc,
+                    font,
+                    theme.text,
+                    theme,
+                );
+            }
+
+            (Highlight::None | Highlight::Executing { .. } | Highlight::Selected { .. }, _) => {
+                render_code_line(
+                    d,
+                    line_text,
+                    shown_columns.clone(),
+                    line_loc,
+                    font,
+                    theme.text,
+                    theme,
+                );
+            }
+        }
+    }
+
+    // points at the executing line from the gutter, in case the highlight is hard to make out
+    if let Highlight::Executing { line, .. } = highlight
+        && shown_lines.contains(&line)
+    {
+        let gutter_center = node_loc.line_pos(line - top, layout)
+            + Vector2::new(-NODE_INSIDE_PADDING * 0.5, NODE_LINE_HEIGHT * 0.5);
+
+        render_sized_arrow(
+            d,
+            gutter_center,
+            Dir::Right,
+            NODE_INSIDE_PADDING * 0.35,
+            theme.text,
+        );
+    }
+}
+
+/// draws the part of the line in `columns`, everything from the first `#` on in the comment color,
+/// and the rest in `code_color`
+fn render_code_line(
+    d: &mut impl RaylibDraw,
+    line_text: &str,
+    columns: Range<usize>,
+    line_loc: Vector2,
+    font: &Font,
+    code_color: Color,
+    theme: &Theme,
+) {
+    let comment_start = line_text
+        .chars()
+        .position(|char| char == '#')
+        .unwrap_or(usize::MAX)
+        .clamp(columns.start, columns.end);
+
+    let code = char_range(line_text, columns.start..comment_start);
+    let comment = char_range(line_text, comment_start..columns.end);
+
+    let comment_offset = Vector2::new(NODE_CHAR_WIDTH * code.chars().count() as f32, 0.0);
+
+    d.draw_text_ex(
+        font,
+        code,
+        line_loc,
+        NODE_FONT_SIZE,
+        NODE_FONT_SPACING,
+        code_color,
+    );
+    d.draw_text_ex(
+        font,
+        comment,
+        line_loc + comment_offset,
+        NODE_FONT_SIZE,
+        NODE_FONT_SPACING,
+        theme.comment,
+    );
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum Highlight {
+    None,
+    Executing {
+        line: usize,
+        blocked: bool,
+    },
+    Selected {
+        start_line: usize,
+        start_col: usize,
+        end_line: usize,
+        end_col: usize,
+    },
+}
+
+impl Highlight {
+    /// the columns of `line_text` that are selected, as a start and an end past it.
+    /// a line the selection carries on past also gets the newline,
+    /// as a one column stub, even when the line is empty
+    fn selected_columns(&self, line_no: usize, line_text: &str) -> Option<(usize, usize)> {
+        let Highlight::Selected {
+            start_line,
+            start_col,
+            end_line,
+            end_col,
+        } = *self
+        else {
+            return None;
+        };
+
+        if line_no < start_line || end_line < line_no {
+            return None;
+        }
+
+        let line_len = line_text.chars().count();
+
+        let start = if line_no == start_line {
+            start_col.min(line_len)
+        } else {
+            0
+        };
+
+        let end = if line_no == end_line {
+            end_col.min(line_len)
+        } else {
+            line_len + 1
+        };
+
+        Some((start, end.max(start)))
+    }
+}
+
+fn show_error(
+    node_loc: &NodeCoord,
+    node: &ExecNode,
+    highlighted_node: &NodeCoord,
+    error_line: u8,
+) -> bool {
+    let (select_start, select_end) = node.selection_range();
+    let select_start_line = line_column(&node.text, select_start).0;
+    let select_end_line = line_column(&node.text, select_end).0;
+
+    let error_line = error_line as usize;
+
+    let node_is_highlighted = node_loc == highlighted_node;
+    let cursor_at_error_line = select_start_line <= error_line && error_line <= select_end_line;
+
+    !node_is_highlighted || !cursor_at_error_line
+}
+
+fn render_error_msg(
+    d: &mut impl RaylibDraw,
+    node_loc: &NodeCoord,
+    message: &str,
+    font: &Font,
+    color: Color,
+    theme: &Theme,
+    layout: &Layout,
+) {
+    const BOX_HEIGHT: f32 = NODE_LINE_HEIGHT + 2.0 * NODE_INSIDE_PADDING;
+
+    const BOX_NODE_PADDING: f32 = 0.25 * (NODE_OUTSIDE_PADDING - BOX_HEIGHT);
+
+    let text_width = layout.outside_side_length() - 2.0 * NODE_INSIDE_PADDING;
+
+    let fits =
+        |text: &str| font.measure_text(text, NODE_FONT_SIZE, NODE_FONT_SPACING).x <= text_width;
+
+    let lines = wrap_in_two(message, fits);
+
+    // the box grows upwards, away from the node
+    let box_height = BOX_HEIGHT + (lines.len() - 1) as f32 * NODE_LINE_HEIGHT;
+
+    let bottom_left = node_loc.top_left_corner(layout) - Vector2::new(0.0, BOX_NODE_PADDING);
+
+    let top_left = bottom_left - Vector2::new(0.0, box_height);
+
+    let top_right = top_left + Vector2::new(layout.outside_side_length(), 0.0);
+    let bottom_right = bottom_left + Vector2::new(layout.outside_side_length(), 0.0);
+
+    d.draw_rectangle_v(top_left, bottom_right - top_left, theme.background);
+
+    d.draw_line_ex(top_left, top_right, LINE_THICKNESS, color);
+    d.draw_line_ex(top_left, bottom_left, LINE_THICKNESS, color);
+    d.draw_line_ex(bottom_left, bottom_right, LINE_THICKNESS, color);
+    d.draw_line_ex(top_right, bottom_right, LINE_THICKNESS, color);
+
+    // warnings come through here too, they keep the single border
+    if theme.error_marks && color == theme.error {
+        render_error_outline(d, top_left, bottom_right, color);
+    }
+
+    for (i, line) in lines.iter().enumerate() {
+        let center = top_left
+            + Vector2::new(
+                0.5 * layout.outside_side_length(),
+                NODE_INSIDE_PADDING + (i as f32 + 0.5) * NODE_LINE_HEIGHT,
+            );
+
+        render_centered_text(d, line, center, font, color);
+    }
+}
+
+/// splits the text at a space if it doesn't fit on one line,
+/// keeping as much as possible on the first line
+fn wrap_in_two(text: &str, fits: impl Fn(&str) -> bool) -> Vec<&str> {
+    if fits(text) {
+        return vec![text];
+    }
+
+    let split = text
+        .match_indices(' ')
+        .map(|(i, _)| i)
+        .filter(|&i| fits(&text[..i]))
+        .last()
+        .or_else(|| text.find(' '));
+
+    match split {
+        Some(i) => vec![&text[..i], &text[i + 1..]],
+        None => vec![text],
+    }
+}
+
+fn neighbor_sending_io(
+    nodes: &Nodes,
+    node_loc: &NodeCoord,
+    io_dir: Dir,
+    torus: Option<Torus>,
+) -> bool {
+    let Some(neighbor_loc) = node_loc.io_neighbor(io_dir, torus) else {
+        return false;
+    };
+
+    let Some(neighbor) = nodes.get(&neighbor_loc) else {
+        return false;
+    };
+
+    match neighbor {
+        Node::Exec(exec_node) => {
+            let Some(neighbor_exec) = &exec_node.exec else {
+                return false;
+            };
+
+            match neighbor_exec.io {
+                NodeIO::Outbound(neighbor_io_dir, _) => neighbor_io_dir == io_dir.inverse(),
+                NodeIO::OutboundAny(_) => {
+                    any_write_recipient(nodes, neighbor_loc, torus) == Some(io_dir.inverse())
+                }
+                NodeIO::None | NodeIO::Inbound(_) | NodeIO::InboundAny => false,
+            }
+        }
+
+        Node::Input(input_node) => {
+            io_dir == input_node.dir.inverse() && input_node.current().is_some()
+        }
+
+        Node::Output(_) => false,
+
+        Node::Random(random_node) => io_dir == Dir::Up && random_node.current().is_some(),
+    }
+}
+
+/// what the ACC, BAK and MODE gizmos say. a node that isn't running shows the registers it'll start with
+fn gizmo_text(exec: &Option<NodeExec>) -> (String, String, &'static str) {
+    let Some(exec) = exec else {
+        return ("0".to_string(), "(0)".to_string(), "EDIT");
+    };
+
+    // BAK can't be used directly, which the parentheses are a reminder of.
+    // they're left off of the values that would be too wide for the gizmo with them
+    let bak = if exec.bak.get() < -99 {
+        exec.bak.to_string()
+    } else {
+        format!("({})", exec.bak)
+    };
+
+    let mode = match exec.io {
+        _ if exec.halted => "HALT",
+        NodeIO::None => "EXEC",
+        NodeIO::Inbound(_) | NodeIO::InboundAny => "READ",
+        NodeIO::Outbound(_, _) | NodeIO::OutboundAny(_) => "WRTE",
+    };
+
+    (exec.acc.to_string(), bak, mode)
+}
+
+fn render_node_gizmos(
+    d: &mut impl RaylibDraw,
+    node_loc: NodeCoord,
+    exec: &Option<NodeExec>,
+    font: &Font,
+    primary: Color,
+    theme: &Theme,
+    layout: &Layout,
+) {
+    let gizmo_width = layout.gizmo_width();
+    let gizmo_height = layout.gizmo_height();
+
+    let (acc, bak, mode) = gizmo_text(exec);
+
+    let placeholder_gizmos = [
+        ("ACC", acc.as_str()),
+        ("BAK", bak.as_str()),
+        ("LAST", "N/A"),
+        ("MODE", mode),
+    ];
+
+    for (i, (top, bottom)) in placeholder_gizmos.into_iter().enumerate() {
+        let gizmos_top_left =
+            node_loc.top_right_corner(layout) - Vector2::new(gizmo_width, i as f32 * -gizmo_height);
+
+        let left_right = Vector2::new(gizmo_width, 0.0);
+        let top_down = Vector2::new(0.0, gizmo_height);
+
+        // draws a rectangle out of individual lines
+        // doing this makes the lines centered, rather than aligned to the outside
+        d.draw_line_ex(
+            gizmos_top_left,
+            gizmos_top_left + left_right,
+            LINE_THICKNESS,
+            primary,
+        );
+        d.draw_line_ex(
+            gizmos_top_left,
+            gizmos_top_left + top_down,
+            LINE_THICKNESS,
+            primary,
+        );
+        d.draw_line_ex(
+            gizmos_top_left + left_right,
+            gizmos_top_left + left_right + top_down,
+            LINE_THICKNESS,
+            primary,
+        );
+        d.draw_line_ex(
+            gizmos_top_left + top_down,
+            gizmos_top_left + top_down + left_right,
+            LINE_THICKNESS,
+            primary,
+        );
+
+        let text_center = gizmos_top_left + Vector2::new(gizmo_width / 2., gizmo_height / 2.);
+        let text_offset = Vector2::new(0.0, NODE_LINE_HEIGHT / 2.0);
+        let top_text = text_center - text_offset;
+        let bottom_text = text_center + text_offset;
+
+        render_centered_text(d, top, top_text, font, theme.dim_text);
+        render_centered_text(d, bottom, bottom_text, font, theme.text);
+    }
+}
+
+fn render_cursor(
+    d: &mut impl RaylibDraw,
+    node_loc: NodeCoord,
+    node: &ExecNode,
+    theme: &Theme,
+    layout: &Layout,
+) {
+    let (line, column) = line_column(&node.text, node.cursor);
+    let (shown_lines, shown_columns) = node.shown(layout);
+
+    // scrolled out of view, which only lasts until it's moved
+    if !shown_lines.contains(&line) || !(shown_columns.start..=shown_columns.end).contains(&column)
+    {
+        return;
+    }
+
+    let x_offset = (column - shown_columns.start) as f32 * NODE_CHAR_WIDTH;
+
+    let cursor_top =
+        node_loc.line_pos(line - shown_lines.start, layout) + Vector2::new(x_offset, 0.);
+    let cursor_bottom = cursor_top + Vector2::new(0., NODE_LINE_HEIGHT);
+
+    d.draw_line_ex(cursor_top, cursor_bottom, LINE_THICKNESS, theme.text);
+}
+
+/// underlines the whole line, or just the token starting at `column` if there is one
+fn render_squiggle(
+    d: &mut impl RaylibDraw,
+    node_loc: NodeCoord,
+    node: &ExecNode,
+    line_no: u8,
+    column: Option<u8>,
+    color: Color,
+    layout: &Layout,
+) {
+    let (shown_lines, shown_columns) = node.shown(layout);
+
+    let Some(line) = node.text.lines().nth(line_no as usize) else {
+        return;
+    };
+
+    if !shown_lines.contains(&(line_no as usize)) {
+        return;
+    }
+
+    let (start, len) = match column {
+        Some(column) => {
+            let token = line.chars().skip(column as usize);
+
+            (
+                column as usize,
+                token.take_while(|char| !char.is_ascii_whitespace()).count(),
+            )
+        }
+        None => (0, line.chars().count()),
+    };
+
+    // only as much of it as is scrolled into view
+    let clip =
+        |column: usize| column.clamp(shown_columns.start, shown_columns.end) - shown_columns.start;
+    let (start, len) = (clip(start), clip(start + len) - clip(start));
+
+    let squiggle_start = node_loc.line_pos(line_no as usize - shown_lines.start, layout)
+        + Vector2::new(start as f32 * NODE_CHAR_WIDTH, NODE_LINE_HEIGHT);
+    let squiggle_end = squiggle_start + Vector2::new(len as f32 * NODE_CHAR_WIDTH, 0.0);
+
+    d.draw_line_ex(squiggle_start, squiggle_end, LINE_THICKNESS, color);
+}
+
+fn render_io_arrow(
+    d: &mut impl RaylibDraw,
+    node_loc: &NodeCoord,
+    dir: Dir,
+    label: &str,
+    font: &Font,
+    theme: &Theme,
+    layout: &Layout,
+) {
+    let indicator_center = node_loc.io_indicator(dir, layout);
+
+    let component_offset = dir
+        .rotate_right()
+        .normalized()
+        .scale_by(1. / 3. * NODE_OUTSIDE_PADDING);
+
+    let arrow_center = indicator_center - component_offset;
+
+    render_arrow(d, arrow_center, dir, theme.text);
+
+    let (text_center, scale) = io_label_placement(arrow_center, dir, label);
+    let font_size = NODE_FONT_SIZE * scale;
+    let spacing = NODE_FONT_SPACING * scale;
+
+    let text_size = font.measure_text(label, font_size, spacing);
+
+    d.draw_text_ex(
+        font,
+        label,
+        text_center - text_size.scale_by(0.5),
+        font_size,
+        spacing,
+        theme.text,
+    );
+}
+
+/// the room left between an IO arrow's label and the borders or the arrow next to it
+const IO_LABEL_MARGIN: f32 = 4.0;
+
+/// how much room the label takes up when it's drawn at `scale` times the node font size
+fn io_label_size(label: &str, scale: f32) -> Vector2 {
+    Vector2::new(
+        label.chars().count() as f32 * NODE_CHAR_WIDTH,
+        NODE_LINE_HEIGHT,
+    )
+    .scale_by(scale)
+}
+
+/// the center of an IO arrow's label, and how much it's scaled down by.
+/// the label goes just to the side of the arrow, and is shrunk if it's too long
+/// to fit in the gap between the two nodes without touching their borders
+fn io_label_placement(arrow_center: Vector2, dir: Dir, label: &str) -> (Vector2, f32) {
+    let horizontal = matches!(dir, Dir::Left | Dir::Right);
+
+    // across the gap for arrows pointing left or right, along it for the others
+    let room = NODE_OUTSIDE_PADDING - 2.0 * (LINE_THICKNESS + IO_LABEL_MARGIN);
+
+    let full_size = io_label_size(label, 1.0);
+    let across = if horizontal { full_size.x } else { full_size.y };
+
+    let scale = (room / across).min(1.0);
+    let size = io_label_size(label, scale);
+
+    // the arrow's wings reach `NODE_LINE_HEIGHT` out to the side of its center
+    let beside = if horizontal { size.y } else { size.x };
+    let offset = NODE_LINE_HEIGHT + IO_LABEL_MARGIN + beside / 2.0;
+
+    let side = dir.rotate_right().normalized();
+
+    (arrow_center + side.scale_by(offset), scale)
+}
+
+fn render_dashed_line(
+    d: &mut impl RaylibDraw,
+    start_pos: Vector2,
+    end_pos: Vector2,
+    color: Color,
+    dashes: usize,
+) {
+    let dash_len = start_pos.distance_to(end_pos) / (2 * dashes + 1) as f32;
+
+    let dash_tail = (end_pos - start_pos).normalized().scale_by(dash_len);
+
+    for dash_no in 0..=dashes {
+        let dash_start = start_pos + dash_tail.scale_by(2.0 * dash_no as f32);
+        d.draw_line_ex(dash_start, dash_start + dash_tail, LINE_THICKNESS, color);
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+enum Dir {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Dir {
+    const ALL: [Self; 4] = [Dir::Up, Dir::Down, Dir::Left, Dir::Right];
+
+    fn normalized(&self) -> Vector2 {
+        match self {
+            Dir::Up => Vector2::new(0.0, -1.0),
+            Dir::Down => Vector2::new(0.0, 1.0),
+            Dir::Left => Vector2::new(-1.0, 0.0),
+            Dir::Right => Vector2::new(1.0, 0.0),
+        }
+    }
+
+    fn inverse(&self) -> Self {
+        match self {
+            Dir::Up => Dir::Down,
+            Dir::Down => Dir::Up,
+            Dir::Left => Dir::Right,
+            Dir::Right => Dir::Left,
+        }
+    }
+
+    fn rotate_right(&self) -> Self {
+        match self {
+            Dir::Left => Dir::Up,
+            Dir::Up => Dir::Right,
+            Dir::Right => Dir::Down,
+            Dir::Down => Dir::Left,
+        }
+    }
+}
+
+fn render_plus(d: &mut impl RaylibDraw, center: Vector2, color: Color) {
+    d.draw_line_ex(
+        center + Vector2::new(-NODE_LINE_HEIGHT, 0.0),
+        center + Vector2::new(NODE_LINE_HEIGHT, 0.0),
+        LINE_THICKNESS,
+        color,
+    );
+    d.draw_line_ex(
+        center + Vector2::new(0.0, -NODE_LINE_HEIGHT),
+        center + Vector2::new(0.0, NODE_LINE_HEIGHT),
+        LINE_THICKNESS,
+        color,
+    );
+}
+
+fn render_arrow(d: &mut impl RaylibDraw, center: Vector2, direction: Dir, color: Color) {
+    render_sized_arrow(d, center, direction, NODE_LINE_HEIGHT, color);
+}
+
+/// `half_length` is the distance from the center to the tip
+fn render_sized_arrow(
+    d: &mut impl RaylibDraw,
+    center: Vector2,
+    direction: Dir,
+    half_length: f32,
+    color: Color,
+) {
+    let dir_vec = direction.normalized();
+
+    let arrow_tip = center + dir_vec.scale_by(half_length);
+    let arrow_base = center - dir_vec.scale_by(half_length);
+
+    let arrow_left_wing = center
+        + dir_vec
+            .scale_by(half_length)
+            .rotated((1.0 / 4.0) * f32::consts::TAU);
+
+    let arrow_right_wing = center
+        + dir_vec
+            .scale_by(half_length)
+            .rotated(-(1.0 / 4.0) * f32::consts::TAU);
+
+    d.draw_line_ex(arrow_base, arrow_tip, LINE_THICKNESS, color);
+    d.draw_line_ex(arrow_tip, arrow_left_wing, LINE_THICKNESS, color);
+    d.draw_line_ex(arrow_tip, arrow_right_wing, LINE_THICKNESS, color);
+}
+
+fn render_double_arrow(d: &mut impl RaylibDraw, center: Vector2, direction: Dir, color: Color) {
+    let dir_vec = direction.normalized();
+
+    let half_arrow_stem = dir_vec.scale_by(NODE_LINE_HEIGHT);
+
+    let arrow_tip = center + half_arrow_stem;
+    let arrow_base = center - half_arrow_stem;
+
+    let arrow_left_wing = center
+        + dir_vec
+            .scale_by(NODE_LINE_HEIGHT)
+            .rotated((1.0 / 4.0) * f32::consts::TAU);
+
+    let arrow_right_wing = center
+        + dir_vec
+            .scale_by(NODE_LINE_HEIGHT)
+            .rotated(-(1.0 / 4.0) * f32::consts::TAU);
+
+    d.draw_line_ex(arrow_base, arrow_tip, LINE_THICKNESS, color);
+    d.draw_line_ex(arrow_tip, arrow_left_wing, LINE_THICKNESS, color);
+    d.draw_line_ex(arrow_tip, arrow_right_wing, LINE_THICKNESS, color);
+
+    d.draw_line_ex(
+        arrow_tip,
+        arrow_left_wing + half_arrow_stem,
+        LINE_THICKNESS,
+        color,
+    );
+    d.draw_line_ex(
+        arrow_tip,
+        arrow_right_wing + half_arrow_stem,
+        LINE_THICKNESS,
+        color,
+    );
+}
+
+fn render_node_border(
+    d: &mut impl RaylibDraw,
+    node_loc: NodeCoord,
+    line_color: Color,
+    layout: &Layout,
+) {
+    d.draw_line_ex(
+        node_loc.top_left_corner(layout),
+        node_loc.top_right_corner(layout),
+        LINE_THICKNESS,
+        line_color,
+    );
+    d.draw_line_ex(
+        node_loc.top_left_corner(layout),
+        node_loc.bottom_left_corner(layout),
+        LINE_THICKNESS,
+        line_color,
+    );
+    d.draw_line_ex(
+        node_loc.bottom_left_corner(layout),
+        node_loc.bottom_right_corner(layout),
+        LINE_THICKNESS,
+        line_color,
+    );
+    d.draw_line_ex(
+        node_loc.top_right_corner(layout),
+        node_loc.bottom_right_corner(layout),
+        LINE_THICKNESS,
+        line_color,
+    );
+}
+
+/// a second border just outside the box's own, so that an error doesn't rely on its color alone
+fn render_error_outline(
+    d: &mut impl RaylibDraw,
+    top_left: Vector2,
+    bottom_right: Vector2,
+    color: Color,
+) {
+    const GAP: f32 = 3.0 * LINE_THICKNESS;
+
+    let size = bottom_right - top_left;
+
+    d.draw_rectangle_lines_ex(
+        Rectangle::new(
+            top_left.x - GAP,
+            top_left.y - GAP,
+            size.x + 2.0 * GAP,
+            size.y + 2.0 * GAP,
+        ),
+        LINE_THICKNESS,
+        color,
+    );
+}
+
+fn render_centered_text(
+    d: &mut impl RaylibDraw,
+    text: &str,
+    center: Vector2,
+    font: &Font,
+    color: Color,
+) {
+    let text_size = font.measure_text(text, NODE_FONT_SIZE, NODE_FONT_SPACING);
+
+    let top_left = center - text_size.scale_by(0.5);
+
+    d.draw_text_ex(
+        font,
+        text,
+        top_left,
+        NODE_FONT_SIZE,
+        NODE_FONT_SPACING,
+        color,
+    );
+}
+
+fn render_dashed_node_border(
+    d: &mut impl RaylibDraw,
+    node_loc: NodeCoord,
+    line_color: Color,
+    layout: &Layout,
+) {
+    render_dashed_line(
+        d,
+        node_loc.top_left_corner(layout),
+        node_loc.top_right_corner(layout),
+        line_color,
+        GHOST_NODE_DASHES,
+    );
+    render_dashed_line(
+        d,
+        node_loc.top_left_corner(layout),
+        node_loc.bottom_left_corner(layout),
+        line_color,
+        GHOST_NODE_DASHES,
+    );
+    render_dashed_line(
+        d,
+        node_loc.bottom_left_corner(layout),
+        node_loc.bottom_right_corner(layout),
+        line_color,
+        GHOST_NODE_DASHES,
+    );
+    render_dashed_line(
+        d,
+        node_loc.top_right_corner(layout),
+        node_loc.bottom_right_corner(layout),
+        line_color,
+        GHOST_NODE_DASHES,
+    );
+}
+
+#[derive(Clone, Debug)]
+struct Input {
+    mods: Modifiers,
+    pressed: Option<Key>,
+    window_dimensions: (i32, i32),
+    mouse_wheel_move: f32,
+    /// in screen space
+    mouse_position: Vector2,
+    clicked: bool,
+    drag: Option<Drag>,
+    /// as it is on the system clipboard, or empty if it isn't plain ASCII
+    clipboard: String,
+}
+
+/// the left mouse button being held down, in screen space
+#[derive(Clone, Copy, Debug)]
+struct Drag {
+    /// where the button went down
+    start: Vector2,
+    end: Vector2,
+    /// the button was let go this frame, so this is the last of the drag
+    released: bool,
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+enum Key {
+    Esc,
+    Tab,
+    Backspace,
+    Enter,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Delete,
+    Arrow(Dir),
+    Char(char),
+    /// the function keys, F1 through F12
+    F(u8),
+}
+
+impl Key {
+    fn from(raylib_key: KeyboardKey, shift_held: bool) -> Option<Self> {
+        let unbound = None;
+        let handled_elsewhere = None;
+
+        let case = if shift_held { 1 } else { 0 };
+
+        use KeyboardKey as RK;
+
+        Some(
+            match raylib_key {
+                // symbols
+                RK::KEY_NULL => return None,
+                RK::KEY_APOSTROPHE => [Key::Char('\''), Key::Char('"')],
+                RK::KEY_COMMA => [Key::Char(','), Key::Char('<')],
+                RK::KEY_MINUS => [Key::Char('-'), Key::Char('_')],
+                RK::KEY_PERIOD => [Key::Char('.'), Key::Char('>')],
+                RK::KEY_SLASH => [Key::Char('/'), Key::Char('?')],
+                RK::KEY_SEMICOLON => [Key::Char(';'), Key::Char(':')],
+                RK::KEY_EQUAL => [Key::Char('='), Key::Char('+')],
+                // number keys
+                RK::KEY_ZERO => [Key::Char('0'), Key::Char(')')],
+                RK::KEY_ONE => [Key::Char('1'), Key::Char('!')],
                 RK::KEY_TWO => [Key::Char('2'), Key::Char('@')],
                 RK::KEY_THREE => [Key::Char('3'), Key::Char('#')],
                 RK::KEY_FOUR => [Key::Char('4'), Key::Char('$')],
@@ -1332,8 +4124,8 @@ impl Key {
                 RK::KEY_LEFT => [Key::Arrow(Dir::Left), Key::Arrow(Dir::Left)],
                 RK::KEY_DOWN => [Key::Arrow(Dir::Down), Key::Arrow(Dir::Down)],
                 RK::KEY_UP => [Key::Arrow(Dir::Up), Key::Arrow(Dir::Up)],
-                RK::KEY_PAGE_UP => return unbound,
-                RK::KEY_PAGE_DOWN => return unbound,
+                RK::KEY_PAGE_UP => [Key::PageUp, Key::PageUp],
+                RK::KEY_PAGE_DOWN => [Key::PageDown, Key::PageDown],
                 RK::KEY_HOME => [Key::Home, Key::Home],
                 RK::KEY_END => [Key::End, Key::End],
                 RK::KEY_CAPS_LOCK => return unbound,
@@ -1342,39 +4134,39 @@ impl Key {
                 RK::KEY_PRINT_SCREEN => return unbound,
                 RK::KEY_PAUSE => return unbound,
                 // f-keys
-                RK::KEY_F1 => return unbound,
-                RK::KEY_F2 => return unbound,
-                RK::KEY_F3 => return unbound,
-                RK::KEY_F4 => return unbound,
-                RK::KEY_F5 => return unbound,
-                RK::KEY_F6 => return unbound,
-                RK::KEY_F7 => return unbound,
-                RK::KEY_F8 => return unbound,
-                RK::KEY_F9 => return unbound,
-                RK::KEY_F10 => return unbound,
-                RK::KEY_F11 => return unbound,
-                RK::KEY_F12 => return unbound,
+                RK::KEY_F1 => [Key::F(1), Key::F(1)],
+                RK::KEY_F2 => [Key::F(2), Key::F(2)],
+                RK::KEY_F3 => [Key::F(3), Key::F(3)],
+                RK::KEY_F4 => [Key::F(4), Key::F(4)],
+                RK::KEY_F5 => [Key::F(5), Key::F(5)],
+                RK::KEY_F6 => [Key::F(6), Key::F(6)],
+                RK::KEY_F7 => [Key::F(7), Key::F(7)],
+                RK::KEY_F8 => [Key::F(8), Key::F(8)],
+                RK::KEY_F9 => [Key::F(9), Key::F(9)],
+                RK::KEY_F10 => [Key::F(10), Key::F(10)],
+                RK::KEY_F11 => [Key::F(11), Key::F(11)],
+                RK::KEY_F12 => [Key::F(12), Key::F(12)],
                 // modifiers
                 RK::KEY_LEFT_SHIFT => return handled_elsewhere,
                 RK::KEY_LEFT_CONTROL => return handled_elsewhere,
-                RK::KEY_LEFT_ALT => return unbound,
+                RK::KEY_LEFT_ALT => return handled_elsewhere,
                 RK::KEY_LEFT_SUPER => return unbound,
                 RK::KEY_RIGHT_SHIFT => return handled_elsewhere,
                 RK::KEY_RIGHT_CONTROL => return handled_elsewhere,
-                RK::KEY_RIGHT_ALT => return unbound,
+                RK::KEY_RIGHT_ALT => return handled_elsewhere,
                 RK::KEY_RIGHT_SUPER => return unbound,
                 RK::KEY_KB_MENU => return unbound,
                 // keypad
                 RK::KEY_KP_0 => return unbound,
                 RK::KEY_KP_1 => [Key::End, Key::End],
                 RK::KEY_KP_2 => return unbound,
-                RK::KEY_KP_3 => return unbound,
+                RK::KEY_KP_3 => [Key::PageDown, Key::PageDown],
                 RK::KEY_KP_4 => return unbound,
                 RK::KEY_KP_5 => return unbound,
                 RK::KEY_KP_6 => return unbound,
                 RK::KEY_KP_7 => [Key::Home, Key::Home],
                 RK::KEY_KP_8 => return unbound,
-                RK::KEY_KP_9 => return unbound,
+                RK::KEY_KP_9 => [Key::PageUp, Key::PageUp],
                 RK::KEY_KP_DECIMAL => return unbound,
                 RK::KEY_KP_DIVIDE => return unbound,
                 RK::KEY_KP_MULTIPLY => return unbound,
@@ -1382,1327 +4174,8255 @@ impl Key {
                 RK::KEY_KP_ADD => return unbound,
                 RK::KEY_KP_ENTER => return unbound,
                 RK::KEY_KP_EQUAL => return unbound,
-                // unknown
-                RK::KEY_BACK => todo!("what key is this?"),
+                // android's back button, there's nothing sensible for it to do here
+                RK::KEY_BACK => return unbound,
                 // volume
                 RK::KEY_VOLUME_UP => return unbound,
                 RK::KEY_VOLUME_DOWN => return unbound,
             }[case],
         )
     }
-}
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+enum Action {
+    Stop,
+    Step,
+    StepBack,
+    FastForward,
+    StepUntilChange,
+    Navigate(Dir),
+    MoveNode(Dir),
+    DeleteNode,
+    DeleteCluster,
+    NewWorkspace,
+    SelectAll,
+    Copy,
+    CopyWorkspace,
+    Cut,
+    Paste,
+    Open,
+    Save,
+    RenameNode,
+    FitAll,
+    FindReplace,
+    ToggleComment,
+    OpenLineBelow,
+    OpenLineAbove,
+    ExportJson,
+    ToggleHelp,
+    ToggleBreakpoint,
+    NextNode,
+    PreviousNode,
+    GoTo,
+    SnapToNearest,
+    NextError,
+    ToggleStats,
+    ToggleGrid,
+    ToggleIoLog,
+    MoveToOrigin,
+    Poke,
+    Screenshot,
+    ResetNode,
+    MirrorSelection,
+    ToggleDanglingIo,
+}
+
+impl Action {
+    const ALL: [Self; 46] = [
+        Action::Stop,
+        Action::Step,
+        Action::StepBack,
+        Action::FastForward,
+        Action::StepUntilChange,
+        Action::Navigate(Dir::Up),
+        Action::Navigate(Dir::Down),
+        Action::Navigate(Dir::Left),
+        Action::Navigate(Dir::Right),
+        Action::MoveNode(Dir::Up),
+        Action::MoveNode(Dir::Down),
+        Action::MoveNode(Dir::Left),
+        Action::MoveNode(Dir::Right),
+        Action::DeleteNode,
+        Action::DeleteCluster,
+        Action::NewWorkspace,
+        Action::SelectAll,
+        Action::Copy,
+        Action::CopyWorkspace,
+        Action::Cut,
+        Action::Paste,
+        Action::Open,
+        Action::Save,
+        Action::RenameNode,
+        Action::FitAll,
+        Action::FindReplace,
+        Action::ToggleComment,
+        Action::OpenLineBelow,
+        Action::OpenLineAbove,
+        Action::ExportJson,
+        Action::ToggleHelp,
+        Action::ToggleBreakpoint,
+        Action::NextNode,
+        Action::PreviousNode,
+        Action::GoTo,
+        Action::SnapToNearest,
+        Action::NextError,
+        Action::ToggleStats,
+        Action::ToggleGrid,
+        Action::ToggleIoLog,
+        Action::MoveToOrigin,
+        Action::Poke,
+        Action::Screenshot,
+        Action::ResetNode,
+        Action::MirrorSelection,
+        Action::ToggleDanglingIo,
+    ];
+
+    /// the name used to refer to this action in the keymap file
+    fn name(&self) -> &'static str {
+        match self {
+            Action::Stop => "stop",
+            Action::Step => "step",
+            Action::StepBack => "step_back",
+            Action::FastForward => "fast_forward",
+            Action::StepUntilChange => "step_until_change",
+            Action::Navigate(Dir::Up) => "move_up",
+            Action::Navigate(Dir::Down) => "move_down",
+            Action::Navigate(Dir::Left) => "move_left",
+            Action::Navigate(Dir::Right) => "move_right",
+            Action::MoveNode(Dir::Up) => "move_node_up",
+            Action::MoveNode(Dir::Down) => "move_node_down",
+            Action::MoveNode(Dir::Left) => "move_node_left",
+            Action::MoveNode(Dir::Right) => "move_node_right",
+            Action::DeleteNode => "delete_node",
+            Action::DeleteCluster => "delete_cluster",
+            Action::NewWorkspace => "new_workspace",
+            Action::SelectAll => "select_all",
+            Action::Copy => "copy",
+            Action::CopyWorkspace => "copy_workspace",
+            Action::Cut => "cut",
+            Action::Paste => "paste",
+            Action::Open => "open",
+            Action::Save => "save",
+            Action::RenameNode => "rename_node",
+            Action::FitAll => "fit_all",
+            Action::FindReplace => "find_replace",
+            Action::ToggleComment => "toggle_comment",
+            Action::OpenLineBelow => "open_line_below",
+            Action::OpenLineAbove => "open_line_above",
+            Action::ExportJson => "export_json",
+            Action::ToggleHelp => "toggle_help",
+            Action::ToggleBreakpoint => "toggle_breakpoint",
+            Action::NextNode => "next_node",
+            Action::PreviousNode => "previous_node",
+            Action::GoTo => "go_to",
+            Action::SnapToNearest => "snap_to_nearest",
+            Action::NextError => "next_error",
+            Action::ToggleStats => "toggle_stats",
+            Action::ToggleGrid => "toggle_grid",
+            Action::ToggleIoLog => "toggle_io_log",
+            Action::MoveToOrigin => "move_to_origin",
+            Action::Poke => "poke",
+            Action::Screenshot => "screenshot",
+            Action::ResetNode => "reset_node",
+            Action::MirrorSelection => "mirror_selection",
+            Action::ToggleDanglingIo => "toggle_dangling_io",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Action::ALL.into_iter().find(|action| action.name() == name)
+    }
+
+    fn default_binding(&self) -> (Modifiers, Key) {
+        match *self {
+            Action::Stop => (Modifiers::None, Key::Esc),
+            Action::Step => (Modifiers::None, Key::Tab),
+            Action::StepBack => (Modifiers::Shift, Key::Tab),
+            Action::FastForward => (Modifiers::Ctrl, Key::Tab),
+            Action::StepUntilChange => (Modifiers::CtrlShift, Key::Tab),
+            Action::Navigate(dir) => (Modifiers::Ctrl, Key::Arrow(dir)),
+            Action::MoveNode(dir) => (Modifiers::CtrlShift, Key::Arrow(dir)),
+            Action::DeleteNode => (Modifiers::Ctrl, Key::Delete),
+            Action::DeleteCluster => (Modifiers::CtrlShift, Key::Delete),
+            Action::NewWorkspace => (Modifiers::Ctrl, Key::Char('N')),
+            Action::SelectAll => (Modifiers::Ctrl, Key::Char('A')),
+            Action::Copy => (Modifiers::Ctrl, Key::Char('C')),
+            Action::CopyWorkspace => (Modifiers::CtrlShift, Key::Char('C')),
+            Action::Cut => (Modifiers::Ctrl, Key::Char('X')),
+            Action::Paste => (Modifiers::Ctrl, Key::Char('V')),
+            Action::Open => (Modifiers::Ctrl, Key::Char('O')),
+            Action::Save => (Modifiers::Ctrl, Key::Char('S')),
+            Action::RenameNode => (Modifiers::None, Key::F(2)),
+            Action::FitAll => (Modifiers::Ctrl, Key::Char('0')),
+            Action::FindReplace => (Modifiers::Ctrl, Key::Char('H')),
+            Action::ToggleComment => (Modifiers::Ctrl, Key::Char('/')),
+            Action::OpenLineBelow => (Modifiers::Ctrl, Key::Enter),
+            Action::OpenLineAbove => (Modifiers::CtrlShift, Key::Enter),
+            Action::ExportJson => (Modifiers::CtrlShift, Key::Char('S')),
+            Action::ToggleHelp => (Modifiers::None, Key::F(1)),
+            Action::ToggleBreakpoint => (Modifiers::Ctrl, Key::Char('B')),
+            Action::NextNode => (Modifiers::None, Key::PageDown),
+            Action::PreviousNode => (Modifiers::None, Key::PageUp),
+            Action::GoTo => (Modifiers::Ctrl, Key::Char('G')),
+            Action::SnapToNearest => (Modifiers::Ctrl, Key::Home),
+            Action::NextError => (Modifiers::None, Key::F(8)),
+            Action::ToggleStats => (Modifiers::None, Key::F(3)),
+            Action::ToggleGrid => (Modifiers::None, Key::F(4)),
+            Action::ToggleIoLog => (Modifiers::None, Key::F(5)),
+            Action::MoveToOrigin => (Modifiers::CtrlShift, Key::Home),
+            Action::Poke => (Modifiers::None, Key::F(9)),
+            Action::Screenshot => (Modifiers::Ctrl, Key::Char('P')),
+            Action::ResetNode => (Modifiers::Ctrl, Key::Char('R')),
+            Action::MirrorSelection => (Modifiers::Ctrl, Key::Char('M')),
+            Action::ToggleDanglingIo => (Modifiers::None, Key::F(6)),
+        }
+    }
+}
+
+const KEYMAP_PATH: &str = "keymap.toml";
+
+/// Maps key combinations to the actions they trigger.
+///
+/// Keys that aren't bound to an action (typing, cursor movement, etc.) are handled directly by `handle_input`.
+#[derive(Clone, Debug)]
+struct Keymap {
+    bindings: HashMap<(Modifiers, Key), Action>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = Action::ALL
+            .into_iter()
+            .map(|action| (action.default_binding(), action))
+            .collect();
+
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    /// Reads a table of `action_name = "Ctrl+Shift+Key"` entries.
+    /// Any action that isn't mentioned, or whose entry can't be understood, keeps its default binding.
+    fn from_toml(toml: &str) -> Self {
+        let mut keymap = Keymap::default();
+
+        let table: Table = match toml::from_str(toml) {
+            Ok(table) => table,
+            Err(_) => {
+                println!("keymap is not valid toml, using default bindings");
+                return keymap;
+            }
+        };
+
+        let rebindings: Vec<_> = table
+            .into_iter()
+            .filter_map(|(name, value)| {
+                let Some(action) = Action::from_name(&name) else {
+                    println!("unknown action in keymap: {name:?}");
+                    return None;
+                };
+
+                let Some(combo) = value.as_str().and_then(parse_key_combo) else {
+                    println!("invalid key combination for {name:?} in keymap: {value}");
+                    return None;
+                };
+
+                Some((action, combo))
+            })
+            .collect();
+
+        // a default binding can be taken over by another action, as long as its own action is
+        // moved somewhere else
+        let moved: HashSet<_> = rebindings.iter().map(|&(action, _)| action).collect();
+        let mut rebound = HashSet::new();
+
+        for (action, combo) in rebindings {
+            if let Some(&bound) = keymap.bindings.get(&combo)
+                && bound != action
+                && (!moved.contains(&bound) || rebound.contains(&bound))
+            {
+                println!(
+                    "{} is already bound to {:?} in keymap, keeping the default for {:?}",
+                    fmt_key_combo(combo.0, combo.1),
+                    bound.name(),
+                    action.name()
+                );
+                continue;
+            }
+
+            keymap.bindings.retain(|_, bound| *bound != action);
+            keymap.bindings.insert(combo, action);
+            rebound.insert(action);
+        }
+
+        keymap
+    }
+
+    fn load() -> Self {
+        match std::fs::read_to_string(KEYMAP_PATH) {
+            Ok(toml) => Keymap::from_toml(&toml),
+            Err(_) => Keymap::default(),
+        }
+    }
+
+    fn action(&self, mods: Modifiers, key: Key) -> Option<Action> {
+        self.bindings.get(&(mods, key)).copied()
+    }
+
+    /// every bound action next to its key combination, in the order of `Action::ALL`
+    fn help_lines(&self) -> Vec<(String, String)> {
+        Action::ALL
+            .iter()
+            // poking only works in debug builds
+            .filter(|&&action| cfg!(debug_assertions) || action != Action::Poke)
+            .filter_map(|action| {
+                let (mods, key) = self
+                    .bindings
+                    .iter()
+                    .find_map(|(combo, bound)| (bound == action).then_some(combo))?;
+
+                Some((fmt_key_combo(*mods, *key), action.name().replace('_', " ")))
+            })
+            .collect()
+    }
+}
+
+/// the inverse of `parse_key_combo`
+fn fmt_key_combo(mods: Modifiers, key: Key) -> String {
+    let mods = match mods {
+        Modifiers::None => "",
+        Modifiers::Ctrl => "Ctrl+",
+        Modifiers::Shift => "Shift+",
+        Modifiers::CtrlShift => "Ctrl+Shift+",
+        Modifiers::Alt => "Alt+",
+        Modifiers::AltShift => "Alt+Shift+",
+    };
+
+    let key = match key {
+        Key::Esc => "Esc".to_string(),
+        Key::Tab => "Tab".to_string(),
+        Key::Backspace => "Backspace".to_string(),
+        Key::Enter => "Enter".to_string(),
+        Key::Home => "Home".to_string(),
+        Key::End => "End".to_string(),
+        Key::PageUp => "PageUp".to_string(),
+        Key::PageDown => "PageDown".to_string(),
+        Key::Delete => "Delete".to_string(),
+        Key::Arrow(Dir::Up) => "Up".to_string(),
+        Key::Arrow(Dir::Down) => "Down".to_string(),
+        Key::Arrow(Dir::Left) => "Left".to_string(),
+        Key::Arrow(Dir::Right) => "Right".to_string(),
+        Key::Char(' ') => "Space".to_string(),
+        Key::Char(char) => char.to_string(),
+        Key::F(n) => format!("F{n}"),
+    };
+
+    format!("{mods}{key}")
+}
+
+fn parse_key_combo(combo: &str) -> Option<(Modifiers, Key)> {
+    let parts: Vec<&str> = combo.split('+').map(str::trim).collect();
+
+    let (key, mods) = parts.split_last()?;
+
+    let (mut ctrl, mut alt, mut shift) = (false, false, false);
+
+    for modifier in mods {
+        match modifier.to_ascii_uppercase().as_str() {
+            "CTRL" => ctrl = true,
+            "ALT" => alt = true,
+            "SHIFT" => shift = true,
+            _ => return None,
+        }
+    }
+
+    let mods = match (ctrl, alt, shift) {
+        (true, true, _) => return None,
+        (true, false, true) => Modifiers::CtrlShift,
+        (true, false, false) => Modifiers::Ctrl,
+        (false, true, true) => Modifiers::AltShift,
+        (false, true, false) => Modifiers::Alt,
+        (false, false, true) => Modifiers::Shift,
+        (false, false, false) => Modifiers::None,
+    };
+
+    let key = match key.to_ascii_uppercase().as_str() {
+        "ESC" => Key::Esc,
+        "TAB" => Key::Tab,
+        "BACKSPACE" => Key::Backspace,
+        "ENTER" => Key::Enter,
+        "HOME" => Key::Home,
+        "END" => Key::End,
+        "PAGEUP" => Key::PageUp,
+        "PAGEDOWN" => Key::PageDown,
+        "DELETE" => Key::Delete,
+        "UP" => Key::Arrow(Dir::Up),
+        "DOWN" => Key::Arrow(Dir::Down),
+        "LEFT" => Key::Arrow(Dir::Left),
+        "RIGHT" => Key::Arrow(Dir::Right),
+        "SPACE" => Key::Char(' '),
+        other if other.len() > 1 && other.starts_with('F') => Key::F(other[1..].parse().ok()?),
+        other => {
+            let mut chars = other.chars();
+
+            match (chars.next(), chars.next()) {
+                (Some(char), None) => Key::Char(char),
+                _ => return None,
+            }
+        }
+    };
+
+    Some((mods, key))
+}
+
+enum RepeatKey {
+    None,
+    Held { key: KeyboardKey, repeat_delay: f32 },
+}
+
+/// how long a key has to be held before it starts repeating, and how long between repeats after that
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct KeyRepeat {
+    delay: f32,
+    interval: f32,
+}
+
+impl Default for KeyRepeat {
+    fn default() -> Self {
+        Self {
+            delay: DEFAULT_KEY_REPEAT_DELAY_S,
+            interval: DEFAULT_KEY_REPEAT_INTERVAL_S,
+        }
+    }
+}
+
+impl KeyRepeat {
+    /// either one that's missing or not a valid time keeps its default
+    fn from_args() -> Self {
+        let seconds = |flag: &str| {
+            std::env::args().find_map(|arg| parse_repeat_seconds(arg.strip_prefix(flag)?))
+        };
+
+        let default = KeyRepeat::default();
+
+        Self {
+            delay: seconds("--repeat-delay=").unwrap_or(default.delay),
+            interval: seconds("--repeat-interval=").unwrap_or(default.interval),
+        }
+    }
+}
+
+/// has to be more than zero, or a held key would repeat every frame
+fn parse_repeat_seconds(text: &str) -> Option<f32> {
+    text.parse::<f32>()
+        .ok()
+        .filter(|seconds| seconds.is_finite() && *seconds > 0.0)
+}
+
+fn get_input(
+    rl: &mut RaylibHandle,
+    repeat: &mut RepeatKey,
+    key_repeat: KeyRepeat,
+    drag_start: &mut Option<Vector2>,
+) -> Input {
+    let ctrl_held = rl.is_key_down(KeyboardKey::KEY_LEFT_CONTROL)
+        || rl.is_key_down(KeyboardKey::KEY_RIGHT_CONTROL);
+
+    let alt_held =
+        rl.is_key_down(KeyboardKey::KEY_LEFT_ALT) || rl.is_key_down(KeyboardKey::KEY_RIGHT_ALT);
+
+    let shift_held =
+        rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT) || rl.is_key_down(KeyboardKey::KEY_RIGHT_SHIFT);
+
+    // Ctrl wins out over Alt, there aren't any bindings that use both
+    let mods = match (ctrl_held, alt_held, shift_held) {
+        (true, _, true) => Modifiers::CtrlShift,
+        (true, _, false) => Modifiers::Ctrl,
+        (false, true, true) => Modifiers::AltShift,
+        (false, true, false) => Modifiers::Alt,
+        (false, false, true) => Modifiers::Shift,
+        (false, false, false) => Modifiers::None,
+    };
+
+    let raylib_key_pressed = rl.get_key_pressed();
+
+    let pressed = if let Some(key) = raylib_key_pressed {
+        *repeat = RepeatKey::Held {
+            key,
+            repeat_delay: key_repeat.delay,
+        };
+        raylib_key_pressed.and_then(|rk| Key::from(rk, shift_held))
+    } else if let RepeatKey::Held { key, repeat_delay } = repeat
+        && rl.is_key_down(*key)
+    {
+        *repeat_delay -= rl.get_frame_time();
+        if *repeat_delay <= 0.0 {
+            *repeat_delay = key_repeat.interval;
+            Key::from(*key, shift_held)
+        } else {
+            None
+        }
+    } else {
+        *repeat = RepeatKey::None;
+        raylib_key_pressed.and_then(|rk| Key::from(rk, shift_held))
+    };
+
+    let clipboard = match rl.get_clipboard_text() {
+        Ok(text) if text.is_ascii() => text,
+
+        Ok(_) | Err(_) => String::new(),
+    };
+
+    let mouse_position = rl.get_mouse_position();
+    let clicked = rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT);
+
+    if clicked {
+        *drag_start = Some(mouse_position);
+    }
+
+    let held = rl.is_mouse_button_down(MouseButton::MOUSE_BUTTON_LEFT);
+
+    let drag = drag_start.map(|start| Drag {
+        start,
+        end: mouse_position,
+        released: !held,
+    });
+
+    if !held {
+        *drag_start = None;
+    }
+
+    Input {
+        mods,
+        pressed,
+        window_dimensions: (rl.get_screen_width(), rl.get_screen_height()),
+        mouse_wheel_move: rl.get_mouse_wheel_move(),
+        mouse_position,
+        clicked,
+        drag,
+        clipboard,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Output {
+    clipboard: Option<String>,
+    /// where to save the frame as an image, once it's been drawn
+    screenshot: Option<PathBuf>,
+}
+
+enum Update<T> {
+    Exit,
+    Update { new: T, output: Output },
+}
+
+impl<T> Update<T> {
+    fn no_output(new: T) -> Self {
+        Update::Update {
+            new,
+            output: Output {
+                clipboard: None,
+                screenshot: None,
+            },
+        }
+    }
+}
+
+fn update(state: State, mut input: Input) -> Update<State> {
+    let highlighted_before = state.model.highlighted_node;
+
+    let mut model = state.model;
+    let lines = input.mouse_wheel_move.round() as isize;
+    let hovered_node = NodeCoord::at_world_pos(
+        screen_to_world(input.mouse_position, state.camera),
+        &state.layout,
+    );
+
+    // the wheel scrolls the IO log, or text that doesn't fit its node, rather than zooming
+    // while the mouse is over it
+    if model.show_io_log
+        && io_log_rect(input.window_dimensions).check_collision_point_rec(input.mouse_position)
+    {
+        model.io_log_scroll = scroll_io_log(model.io_log_scroll, lines, model.io_log.len());
+        input.mouse_wheel_move = 0.0;
+    } else if let Some(Node::Exec(exec_node)) = model.nodes.get_mut(&hovered_node)
+        && exec_node.room(&state.layout).lines > state.layout.lines
+    {
+        exec_node.scroll_by(-lines, &state.layout);
+        input.mouse_wheel_move = 0.0;
+    }
+
+    // an open prompt gets the key instead
+    let action = match input.pressed {
+        Some(pressed) if model.prompt.is_none() => state.keymap.action(input.mods, pressed),
+        _ => None,
+    };
+
+    let model = if input.clicked {
+        let world_pos = screen_to_world(input.mouse_position, state.camera);
+
+        handle_click(
+            model,
+            NodeCoord::at_world_pos(world_pos, &state.layout),
+            input.mods,
+        )
+    } else {
+        model
+    };
+
+    let model = match input.drag {
+        Some(drag) => handle_drag(model, drag, input.mods, state.camera, &state.layout),
+        None => model,
+    };
+
+    match handle_input(
+        model,
+        &input,
+        &state.keymap,
+        &state.layout,
+        &state.exec_template,
+    ) {
+        Update::Exit => {
+            return Update::Exit;
+        }
+
+        Update::Update { mut new, output } => {
+            let camera = if new.view == View::Reset {
+                new.view = View::FollowHighlight;
+                init_camera()
+            } else {
+                state.camera
+            };
+
+            if new.highlighted_node != highlighted_before {
+                new.view = View::FollowHighlight;
+            } else if input.mouse_wheel_move != 0.0 {
+                new.view = View::ZoomedToCursor;
+            }
+
+            let camera = match new.view {
+                View::FollowHighlight | View::Reset => update_camera(
+                    camera,
+                    new.highlighted_node,
+                    input.window_dimensions,
+                    input.mouse_wheel_move,
+                    state.camera_follow,
+                    &state.layout,
+                ),
+
+                View::FitAll => {
+                    fit_all_camera(camera, &new.nodes, input.window_dimensions, &state.layout)
+                }
+
+                View::ZoomedToCursor => zoom_to_cursor(
+                    camera,
+                    input.window_dimensions,
+                    input.mouse_wheel_move,
+                    input.mouse_position,
+                ),
+            };
+
+            Update::Update {
+                new: State {
+                    camera,
+                    model: new,
+                    keymap: state.keymap,
+                    theme: state.theme,
+                    layout: state.layout,
+                    camera_follow: state.camera_follow,
+                    key_repeat: state.key_repeat,
+                    exec_template: state.exec_template,
+                    show_stats: state.show_stats != (action == Some(Action::ToggleStats)),
+                    show_grid: state.show_grid != (action == Some(Action::ToggleGrid)),
+                },
+                output,
+            }
+        }
+    }
+}
+
+/// ties go to whichever comes first in reading order
+fn nearest_node(nodes: &Nodes, from: NodeCoord) -> Option<NodeCoord> {
+    nodes
+        .keys()
+        .copied()
+        .min_by_key(|&node_loc| (node_loc.distance(from), node_loc))
+}
+
+/// the next (or previous) node in reading order, wrapping around at either end
+fn cycle_nodes(nodes: &Nodes, from: NodeCoord, forward: bool) -> Option<NodeCoord> {
+    let mut node_locs: Vec<_> = nodes.keys().copied().collect();
+
+    node_locs.sort();
+
+    if forward {
+        node_locs
+            .iter()
+            .find(|&&node_loc| node_loc > from)
+            .or(node_locs.first())
+            .copied()
+    } else {
+        node_locs
+            .iter()
+            .rfind(|&&node_loc| node_loc < from)
+            .or(node_locs.last())
+            .copied()
+    }
+}
+
+/// the next exec node in reading order whose code doesn't parse, wrapping around at the end
+fn next_error(nodes: &Nodes, from: NodeCoord) -> Option<NodeCoord> {
+    let mut errored: Vec<_> = nodes
+        .iter()
+        .filter_map(|(node_loc, node)| match node {
+            Node::Exec(exec_node) => exec_node.error().is_some().then_some(*node_loc),
+            Node::Input(_) | Node::Output(_) | Node::Random(_) => None,
+        })
+        .collect();
+
+    errored.sort();
+
+    errored
+        .iter()
+        .find(|&&node_loc| node_loc > from)
+        .or(errored.first())
+        .copied()
+}
+
+fn screen_to_world(screen_pos: Vector2, camera: Camera2D) -> Vector2 {
+    (screen_pos - camera.offset) / camera.zoom + camera.target
+}
+
+/// Shift+click adds a node to the selection, or takes it back out. any other click clears it
+fn handle_click(model: Model, clicked_node: NodeCoord, mods: Modifiers) -> Model {
+    let mut selection = model.selection;
+
+    if mods != Modifiers::Shift {
+        selection.clear();
+    } else if !selection.remove(&clicked_node) && model.nodes.contains_key(&clicked_node) {
+        selection.insert(clicked_node);
+    }
+
+    Model { selection, ..model }
+}
+
+/// a drag that starts on an empty cell stretches out a rubber band,
+/// and letting go of it selects every node it touches. Shift adds them to the selection instead
+fn handle_drag(
+    model: Model,
+    drag: Drag,
+    mods: Modifiers,
+    camera: Camera2D,
+    layout: &Layout,
+) -> Model {
+    let start = screen_to_world(drag.start, camera);
+    let end = screen_to_world(drag.end, camera);
+
+    if model
+        .nodes
+        .contains_key(&NodeCoord::at_world_pos(start, layout))
+    {
+        return model;
+    }
+
+    if !drag.released {
+        return Model {
+            rubber_band: Some((start, end)),
+            ..model
+        };
+    }
+
+    let mut selection = model.selection;
+
+    if mods != Modifiers::Shift {
+        selection.clear();
+    }
+
+    selection.extend(nodes_in_rect(&model.nodes, start, end, layout));
+
+    Model {
+        selection,
+        rubber_band: None,
+        ..model
+    }
+}
+
+/// every node whose cell overlaps the rectangle between two opposite corners
+fn nodes_in_rect(nodes: &Nodes, a: Vector2, b: Vector2, layout: &Layout) -> HashSet<NodeCoord> {
+    let min = NodeCoord::at_world_pos(Vector2::new(a.x.min(b.x), a.y.min(b.y)), layout);
+    let max = NodeCoord::at_world_pos(Vector2::new(a.x.max(b.x), a.y.max(b.y)), layout);
+
+    nodes
+        .keys()
+        .filter(|node_loc| {
+            (min.x..=max.x).contains(&node_loc.x) && (min.y..=max.y).contains(&node_loc.y)
+        })
+        .copied()
+        .collect()
+}
+
+/// also stops treating a node as being typed into once it's no longer highlighted,
+/// or can't be typed into anymore. see `Model::editing`
+fn handle_input(
+    model: Model,
+    input: &Input,
+    keymap: &Keymap,
+    layout: &Layout,
+    exec_template: &NodeText,
+) -> Update<Model> {
+    let newest_transfer = model.io_log.back().copied();
+    let cursor_before = text_cursor(&model.nodes, model.highlighted_node);
+
+    match handle_key(model, input, keymap, layout, exec_template) {
+        Update::Update { mut new, output } => {
+            if new.editing != Some(new.highlighted_node)
+                || editable_text(&new.nodes, new.highlighted_node).is_none()
+            {
+                new.editing = None;
+            }
+
+            // follows the cursor when it's moved or typed at, but not when the wheel scrolls away
+            if text_cursor(&new.nodes, new.highlighted_node) != cursor_before
+                && let Some(Node::Exec(exec_node)) = new.nodes.get_mut(&new.highlighted_node)
+            {
+                exec_node.scroll_to_cursor(layout);
+            }
+
+            // back to the bottom to show what just arrived
+            if new.io_log.back().copied() != newest_transfer {
+                new.io_log_scroll = 0;
+            }
+
+            Update::Update { new, output }
+        }
+
+        Update::Exit => Update::Exit,
+    }
+}
+
+/// where the cursor is in which text, for an exec node
+fn text_cursor(nodes: &Nodes, node_loc: NodeCoord) -> Option<(NodeCoord, NodeText, usize)> {
+    match nodes.get(&node_loc) {
+        Some(Node::Exec(exec_node)) => Some((node_loc, exec_node.text, exec_node.cursor)),
+        Some(Node::Input(_) | Node::Output(_) | Node::Random(_)) | None => None,
+    }
+}
+
+/// the text of the node at `node_loc`, if it's an exec node that isn't running
+fn editable_text(nodes: &Nodes, node_loc: NodeCoord) -> Option<NodeText> {
+    match nodes.get(&node_loc) {
+        Some(Node::Exec(exec_node)) if exec_node.is_in_edit_mode() => Some(exec_node.text),
+        Some(Node::Exec(_) | Node::Input(_) | Node::Output(_) | Node::Random(_)) | None => None,
+    }
+}
+
+/// `exec_template` is what new exec nodes start out with
+fn handle_key(
+    model: Model,
+    input: &Input,
+    keymap: &Keymap,
+    layout: &Layout,
+    exec_template: &NodeText,
+) -> Update<Model> {
+    // the old ghosts value should not be reused, this enforces it
+    std::mem::drop(model.ghosts);
+
+    // hint at what the arrow keys would do with the modifiers that are currently held
+    let ghosts = match keymap.action(input.mods, Key::Arrow(Dir::Up)) {
+        Some(Action::Navigate(_)) => Ghosts::MoveView,
+        Some(Action::MoveNode(_)) => Ghosts::MoveNode,
+        _ => Ghosts::None,
+    };
+
+    let Some(pressed) = input.pressed else {
+        return Update::no_output(Model { ghosts, ..model });
+    };
+
+    if let Some(prompt) = model.prompt {
+        return Update::no_output(handle_prompt(
+            Model {
+                ghosts: Ghosts::None,
+                prompt: None,
+                ..model
+            },
+            prompt,
+            input.mods,
+            pressed,
+            layout,
+        ));
+    }
+
+    // closing the help shouldn't also do whatever Esc is bound to
+    if model.show_help && pressed == Key::Esc {
+        return Update::no_output(Model {
+            ghosts,
+            show_help: false,
+            ..model
+        });
+    }
+
+    match (keymap.action(input.mods, pressed), input.mods, pressed) {
+        // a page at a time, taking over from whatever they're bound to while the log is shown
+        (_, Modifiers::None, key @ (Key::PageUp | Key::PageDown)) if model.show_io_log => {
+            let lines = if key == Key::PageUp {
+                IO_LOG_LINES as isize
+            } else {
+                -(IO_LOG_LINES as isize)
+            };
+
+            Update::no_output(Model {
+                io_log_scroll: scroll_io_log(model.io_log_scroll, lines, model.io_log.len()),
+                ghosts,
+                ..model
+            })
+        }
+
+        (Some(Action::ToggleHelp), _, _) => Update::no_output(Model {
+            ghosts,
+            show_help: !model.show_help,
+            ..model
+        }),
+
+        // flipped in `update`, since they're kept in the `State` so a new workspace keeps them
+        (Some(Action::ToggleStats | Action::ToggleGrid), _, _) => {
+            Update::no_output(Model { ghosts, ..model })
+        }
+
+        (Some(Action::ToggleIoLog), _, _) => Update::no_output(Model {
+            ghosts,
+            show_io_log: !model.show_io_log,
+            ..model
+        }),
+
+        (Some(Action::ToggleDanglingIo), _, _) => Update::no_output(Model {
+            ghosts,
+            show_dangling_io: !model.show_dangling_io,
+            ..model
+        }),
+
+        // Esc stops editing before it stops the network (or quits)
+        (Some(Action::Stop), _, _) if model.editing.is_some() => Update::no_output(Model {
+            ghosts,
+            editing: None,
+            ..model
+        }),
+
+        (Some(Action::Stop), _, _) => {
+            if let Some(updated_nodes) =
+                stop_execution(&model.nodes, model.highlighted_node, model.torus)
+            {
+                let mut nodes = model.nodes;
+
+                nodes.extend(updated_nodes);
+
+                Update::no_output(Model {
+                    ghosts,
+                    nodes,
+                    cycle: 0,
+                    history: VecDeque::new(),
+                    watch_cycles: None,
+                    io_log: VecDeque::new(),
+                    ..model
+                })
+            } else {
+                Update::Exit
+            }
+        }
+
+        // Tab and Shift+Tab are bound to stepping, so they're only turned away while typing
+        (_, Modifiers::None | Modifiers::Shift, Key::Tab)
+            if model.editing == Some(model.highlighted_node)
+                && (model.read_only || model.locked.contains(&model.highlighted_node)) =>
+        {
+            Update::no_output(Model { ghosts, ..model })
+        }
+
+        // while typing into a node, Tab indents it rather than stepping the network
+        (_, Modifiers::None, Key::Tab) if model.editing == Some(model.highlighted_node) => {
+            let mut nodes = model.nodes;
+            let mut dirty = model.dirty;
+
+            // a full node is left alone
+            if let Some(Node::Exec(exec_node)) = nodes.get_mut(&model.highlighted_node) {
+                let before = exec_node.text;
+
+                exec_node.indent(layout);
+                dirty |= exec_node.text != before;
+            }
+
+            Update::no_output(Model {
+                nodes,
+                ghosts,
+                dirty,
+                ..model
+            })
+        }
+
+        (_, Modifiers::Shift, Key::Tab) if model.editing == Some(model.highlighted_node) => {
+            let mut nodes = model.nodes;
+            let mut dirty = model.dirty;
+
+            // a line with nothing to take off is left alone
+            if let Some(Node::Exec(exec_node)) = nodes.get_mut(&model.highlighted_node) {
+                let before = exec_node.text;
+
+                exec_node.dedent(layout);
+                dirty |= exec_node.text != before;
+            }
+
+            Update::no_output(Model {
+                nodes,
+                ghosts,
+                dirty,
+                ..model
+            })
+        }
+
+        (Some(Action::Step), _, _) => {
+            if let Some((updated_nodes, report)) =
+                step_execution(&model.nodes, model.highlighted_node, model.torus)
+            {
+                let mut history = model.history;
+                let mut io_log = model.io_log;
+
+                log_transfers(&mut io_log, model.cycle + 1, &report.transfers);
+
+                push_history(
+                    &mut history,
+                    Snapshot {
+                        runtimes: runtimes(&model.nodes),
+                        highlighted_node: model.highlighted_node,
+                    },
+                );
+
+                let mut nodes = model.nodes;
+
+                nodes.extend(updated_nodes);
+
+                Update::no_output(Model {
+                    nodes,
+                    ghosts,
+                    cycle: model.cycle + 1,
+                    history,
+                    watch_cycles: None,
+                    io_log,
+                    ..model
+                })
+            } else {
+                Update::no_output(Model { ghosts, ..model })
+            }
+        }
+
+        // the highlight stays where it is, and so do any edits. only the network is rewound
+        (Some(Action::StepBack), _, _) => {
+            let mut history = model.history;
+
+            match history.pop_back() {
+                Some(snapshot) => {
+                    let cycle = model.cycle.saturating_sub(1);
+
+                    // what arrived in the cycle that was undone didn't happen after all
+                    let mut io_log = model.io_log;
+                    io_log.retain(|&(logged_cycle, _)| logged_cycle <= cycle);
+
+                    let mut nodes = model.nodes;
+                    restore_runtime(&mut nodes, snapshot.runtimes);
+
+                    Update::no_output(Model {
+                        nodes,
+                        ghosts,
+                        cycle,
+                        history,
+                        watch_cycles: None,
+                        io_log,
+                        ..model
+                    })
+                }
+
+                None => Update::no_output(Model {
+                    ghosts,
+                    history,
+                    ..model
+                }),
+            }
+        }
+
+        (Some(Action::FastForward), _, _) => {
+            let mut nodes = model.nodes;
+            let mut history = model.history;
+            let mut io_log = model.io_log;
+
+            let elapsed = run_until(
+                &mut nodes,
+                &mut history,
+                &mut io_log,
+                model.cycle,
+                model.highlighted_node,
+                model.torus,
+                |nodes| {
+                    nodes.values().any(|node| match node {
+                        Node::Exec(exec_node) => exec_node.at_breakpoint(),
+                        Node::Input(_) | Node::Output(_) | Node::Random(_) => false,
+                    })
+                },
+            );
+
+            Update::no_output(Model {
+                nodes,
+                ghosts,
+                cycle: model.cycle + elapsed,
+                history,
+                watch_cycles: None,
+                io_log,
+                ..model
+            })
+        }
+
+        (Some(Action::StepUntilChange), _, _) => {
+            let mut nodes = model.nodes;
+            let mut history = model.history;
+            let mut io_log = model.io_log;
+
+            let before = watched_state(&nodes, model.highlighted_node);
+
+            let elapsed = run_until(
+                &mut nodes,
+                &mut history,
+                &mut io_log,
+                model.cycle,
+                model.highlighted_node,
+                model.torus,
+                |nodes| watched_state(nodes, model.highlighted_node) != before,
+            );
+
+            Update::no_output(Model {
+                nodes,
+                ghosts,
+                cycle: model.cycle + elapsed,
+                history,
+                watch_cycles: Some(elapsed),
+                io_log,
+                ..model
+            })
+        }
+
+        // moving is checked in its own arms, since it can involve more nodes than the highlighted one
+        (
+            Some(
+                Action::DeleteNode
+                | Action::Cut
+                | Action::Paste
+                | Action::RenameNode
+                | Action::ToggleComment
+                | Action::OpenLineBelow
+                | Action::OpenLineAbove
+                | Action::FindReplace,
+            ),
+            _,
+            _,
+        )
+        | (None, Modifiers::None | Modifiers::Shift, Key::Char(_) | Key::Enter)
+        | (None, Modifiers::None, Key::Delete | Key::Backspace)
+            if model.read_only || model.locked.contains(&model.highlighted_node) =>
+        {
+            Update::no_output(Model { ghosts, ..model })
+        }
+
+        // the rest of what changes the network, rather than running it
+        (
+            Some(
+                Action::MoveNode(_)
+                | Action::MoveToOrigin
+                | Action::MirrorSelection
+                | Action::DeleteCluster
+                | Action::NewWorkspace,
+            ),
+            _,
+            _,
+        ) if model.read_only => Update::no_output(Model { ghosts, ..model }),
+
+        (None, mods @ (Modifiers::None | Modifiers::Shift), Key::Arrow(dir)) => {
+            let mut nodes = model.nodes;
+            match nodes.get_mut(&model.highlighted_node) {
+                Some(Node::Exec(exec_node)) => {
+                    let select = mods == Modifiers::Shift;
+
+                    match dir {
+                        Dir::Up => exec_node.up(select),
+                        Dir::Down => exec_node.down(select),
+                        Dir::Left => exec_node.left(select),
+                        Dir::Right => exec_node.right(select),
+                    };
+
+                    if !select {
+                        exec_node.deselect();
+                    }
+
+                    Update::no_output(Model {
+                        nodes,
+                        ghosts,
+                        ..model
+                    })
+                }
+
+                None | Some(Node::Input(_) | Node::Output(_) | Node::Random(_)) => {
+                    Update::no_output(Model {
+                        nodes,
+                        ghosts,
+                        ..model
+                    })
+                }
+            }
+        }
+
+        (Some(Action::Navigate(dir)), _, _) => Update::no_output(Model {
+            highlighted_node: model.highlighted_node.neighbor(dir),
+            ghosts,
+            ..model
+        }),
+
+        (Some(action @ (Action::NextNode | Action::PreviousNode)), _, _) => {
+            let forward = action == Action::NextNode;
+
+            Update::no_output(Model {
+                highlighted_node: cycle_nodes(&model.nodes, model.highlighted_node, forward)
+                    .unwrap_or(model.highlighted_node),
+                ghosts,
+                ..model
+            })
+        }
+
+        (Some(Action::SnapToNearest), _, _) => Update::no_output(Model {
+            highlighted_node: nearest_node(&model.nodes, model.highlighted_node)
+                .unwrap_or(model.highlighted_node),
+            ghosts,
+            ..model
+        }),
+
+        (Some(Action::NextError), _, _) => Update::no_output(Model {
+            highlighted_node: next_error(&model.nodes, model.highlighted_node)
+                .unwrap_or(model.highlighted_node),
+            ghosts,
+            ..model
+        }),
+
+        // the rest of the network carries on as it was
+        (Some(Action::ResetNode), _, _) => {
+            let mut nodes = model.nodes;
+
+            if let Some(Node::Exec(exec_node)) = nodes.get_mut(&model.highlighted_node) {
+                exec_node.reset_runtime();
+            }
+
+            Update::no_output(Model {
+                nodes,
+                ghosts,
+                ..model
+            })
+        }
+
+        // a debugging aid, so release builds leave it out
+        (Some(Action::Poke), _, _) if cfg!(debug_assertions) => Update::no_output(Model {
+            ghosts,
+            prompt: Some(Prompt::Poke {
+                text: PromptText::new(),
+                invalid: false,
+            }),
+            ..model
+        }),
+
+        (Some(Action::Poke), _, _) => Update::no_output(Model { ghosts, ..model }),
+
+        (Some(Action::GoTo), _, _) => Update::no_output(Model {
+            ghosts,
+            prompt: Some(Prompt::GoTo {
+                text: PromptText::new(),
+                invalid: false,
+            }),
+            ..model
+        }),
+
+        // locked cells are pinned where the puzzle put them, so nothing moves if there are any
+        (Some(Action::MoveToOrigin), _, _) => {
+            let Some((dx, dy)) = origin_offset(&model.nodes).filter(|_| model.locked.is_empty())
+            else {
+                return Update::no_output(Model { ghosts, ..model });
+            };
+
+            let shift = |node_loc: NodeCoord| NodeCoord::at(node_loc.x + dx, node_loc.y + dy);
+
+            Update::no_output(Model {
+                nodes: model
+                    .nodes
+                    .into_iter()
+                    .map(|(node_loc, node)| (shift(node_loc), node))
+                    .collect(),
+                ghosts,
+                highlighted_node: shift(model.highlighted_node),
+                selection: model.selection.into_iter().map(shift).collect(),
+                torus: model.torus.map(|Torus { min, max }| Torus {
+                    min: shift(min),
+                    max: shift(max),
+                }),
+                dirty: model.dirty || (dx, dy) != (0, 0),
+                ..model
+            })
+        }
+
+        // flips the selection left to right within its own bounding box.
+        // like moving, it's refused if it would land on a node that isn't selected, or a locked cell
+        (Some(Action::MirrorSelection), _, _) => {
+            let mut nodes = model.nodes;
+
+            let selection: HashSet<_> = model
+                .selection
+                .into_iter()
+                .filter(|node_loc| nodes.contains_key(node_loc))
+                .collect();
+
+            let Some(mirror) = mirror_horizontally(&selection) else {
+                return Update::no_output(Model {
+                    nodes,
+                    ghosts,
+                    selection,
+                    ..model
+                });
+            };
+
+            let collides = selection.iter().any(|&node_loc| {
+                let dst = mirror(node_loc);
+                nodes.contains_key(&dst) && !selection.contains(&dst)
+            });
+
+            let locked = selection.iter().any(|&node_loc| {
+                model.locked.contains(&node_loc) || model.locked.contains(&mirror(node_loc))
+            });
+
+            if collides || locked {
+                return Update::no_output(Model {
+                    nodes,
+                    ghosts,
+                    selection,
+                    ..model
+                });
+            }
+
+            let moved: Vec<_> = selection
+                .iter()
+                .map(|&node_loc| (mirror(node_loc), nodes.remove(&node_loc).unwrap()))
+                .collect();
+
+            nodes.extend(moved);
+
+            let highlighted_node = if selection.contains(&model.highlighted_node) {
+                mirror(model.highlighted_node)
+            } else {
+                model.highlighted_node
+            };
+
+            Update::no_output(Model {
+                nodes,
+                ghosts,
+                highlighted_node,
+                selection: selection.iter().map(|&node_loc| mirror(node_loc)).collect(),
+                dirty: true,
+                ..model
+            })
+        }
+
+        (Some(Action::MoveNode(dir)), _, _) if !model.selection.is_empty() => {
+            let mut nodes = model.nodes;
+
+            // selected cells might have been emptied since they were selected
+            let selection: HashSet<_> = model
+                .selection
+                .into_iter()
+                .filter(|node_loc| nodes.contains_key(node_loc))
+                .collect();
+
+            let collides = selection.iter().any(|node_loc| {
+                let dst = node_loc.neighbor(dir);
+                nodes.contains_key(&dst) && !selection.contains(&dst)
+            });
+
+            let locked = selection.iter().any(|node_loc| {
+                model.locked.contains(node_loc) || model.locked.contains(&node_loc.neighbor(dir))
+            });
+
+            if collides || locked || selection.is_empty() {
+                return Update::no_output(Model {
+                    nodes,
+                    ghosts,
+                    selection,
+                    ..model
+                });
+            }
+
+            // everything is taken out first, so that nodes can move into each other's old spots
+            let moved: Vec<_> = selection
+                .iter()
+                .map(|node_loc| (node_loc.neighbor(dir), nodes.remove(node_loc).unwrap()))
+                .collect();
+
+            nodes.extend(moved);
+
+            let highlighted_node = if selection.contains(&model.highlighted_node) {
+                model.highlighted_node.neighbor(dir)
+            } else {
+                model.highlighted_node
+            };
+
+            Update::no_output(Model {
+                nodes,
+                ghosts,
+                highlighted_node,
+                selection: selection
+                    .iter()
+                    .map(|node_loc| node_loc.neighbor(dir))
+                    .collect(),
+                dirty: true,
+                ..model
+            })
+        }
+
+        (Some(Action::MoveNode(dir)), _, _) => {
+            let mut nodes = model.nodes;
+            let src = model.highlighted_node;
+            let dst = model.highlighted_node.neighbor(dir);
+            if nodes.contains_key(&src)
+                && !nodes.contains_key(&dst)
+                && !model.locked.contains(&src)
+                && !model.locked.contains(&dst)
+            {
+                let node = nodes.remove(&src).unwrap();
+
+                nodes.try_insert(dst, node).unwrap();
+
+                Update::no_output(Model {
+                    nodes,
+                    ghosts,
+                    highlighted_node: dst,
+                    dirty: true,
+                    ..model
+                })
+            } else {
+                Update::no_output(Model {
+                    nodes,
+                    ghosts,
+                    ..model
+                })
+            }
+        }
+
+        (None, Modifiers::None, Key::Delete) => {
+            let mut nodes = model.nodes;
+            let mut dirty = model.dirty;
+            let mut editing = model.editing;
+
+            match nodes.get_mut(&model.highlighted_node) {
+                Some(Node::Exec(exec_node)) if exec_node.is_in_edit_mode() => {
+                    let before = exec_node.text;
+
+                    exec_node.delete_forward(layout);
+                    dirty |= exec_node.text != before;
+                    editing = Some(model.highlighted_node);
+                }
+
+                // the text of a running node can't be changed
+                Some(Node::Exec(_)) => {}
+
+                Some(Node::Input(_) | Node::Output(_) | Node::Random(_)) => {
+                    nodes.remove(&model.highlighted_node);
+                    dirty = true;
+                }
+
+                None => {}
+            }
+
+            Update::no_output(Model {
+                nodes,
+                ghosts,
+                dirty,
+                editing,
+                ..model
+            })
+        }
+
+        (Some(Action::DeleteNode), _, _) => {
+            let mut nodes = model.nodes;
+
+            let dirty = nodes.remove(&model.highlighted_node).is_some() || model.dirty;
+
+            Update::no_output(Model {
+                nodes,
+                ghosts,
+                dirty,
+                ..model
+            })
+        }
+
+        (Some(Action::DeleteCluster), _, _) => Update::no_output(Model {
+            ghosts,
+            prompt: model
+                .nodes
+                .contains_key(&model.highlighted_node)
+                .then_some(Prompt::DeleteCluster),
+            ..model
+        }),
+
+        (Some(Action::NewWorkspace), _, _) if model.dirty => Update::no_output(Model {
+            ghosts,
+            prompt: Some(Prompt::NewWorkspace),
+            ..model
+        }),
+
+        (Some(Action::NewWorkspace), _, _) => Update::no_output(Model {
+            ghosts,
+            view: View::Reset,
+            ..Model::empty()
+        }),
+
+        (Some(Action::SelectAll), _, _) => {
+            let mut nodes = model.nodes;
+
+            if let Some(Node::Exec(exec_node)) = nodes.get_mut(&model.highlighted_node) {
+                exec_node.select_all();
+
+                Update::no_output(Model {
+                    nodes,
+                    ghosts,
+                    ..model
+                })
+            } else {
+                Update::no_output(Model {
+                    nodes,
+                    ghosts,
+                    ..model
+                })
+            }
+        }
+
+        // takes the place of any copied node, so pasting doesn't prefer that instead
+        (Some(Action::CopyWorkspace), _, _) => {
+            let toml = serialize_toml(
+                &model.nodes,
+                Some(model.highlighted_node),
+                &model.locked,
+                model.torus,
+            );
+
+            Update::Update {
+                new: Model {
+                    ghosts,
+                    node_clipboard: None,
+                    ..model
+                },
+                output: Output {
+                    clipboard: Some(toml),
+                    screenshot: None,
+                },
+            }
+        }
+
+        (Some(Action::Copy), _, _) => {
+            if let Some(node) = model.nodes.get(&model.highlighted_node) {
+                match node {
+                    Node::Exec(exec_node) if exec_node.text_selected() => {
+                        let selection = exec_node.selection().to_string();
+                        Update::Update {
+                            new: Model {
+                                ghosts,
+                                node_clipboard: None,
+                                ..model
+                            },
+                            output: Output {
+                                clipboard: Some(selection),
+                                screenshot: None,
+                            },
+                        }
+                    }
+
+                    Node::Exec(exec_node) => {
+                        let node_text = exec_node.text.to_string();
+
+                        Update::Update {
+                            new: Model {
+                                ghosts,
+                                node_clipboard: Some(node.clone()),
+                                ..model
+                            },
+                            output: Output {
+                                clipboard: Some(node_text),
+                                screenshot: None,
+                            },
+                        }
+                    }
+
+                    Node::Input(input_node) => {
+                        let data = fmt_nums(input_node.data());
+
+                        Update::Update {
+                            new: Model {
+                                ghosts,
+                                node_clipboard: Some(node.clone()),
+                                ..model
+                            },
+                            output: Output {
+                                clipboard: Some(data),
+                                screenshot: None,
+                            },
+                        }
+                    }
+
+                    Node::Output(output_node) => {
+                        let received = fmt_nums(output_node.received());
+
+                        Update::Update {
+                            new: Model {
+                                ghosts,
+                                node_clipboard: Some(node.clone()),
+                                ..model
+                            },
+                            output: Output {
+                                clipboard: Some(received),
+                                screenshot: None,
+                            },
+                        }
+                    }
+
+                    Node::Random(random_node) => {
+                        let seed = random_node.seed.to_string();
+
+                        Update::Update {
+                            new: Model {
+                                ghosts,
+                                node_clipboard: Some(node.clone()),
+                                ..model
+                            },
+                            output: Output {
+                                clipboard: Some(seed),
+                                screenshot: None,
+                            },
+                        }
+                    }
+                }
+            } else {
+                Update::no_output(Model { ghosts, ..model })
+            }
+        }
+
+        (Some(Action::Cut), _, _) => {
+            let mut nodes = model.nodes;
+
+            match nodes.entry(model.highlighted_node) {
+                Entry::Vacant(_) => Update::no_output(Model {
+                    ghosts,
+                    nodes,
+                    ..model
+                }),
+
+                Entry::Occupied(mut entry) => match entry.get_mut() {
+                    Node::Exec(exec_node)
+                        if exec_node.text_selected() && !exec_node.is_in_edit_mode() =>
+                    {
+                        Update::no_output(Model {
+                            ghosts,
+                            nodes,
+                            ..model
+                        })
+                    }
+
+                    Node::Exec(exec_node) if exec_node.text_selected() => {
+                        let selection = exec_node.selection().to_string();
+
+                        exec_node.insert("", layout);
+
+                        Update::Update {
+                            new: Model {
+                                ghosts,
+                                nodes,
+                                dirty: true,
+                                ..model
+                            },
+                            output: Output {
+                                clipboard: Some(selection),
+                                screenshot: None,
+                            },
+                        }
+                    }
+
+                    Node::Exec(_) | Node::Input(_) | Node::Output(_) | Node::Random(_) => {
+                        let cut_node = entry.remove();
+
+                        Update::no_output(Model {
+                            ghosts,
+                            nodes,
+                            node_clipboard: Some(cut_node),
+                            dirty: true,
+                            ..model
+                        })
+                    }
+                },
+            }
+        }
+
+        (Some(Action::Paste), _, _) => {
+            let mut nodes = model.nodes;
+
+            match (&model.node_clipboard, nodes.entry(model.highlighted_node)) {
+                (Some(copied_node), Entry::Vacant(vacant_entry)) => {
+                    vacant_entry.insert(copied_node.clone());
+
+                    Update::no_output(Model {
+                        nodes,
+                        ghosts,
+                        dirty: true,
+                        ..model
+                    })
+                }
+
+                (_, Entry::Occupied(mut occupied_entry)) => match occupied_entry.get_mut() {
+                    Node::Exec(exec_node) if exec_node.is_in_edit_mode() => {
+                        exec_node.insert(&input.clipboard.to_ascii_uppercase(), layout);
+
+                        Update::no_output(Model {
+                            ghosts,
+                            nodes,
+                            dirty: true,
+                            ..model
+                        })
+                    }
+
+                    Node::Exec(_) | Node::Input(_) | Node::Output(_) | Node::Random(_) => {
+                        Update::no_output(Model {
+                            ghosts,
+                            nodes,
+                            ..model
+                        })
+                    }
+                },
+
+                (None, Entry::Vacant(vacant_entry)) => {
+                    let mut dirty = model.dirty;
+
+                    if let Some(input_node) = InputNode::from_data_str(&input.clipboard) {
+                        vacant_entry.insert(Node::Input(input_node));
+                        dirty = true;
+                    } else if let Ok((pasted, _)) = parse_toml(&input.clipboard)
+                        && let Some(merged) =
+                            paste_workspace(&nodes, pasted, model.highlighted_node)
+                    {
+                        nodes = merged;
+                        dirty = true;
+                    }
+
+                    Update::no_output(Model {
+                        ghosts,
+                        nodes,
+                        dirty,
+                        ..model
+                    })
+                }
+            }
+        }
+
+        (Some(Action::Open), _, _) => {
+            if let Some(path) = rfd::FileDialog::new()
+                .set_title("Load TIS workspace from file")
+                .add_filter("TIS workspace", &["toml"])
+                .add_filter("TIS-100 save", &["txt"])
+                .pick_file()
+            {
+                let is_tis100_save = path.extension().is_some_and(|ext| ext == "txt");
+
+                let import = |contents: &str| {
+                    if is_tis100_save {
+                        import_tis100(contents, TIS100_GRID_WIDTH).map(|nodes| Puzzle {
+                            nodes,
+                            highlighted_node: NodeCoord::at(0, 0),
+                            locked: HashSet::new(),
+                            torus: None,
+                        })
+                    } else {
+                        parse_puzzle(contents, path.parent())
+                    }
+                };
+
+                match std::fs::read_to_string(&path) {
+                    Ok(contents) => match import(&contents) {
+                        // a TIS-100 save isn't a workspace, so it can't be saved back over
+                        Ok(puzzle) => Update::no_output(Model {
+                            nodes: puzzle.nodes,
+                            highlighted_node: puzzle.highlighted_node,
+                            locked: puzzle.locked,
+                            torus: puzzle.torus,
+                            cycle: 0,
+                            history: VecDeque::new(),
+                            watch_cycles: None,
+                            io_log: VecDeque::new(),
+                            ghosts,
+                            dirty: is_tis100_save,
+                            current_path: (!is_tis100_save).then_some(path),
+                            ..model
+                        }),
+
+                        Err(import_err) => {
+                            let origin = NodeCoord::at(0, 0);
+                            let description = format!("# {}", import_err.to_str());
+
+                            let node =
+                                Node::exec_with_lines(["## ERROR", "", &description], layout)
+                                    .unwrap();
+
+                            let nodes = Nodes::from([(origin, node)]);
+
+                            Update::no_output(Model {
+                                nodes,
+                                ghosts,
+                                locked: HashSet::new(),
+                                torus: None,
+                                cycle: 0,
+                                history: VecDeque::new(),
+                                watch_cycles: None,
+                                io_log: VecDeque::new(),
+                                ..model
+                            })
+                        }
+                    },
+
+                    Err(_) => {
+                        let origin = NodeCoord::at(0, 0);
+
+                        let node = Node::exec_with_lines(
+                            ["## ERROR", "", "# COULD NOT OPEN", "# SPECIFIED FILE"],
+                            layout,
+                        )
+                        .unwrap();
+
+                        let nodes = Nodes::from([(origin, node)]);
+
+                        Update::no_output(Model {
+                            nodes,
+                            ghosts,
+                            locked: HashSet::new(),
+                            torus: None,
+                            cycle: 0,
+                            history: VecDeque::new(),
+                            watch_cycles: None,
+                            io_log: VecDeque::new(),
+                            ..model
+                        })
+                    }
+                }
+            } else {
+                Update::no_output(Model { ghosts, ..model })
+            }
+        }
+
+        (Some(Action::Save), _, _) => {
+            Update::no_output(save_with_dialog(Model { ghosts, ..model }))
+        }
+
+        (Some(Action::ExportJson), _, _) => {
+            let model = Model { ghosts, ..model };
+
+            if let Some(path) = rfd::FileDialog::new()
+                .set_title("Export TIS network as JSON")
+                .add_filter("JSON", &["json"])
+                .set_file_name("my_tis_network.json")
+                .save_file()
+                && let Err(err) = std::fs::write(path, to_json(&model))
+            {
+                // TODO: show this to the user
+                println!("io error while exporting json: {:?}", err);
+            }
+
+            Update::no_output(model)
+        }
+
+        // the frame can only be grabbed once it's been drawn, so that's left to the main loop
+        (Some(Action::Screenshot), _, _) => Update::Update {
+            output: Output {
+                clipboard: None,
+                screenshot: rfd::FileDialog::new()
+                    .set_title("Save screenshot")
+                    .add_filter("PNG image", &["png"])
+                    .set_file_name("tis_network.png")
+                    .save_file(),
+            },
+            new: Model { ghosts, ..model },
+        },
+
+        (None, Modifiers::None | Modifiers::Shift, Key::Char(char)) => {
+            let mut nodes = model.nodes;
+            let mut dirty = model.dirty;
+            let mut editing = model.editing;
+
+            match nodes.entry(model.highlighted_node) {
+                Entry::Occupied(mut occupied) => {
+                    match occupied.get_mut() {
+                        Node::Exec(exec_node) if exec_node.is_in_edit_mode() => {
+                            // apparently this is the easiest way to turn a `char` into a `&str`
+                            // (without allocating a single-char `String` first`)
+                            let mut buf = [0; std::mem::size_of::<char>()];
+
+                            exec_node.insert(char.encode_utf8(&mut buf), layout);
+                            dirty = true;
+                            editing = Some(model.highlighted_node);
+                        }
+
+                        // typing digits edits the seed, while the node isn't running
+                        Node::Random(random_node)
+                            if random_node.state.is_none() && char.is_ascii_digit() =>
+                        {
+                            let digit = char.to_digit(10).unwrap();
+
+                            if let Some(seed) = random_node
+                                .seed
+                                .checked_mul(10)
+                                .and_then(|seed| seed.checked_add(digit))
+                            {
+                                random_node.seed = seed;
+                                dirty = true;
+                            }
+                        }
+
+                        // < and > sort the data, R reverses it
+                        Node::Input(input_node) if matches!(char, '<' | '>' | 'R') => {
+                            match char {
+                                '<' => input_node.sort(true),
+                                '>' => input_node.sort(false),
+                                _ => input_node.reverse(),
+                            }
+
+                            dirty = true;
+                        }
+
+                        Node::Exec(_) | Node::Input(_) | Node::Output(_) | Node::Random(_) => {
+                            // TODO: handle direct node input?
+                        }
+                    }
+                }
+
+                Entry::Vacant(vacant) => match char {
+                    'E' => {
+                        // the template was checked against the layout when it was loaded
+                        vacant.insert(
+                            Node::exec_with_text(exec_template, layout)
+                                .unwrap_or_else(|_| Node::empty_exec()),
+                        );
+                        dirty = true;
+                    }
+                    'I' => {
+                        vacant.insert(Node::empty_input());
+                        dirty = true;
+                    }
+                    'O' => {
+                        vacant.insert(Node::empty_output());
+                        dirty = true;
+                    }
+                    'R' => {
+                        vacant.insert(Node::random(0));
+                        dirty = true;
+                    }
+                    _ => {}
+                },
+            }
+
+            Update::no_output(Model {
+                nodes,
+                ghosts,
+                dirty,
+                editing,
+                ..model
+            })
+        }
+
+        (None, mods @ (Modifiers::None | Modifiers::Shift), Key::Home) => {
+            let mut nodes = model.nodes;
+
+            if let Some(Node::Exec(exec_node)) = nodes.get_mut(&model.highlighted_node) {
+                let select = mods == Modifiers::Shift;
+
+                exec_node.home(select);
+
+                if !select {
+                    exec_node.deselect();
+                }
+            }
+
+            Update::no_output(Model {
+                nodes,
+                ghosts,
+                ..model
+            })
+        }
+
+        (None, mods @ (Modifiers::None | Modifiers::Shift), Key::End) => {
+            let mut nodes = model.nodes;
+
+            if let Some(Node::Exec(exec_node)) = nodes.get_mut(&model.highlighted_node) {
+                let select = mods == Modifiers::Shift;
+
+                exec_node.end(select);
+
+                if !select {
+                    exec_node.deselect();
+                }
+            }
+
+            Update::no_output(Model {
+                nodes,
+                ghosts,
+                ..model
+            })
+        }
+
+        (None, Modifiers::None, Key::Backspace) => {
+            let mut nodes = model.nodes;
+            let mut dirty = model.dirty;
+            let mut editing = model.editing;
+
+            if let Some(Node::Exec(exec_node)) = nodes.get_mut(&model.highlighted_node)
+                && exec_node.is_in_edit_mode()
+            {
+                let before = exec_node.text;
+
+                exec_node.backspace(layout);
+                dirty |= exec_node.text != before;
+                editing = Some(model.highlighted_node);
+            }
+
+            Update::no_output(Model {
+                nodes,
+                ghosts,
+                dirty,
+                editing,
+                ..model
+            })
+        }
+
+        (None, mods @ (Modifiers::None | Modifiers::Shift), Key::Enter) => {
+            let mut nodes = model.nodes;
+            let mut dirty = model.dirty;
+            let mut editing = model.editing;
+
+            if let Some(Node::Exec(exec_node)) = nodes.get_mut(&model.highlighted_node)
+                && exec_node.is_in_edit_mode()
+            {
+                let select = mods == Modifiers::Shift;
+                let before = exec_node.text;
+
+                exec_node.enter(select, layout);
+                dirty |= exec_node.text != before;
+                editing = Some(model.highlighted_node);
+            }
+
+            Update::no_output(Model {
+                nodes,
+                ghosts,
+                dirty,
+                editing,
+                ..model
+            })
+        }
+
+        (Some(Action::RenameNode), _, _) => {
+            let prompt = model
+                .nodes
+                .get(&model.highlighted_node)
+                .map(|node| Prompt::RenameNode(node.name().copied().unwrap_or_default()));
+
+            Update::no_output(Model {
+                ghosts,
+                prompt,
+                ..model
+            })
+        }
+
+        (Some(Action::FitAll), _, _) => Update::no_output(Model {
+            ghosts,
+            view: View::FitAll,
+            ..model
+        }),
+
+        (Some(action @ (Action::OpenLineBelow | Action::OpenLineAbove)), _, _) => {
+            let mut nodes = model.nodes;
+            let mut dirty = model.dirty;
+
+            if let Some(Node::Exec(exec_node)) = nodes.get_mut(&model.highlighted_node)
+                && exec_node.is_in_edit_mode()
+            {
+                let before = exec_node.text;
+
+                if action == Action::OpenLineBelow {
+                    exec_node.open_line_below(layout);
+                } else {
+                    exec_node.open_line_above(layout);
+                }
+
+                // a node that's already as tall as it can be is left alone
+                dirty |= exec_node.text != before;
+            }
+
+            Update::no_output(Model {
+                nodes,
+                ghosts,
+                dirty,
+                ..model
+            })
+        }
+
+        (Some(Action::ToggleComment), _, _) => {
+            let mut nodes = model.nodes;
+            let mut dirty = model.dirty;
+
+            if let Some(Node::Exec(exec_node)) = nodes.get_mut(&model.highlighted_node)
+                && exec_node.is_in_edit_mode()
+            {
+                let before = exec_node.text;
+
+                exec_node.toggle_comment(layout);
+                dirty |= exec_node.text != before;
+            }
+
+            Update::no_output(Model {
+                nodes,
+                ghosts,
+                dirty,
+                ..model
+            })
+        }
+
+        (Some(Action::ToggleBreakpoint), _, _) => {
+            let mut nodes = model.nodes;
+
+            if let Some(Node::Exec(exec_node)) = nodes.get_mut(&model.highlighted_node) {
+                exec_node.toggle_breakpoint();
+            }
+
+            Update::no_output(Model {
+                nodes,
+                ghosts,
+                ..model
+            })
+        }
+
+        (Some(Action::FindReplace), _, _) => {
+            let prompt = match model.nodes.get(&model.highlighted_node) {
+                Some(Node::Exec(exec_node)) if exec_node.is_in_edit_mode() => {
+                    // start off searching for whatever is selected, if it makes sense to
+                    let find = PromptText::from(exec_node.selection())
+                        .ok()
+                        .filter(|selection| !selection.contains('\n'))
+                        .unwrap_or_default();
+
+                    Some(Prompt::FindReplace {
+                        find,
+                        replacement: PromptText::new(),
+                        editing_replacement: false,
+                    })
+                }
+
+                Some(Node::Exec(_) | Node::Input(_) | Node::Output(_) | Node::Random(_)) | None => {
+                    None
+                }
+            };
+
+            Update::no_output(Model {
+                ghosts,
+                prompt,
+                ..model
+            })
+        }
+
+        (
+            None,
+            mods @ (Modifiers::Alt | Modifiers::AltShift),
+            Key::Arrow(dir @ (Dir::Left | Dir::Right)),
+        ) => {
+            let mut nodes = model.nodes;
+
+            if let Some(Node::Exec(exec_node)) = nodes.get_mut(&model.highlighted_node) {
+                let select = mods == Modifiers::AltShift;
+
+                if dir == Dir::Left {
+                    exec_node.word_left(select);
+                } else {
+                    exec_node.word_right(select);
+                }
+            }
+
+            Update::no_output(Model {
+                nodes,
+                ghosts,
+                ..model
+            })
+        }
+
+        (
+            None,
+            Modifiers::Ctrl | Modifiers::CtrlShift | Modifiers::Alt | Modifiers::AltShift,
+            _,
+        )
+        | (None, _, Key::F(_))
+        | (None, Modifiers::None | Modifiers::Shift, Key::Esc | Key::Tab)
+        | (None, Modifiers::None | Modifiers::Shift, Key::PageUp | Key::PageDown)
+        | (None, Modifiers::Shift, Key::Backspace | Key::Delete) => {
+            Update::no_output(Model { ghosts, ..model })
+        }
+    }
+}
+
+/// the highlighted node's cluster, as far as deleting it goes: locked nodes stay, even if they're
+/// part of it
+fn removable_cluster(model: &Model) -> HashSet<NodeCoord> {
+    let mut cluster = connected_nodes(&model.nodes, model.highlighted_node, model.torus);
+
+    cluster.retain(|node_loc| !model.locked.contains(node_loc));
+
+    cluster
+}
+
+/// asks where to save, then writes the workspace there, or exports it if the extension says to.
+/// only saving the workspace itself clears `dirty`
+fn save_with_dialog(model: Model) -> Model {
+    let Some(path) = rfd::FileDialog::new()
+        .set_title("Save TIS workspace to file")
+        .add_filter("TIS workspace", &["toml"])
+        .add_filter("TIS-100 save", &["txt"])
+        .add_filter("Output node values", &["csv"])
+        .set_file_name("my_tis_workspace.toml")
+        .save_file()
+    else {
+        return model;
+    };
+
+    let is_export = path
+        .extension()
+        .is_some_and(|ext| ext == "csv" || ext == "txt");
+
+    let contents = if path.extension().is_some_and(|ext| ext == "csv") {
+        export_outputs_csv(&model.nodes)
+    } else if path.extension().is_some_and(|ext| ext == "txt") {
+        let (save, skipped) = export_tis100(&model.nodes, TIS100_GRID_WIDTH);
+
+        for node_loc in skipped {
+            // TODO: show this to the user
+            println!(
+                "node at {} has no TIS-100 equivalent, skipping it",
+                fmt_coord(&node_loc)
+            );
+        }
+
+        save
+    } else {
+        serialize_toml(
+            &model.nodes,
+            Some(model.highlighted_node),
+            &model.locked,
+            model.torus,
+        )
+    };
+
+    match std::fs::write(&path, contents) {
+        Ok(()) if is_export => model,
+
+        Ok(()) => Model {
+            dirty: false,
+            current_path: Some(path),
+            ..model
+        },
+
+        Err(err) => {
+            // TODO: show this to the user
+            println!("io error while saving file: {:?}", err);
+            model
+        }
+    }
+}
+
+/// `model.prompt` is expected to have been taken out and passed in as `prompt`.
+/// It is put back if the key doesn't finish the prompt.
+fn handle_prompt(
+    model: Model,
+    prompt: Prompt,
+    mods: Modifiers,
+    pressed: Key,
+    layout: &Layout,
+) -> Model {
+    match (prompt, mods, pressed) {
+        (_, _, Key::Esc) => model,
+
+        (Prompt::RenameNode(name), _, Key::Enter) => {
+            let mut nodes = model.nodes;
+
+            let mut dirty = model.dirty;
+
+            if let Some(node) = nodes.get_mut(&model.highlighted_node) {
+                let name = name.trim();
+
+                node.set_name((!name.is_empty()).then(|| NodeName::from(name).unwrap()));
+                dirty = true;
+            }
+
+            Model {
+                nodes,
+                dirty,
+                ..model
+            }
+        }
+
+        (Prompt::RenameNode(mut name), Modifiers::None | Modifiers::Shift, Key::Char(char)) => {
+            // the name just stops growing once it's full
+            let _ = name.try_push(char);
+
+            Model {
+                prompt: Some(Prompt::RenameNode(name)),
+                ..model
+            }
+        }
+
+        (Prompt::RenameNode(mut name), _, Key::Backspace) => {
+            name.pop();
+
+            Model {
+                prompt: Some(Prompt::RenameNode(name)),
+                ..model
+            }
+        }
+
+        (prompt @ Prompt::RenameNode(_), _, _) => Model {
+            prompt: Some(prompt),
+            ..model
+        },
+
+        (Prompt::DeleteCluster, _, Key::Enter) => {
+            let cluster = removable_cluster(&model);
+            let mut nodes = model.nodes;
+
+            nodes.retain(|node_loc, _| !cluster.contains(node_loc));
+
+            Model {
+                nodes,
+                dirty: model.dirty || !cluster.is_empty(),
+                ..model
+            }
+        }
+
+        (Prompt::NewWorkspace, _, Key::Enter) => Model {
+            view: View::Reset,
+            ..Model::empty()
+        },
+
+        // the changes are only thrown away once they've made it into a file
+        (Prompt::NewWorkspace, Modifiers::None | Modifiers::Shift, Key::Char('S')) => {
+            let model = save_with_dialog(model);
+
+            if model.dirty {
+                model
+            } else {
+                Model {
+                    view: View::Reset,
+                    ..Model::empty()
+                }
+            }
+        }
+
+        // anything else backs out, so a stray key can't confirm it
+        (Prompt::DeleteCluster | Prompt::NewWorkspace, _, _) => model,
+
+        (Prompt::GoTo { text, .. }, _, Key::Enter) => match parse_go_to(&text) {
+            Ok(highlighted_node) => Model {
+                highlighted_node,
+                ..model
+            },
+
+            Err(_) => Model {
+                prompt: Some(Prompt::GoTo {
+                    text,
+                    invalid: true,
+                }),
+                ..model
+            },
+        },
+
+        (Prompt::GoTo { mut text, invalid }, mods, key) => {
+            let edited = edit_prompt_text(&mut text, mods, key);
+
+            Model {
+                prompt: Some(Prompt::GoTo {
+                    text,
+                    invalid: invalid && !edited,
+                }),
+                ..model
+            }
+        }
+
+        (Prompt::Poke { text, .. }, _, Key::Enter) => {
+            let mut nodes = model.nodes;
+
+            let poked = match (parse_poke(&text), nodes.get_mut(&model.highlighted_node)) {
+                (
+                    Some((value, dir)),
+                    Some(Node::Exec(ExecNode {
+                        exec: Some(exec), ..
+                    })),
+                ) => exec.poke(dir, value),
+                _ => false,
+            };
+
+            Model {
+                nodes,
+                prompt: (!poked).then_some(Prompt::Poke {
+                    text,
+                    invalid: true,
+                }),
+                ..model
+            }
+        }
+
+        (Prompt::Poke { mut text, invalid }, mods, key) => {
+            let edited = edit_prompt_text(&mut text, mods, key);
+
+            Model {
+                prompt: Some(Prompt::Poke {
+                    text,
+                    invalid: invalid && !edited,
+                }),
+                ..model
+            }
+        }
+
+        (
+            Prompt::FindReplace {
+                find,
+                replacement,
+                editing_replacement: true,
+            },
+            _,
+            Key::Enter,
+        ) => {
+            let mut nodes = model.nodes;
+
+            let mut dirty = model.dirty;
+
+            if let Some(Node::Exec(exec_node)) = nodes.get_mut(&model.highlighted_node) {
+                let before = exec_node.text;
+
+                exec_node.replace_all(&find, &replacement, layout);
+                dirty |= exec_node.text != before;
+            }
+
+            Model {
+                nodes,
+                dirty,
+                ..model
+            }
+        }
+
+        (
+            Prompt::FindReplace {
+                mut find,
+                mut replacement,
+                editing_replacement,
+            },
+            mods,
+            key,
+        ) => {
+            let field = if editing_replacement {
+                &mut replacement
+            } else {
+                &mut find
+            };
+
+            let editing_replacement = match (mods, key) {
+                (_, Key::Enter | Key::Tab) => !editing_replacement,
+
+                (Modifiers::None | Modifiers::Shift, Key::Char(char)) => {
+                    let _ = field.try_push(char);
+                    editing_replacement
+                }
+
+                (_, Key::Backspace) => {
+                    field.pop();
+                    editing_replacement
+                }
+
+                _ => editing_replacement,
+            };
+
+            Model {
+                prompt: Some(Prompt::FindReplace {
+                    find,
+                    replacement,
+                    editing_replacement,
+                }),
+                ..model
+            }
+        }
+    }
+}
+
+/// every node that can be reached from `starting_node` through neighbors, including itself
+fn connected_nodes(
+    nodes: &Nodes,
+    starting_node: NodeCoord,
+    torus: Option<Torus>,
+) -> HashSet<NodeCoord> {
+    let found = seek_nodes(
+        nodes,
+        Nodes::new(),
+        starting_node,
+        torus,
+        &mut |old_nodes, mut new_nodes, node_loc| match old_nodes.get(&node_loc) {
+            Some(node) if !new_nodes.contains_key(&node_loc) => {
+                new_nodes.insert(node_loc, node.clone());
+                Ok(new_nodes)
+            }
+            _ => Err(new_nodes),
+        },
+    );
+
+    match found {
+        Ok(found) => found.into_keys().collect(),
+        Err(_) => HashSet::new(),
+    }
+}
+
+fn stop_execution(nodes: &Nodes, starting_node: NodeCoord, torus: Option<Torus>) -> Option<Nodes> {
+    let new_nodes = Nodes::new();
+
+    match seek_nodes(
+        nodes,
+        new_nodes,
+        starting_node,
+        torus,
+        &mut stop_node_execution,
+    ) {
+        Ok(new_nodes) => Some(new_nodes),
+        Err(_) => None,
+    }
+}
+
+fn stop_node_execution(
+    old_nodes: &Nodes,
+    mut new_nodes: Nodes,
+    node_loc: NodeCoord,
+) -> Result<Nodes, Nodes> {
+    let Some(mut node) = old_nodes.get(&node_loc).cloned() else {
+        return Err(new_nodes);
+    };
+
+    if new_nodes.contains_key(&node_loc) {
+        return Err(new_nodes);
+    }
+
+    match &mut node {
+        Node::Exec(exec_node) => {
+            if exec_node.exec.is_some() {
+                exec_node.exec = None;
+                new_nodes.insert(node_loc, node);
+                Ok(new_nodes)
+            } else {
+                Err(new_nodes)
+            }
+        }
+
+        Node::Input(input_node) => {
+            if input_node.stop() {
+                new_nodes.insert(node_loc, node);
+                Ok(new_nodes)
+            } else {
+                Err(new_nodes)
+            }
+        }
+
+        Node::Output(output_node) => {
+            if output_node.received.is_some() {
+                output_node.received = None;
+                new_nodes.insert(node_loc, node);
+                Ok(new_nodes)
+            } else {
+                Err(new_nodes)
+            }
+        }
+
+        Node::Random(random_node) => {
+            if random_node.state.is_some() {
+                random_node.state = None;
+                new_nodes.insert(node_loc, node);
+                Ok(new_nodes)
+            } else {
+                Err(new_nodes)
+            }
+        }
+    }
+}
+
+/// what happened during a single step of the network
+#[derive(Debug, Default)]
+struct StepReport {
+    /// exec nodes that started, or ran an instruction that didn't have to wait on a read
+    executed: Vec<NodeCoord>,
+    transfers: Vec<Transfer>,
+    /// exec nodes that are waiting on IO or halted once the step is done
+    blocked: Vec<NodeCoord>,
+    status: NetworkStatus,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Transfer {
+    from: NodeCoord,
+    to: NodeCoord,
+    value: Num,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+enum NetworkStatus {
+    #[default]
+    Running,
+    /// nothing happened, and nothing is waiting on anything either
+    Quiescent,
+    /// nothing happened, because every node that could do something is waiting on IO
+    Deadlocked,
+}
+
+/// `torus` is the box the network wraps around in, if it does
+fn step_execution(
+    nodes: &Nodes,
+    starting_node: NodeCoord,
+    torus: Option<Torus>,
+) -> Option<(Nodes, StepReport)> {
+    let new_nodes = Nodes::new();
+    let mut report = StepReport::default();
+
+    let new_nodes = seek_nodes(
+        nodes,
+        new_nodes,
+        starting_node,
+        torus,
+        &mut |old_nodes, new_nodes, node_loc| {
+            step_node_execution(old_nodes, new_nodes, node_loc, torus, &mut report)
+        },
+    )
+    .ok()?;
+
+    let mut waiting = false;
+
+    for (node_loc, node) in &new_nodes {
+        if let Node::Exec(ExecNode {
+            exec: Some(exec), ..
+        }) = node
+            && exec.is_blocked()
+        {
+            report.blocked.push(*node_loc);
+            waiting |= !exec.halted;
+        }
+    }
+
+    report.executed.sort();
+    report.blocked.sort();
+
+    report.status = if !report.executed.is_empty() || !report.transfers.is_empty() {
+        NetworkStatus::Running
+    } else if waiting {
+        NetworkStatus::Deadlocked
+    } else {
+        NetworkStatus::Quiescent
+    };
+
+    Some((new_nodes, report))
+}
+
+/// visits the nodes connected to `start_loc` depth first, going into the neighbors of
+/// every node `transform` accepts. it's a loop rather than recursion,
+/// so a long enough chain of nodes can't overflow the stack
+fn seek_nodes(
+    old_nodes: &Nodes,
+    mut new_nodes: Nodes,
+    start_loc: NodeCoord,
+    torus: Option<Torus>,
+    transform: &mut impl FnMut(&Nodes, Nodes, NodeCoord) -> Result<Nodes, Nodes>,
+) -> Result<Nodes, Nodes> {
+    new_nodes = transform(old_nodes, new_nodes, start_loc)?;
+
+    let push_neighbors = |stack: &mut Vec<NodeCoord>, node_loc: NodeCoord| {
+        // reversed, so they're popped in the order of `Dir::ALL`
+        for neighbor_dir in Dir::ALL.into_iter().rev() {
+            stack.extend(node_loc.io_neighbor(neighbor_dir, torus));
+        }
+    };
+
+    let mut stack = Vec::new();
+
+    push_neighbors(&mut stack, start_loc);
+
+    while let Some(node_loc) = stack.pop() {
+        new_nodes = match transform(old_nodes, new_nodes, node_loc) {
+            Ok(nodes) => {
+                push_neighbors(&mut stack, node_loc);
+                nodes
+            }
+            Err(nodes) => nodes,
+        }
+    }
+
+    Ok(new_nodes)
+}
+
+fn step_node_execution(
+    old_nodes: &Nodes,
+    mut new_nodes: Nodes,
+    node_loc: NodeCoord,
+    torus: Option<Torus>,
+    report: &mut StepReport,
+) -> Result<Nodes, Nodes> {
+    let Some(mut node) = old_nodes.get(&node_loc).cloned() else {
+        return Err(new_nodes);
+    };
+
+    if new_nodes.contains_key(&node_loc) {
+        return Err(new_nodes);
+    }
+
+    match &mut node {
+        Node::Exec(exec_node) => {
+            let arithmetic = exec_node.arithmetic;
+
+            let Some(ref mut exec) = exec_node.exec else {
+                if let Ok(code) = &exec_node.parsed
+                    && !code.is_empty()
+                {
+                    exec_node.exec = Some(NodeExec::init(code.clone()));
+                    new_nodes.insert(node_loc, node);
+                    report.executed.push(node_loc);
+                    return Ok(new_nodes);
+                } else {
+                    new_nodes.insert(node_loc, node);
+                    return Err(new_nodes);
+                }
+            };
+
+            if let NodeIO::Outbound(_, _) | NodeIO::OutboundAny(_) = exec.io {
+                new_nodes.try_insert(node_loc, node).unwrap();
+                return Ok(new_nodes);
+            }
+
+            if exec.halted {
+                new_nodes.try_insert(node_loc, node).unwrap();
+                return Ok(new_nodes);
+            }
+
+            // an empty program has nothing to execute, so it just sits there
+            let Some(instruction) = exec.code.get(exec.ip as usize) else {
+                new_nodes.insert(node_loc, node);
+                return Err(new_nodes);
+            };
+
+            match instruction.op {
+                Op::Mov(src, dst) => {
+                    if let Some(value) = get_src_value(
+                        exec,
+                        node_loc,
+                        old_nodes,
+                        &mut new_nodes,
+                        src,
+                        torus,
+                        report,
+                    ) {
+                        match dst {
+                            Dst::Acc => {
+                                exec.acc = value;
+                                exec.inc_ip();
+                            }
+                            Dst::Dir(target_dir) => exec.io = NodeIO::Outbound(target_dir, value),
+                            Dst::Any => exec.io = NodeIO::OutboundAny(value),
+                            Dst::Nil => exec.inc_ip(),
+                        }
+                    }
+                }
+                Op::Nop => exec.inc_ip(),
+                Op::Swp => {
+                    (exec.acc, exec.bak) = (exec.bak, exec.acc);
+                    exec.inc_ip();
+                }
+                Op::Sav => {
+                    exec.bak = exec.acc;
+                    exec.inc_ip();
+                }
+                Op::Add(src) => {
+                    if let Some(value) = get_src_value(
+                        exec,
+                        node_loc,
+                        old_nodes,
+                        &mut new_nodes,
+                        src,
+                        torus,
+                        report,
+                    ) {
+                        exec.acc = exec.acc.add_with(value, arithmetic);
+                        exec.inc_ip();
+                    }
+                }
+                Op::Sub(src) => {
+                    if let Some(value) = get_src_value(
+                        exec,
+                        node_loc,
+                        old_nodes,
+                        &mut new_nodes,
+                        src,
+                        torus,
+                        report,
+                    ) {
+                        exec.acc = exec.acc.sub_with(value, arithmetic);
+                        exec.inc_ip();
+                    }
+                }
+                Op::Neg => {
+                    exec.acc = -exec.acc;
+                    exec.inc_ip();
+                }
+                Op::Jmp(target) => exec.ip = target,
+                Op::Jez(target) => {
+                    if exec.acc == Num::ZERO {
+                        exec.ip = target
+                    } else {
+                        exec.inc_ip();
+                    }
+                }
+                Op::Jnz(target) => {
+                    if exec.acc != Num::ZERO {
+                        exec.ip = target
+                    } else {
+                        exec.inc_ip();
+                    }
+                }
+                Op::Jgz(target) => {
+                    if exec.acc > Num::ZERO {
+                        exec.ip = target
+                    } else {
+                        exec.inc_ip();
+                    }
+                }
+                Op::Jlz(target) => {
+                    if exec.acc < Num::ZERO {
+                        exec.ip = target
+                    } else {
+                        exec.inc_ip();
+                    }
+                }
+                Op::Jro(src) => {
+                    if let Some(value) = get_src_value(
+                        exec,
+                        node_loc,
+                        old_nodes,
+                        &mut new_nodes,
+                        src,
+                        torus,
+                        report,
+                    ) {
+                        exec.jro(value);
+                    }
+                }
+                Op::Hcf => exec.halted = true,
+            }
+
+            if !matches!(exec.io, NodeIO::Inbound(_) | NodeIO::InboundAny) {
+                exec.steps += 1;
+                report.executed.push(node_loc);
+            }
+        }
+
+        Node::Input(input_node) => {
+            if input_node.index.is_none() {
+                input_node.index = Some(0);
+            }
+        }
+
+        Node::Random(random_node) => {
+            if random_node.state.is_none() {
+                random_node.start();
+            }
+        }
+
+        Node::Output(output_node) => {
+            let Some(received) = &mut output_node.received else {
+                output_node.received = Some(ArrayVec::new());
+                new_nodes.insert(node_loc, node);
+                return Ok(new_nodes);
+            };
+
+            if !received.is_full()
+                && let Some(sender_loc) = node_loc.io_neighbor(Dir::Up, torus)
+                && let Some(Node::Exec(sender)) = old_nodes.get(&sender_loc)
+            {
+                let mut sender = sender.clone();
+
+                let offered = match sender.exec.as_ref().map(|sender_exec| &sender_exec.io) {
+                    Some(NodeIO::Outbound(Dir::Down, value)) => Some(*value),
+                    Some(NodeIO::OutboundAny(value))
+                        if any_write_recipient(old_nodes, sender_loc, torus) == Some(Dir::Down) =>
+                    {
+                        Some(*value)
+                    }
+                    _ => None,
+                };
+
+                if let Some(sender_exec) = &mut sender.exec
+                    && let Some(value) = offered
+                {
+                    sender_exec.inc_ip();
+                    sender_exec.io = NodeIO::None;
+
+                    received.push(value);
+
+                    report.transfers.push(Transfer {
+                        from: sender_loc,
+                        to: node_loc,
+                        value,
+                    });
+
+                    new_nodes.insert(sender_loc, Node::Exec(sender));
+                }
+            }
+        }
+    }
+
+    new_nodes.try_insert(node_loc, node).unwrap();
+
+    Ok(new_nodes)
+}
+
+fn get_src_value(
+    exec: &mut NodeExec,
+    node_loc: NodeCoord,
+    old_nodes: &Nodes,
+    new_nodes: &mut Nodes,
+    src: Src,
+    torus: Option<Torus>,
+    report: &mut StepReport,
+) -> Option<Num> {
+    match src {
+        Src::Imm(num) => Some(num),
+        Src::Acc => Some(exec.acc),
+        Src::Dir(target_dir) => {
+            exec.io = NodeIO::Inbound(target_dir);
+
+            let value = match exec.inbox.take_if(|(dir, _)| *dir == target_dir) {
+                Some((_, value)) => value,
+                None => {
+                    take_from_neighbor(node_loc, target_dir, old_nodes, new_nodes, torus, report)?
+                }
+            };
+
+            exec.io = NodeIO::None;
+
+            Some(value)
+        }
+        Src::Any => {
+            exec.io = NodeIO::InboundAny;
+
+            let value = match exec.inbox.take() {
+                Some((_, value)) => value,
+                None => ANY_READ_PRIORITY.into_iter().find_map(|target_dir| {
+                    take_from_neighbor(node_loc, target_dir, old_nodes, new_nodes, torus, report)
+                })?,
+            };
+
+            exec.io = NodeIO::None;
+
+            Some(value)
+        }
+        Src::Nil => Some(Num::ZERO),
+    }
+}
+
+/// every side of an exec node that its code names for a read or a write, but has no node on it.
+/// the node would wait there forever, which is allowed, but usually a mistake while building a puzzle.
+/// ANY is left out, since it doesn't name a side
+fn dangling_io(nodes: &Nodes, torus: Option<Torus>) -> Vec<(NodeCoord, Dir)> {
+    let mut exec_nodes: Vec<_> = nodes
+        .iter()
+        .filter_map(|(node_loc, node)| match node {
+            Node::Exec(exec_node) => Some((*node_loc, exec_node.parsed.as_ref().ok()?)),
+            Node::Input(_) | Node::Output(_) | Node::Random(_) => None,
+        })
+        .collect();
+
+    exec_nodes.sort_by_key(|&(node_loc, _)| node_loc);
+
+    exec_nodes
+        .into_iter()
+        .flat_map(|(node_loc, code)| {
+            Dir::ALL
+                .into_iter()
+                .filter(|&dir| {
+                    code.iter().any(|instruction| {
+                        let reads =
+                            matches!(instruction.op.src(), Some(Src::Dir(src)) if src == dir);
+                        let writes =
+                            matches!(instruction.op, Op::Mov(_, Dst::Dir(dst)) if dst == dir);
+
+                        reads || writes
+                    })
+                })
+                .filter(move |&dir| {
+                    !node_loc
+                        .io_neighbor(dir, torus)
+                        .is_some_and(|loc| nodes.contains_key(&loc))
+                })
+                .map(move |dir| (node_loc, dir))
+        })
+        .collect()
+}
+
+/// the order TIS-100 offers a value written to ANY to the neighbors that are reading it,
+/// see notes.md
+const ANY_WRITE_PRIORITY: [Dir; 4] = [Dir::Left, Dir::Right, Dir::Up, Dir::Down];
+
+/// the order a read from ANY checks the neighbors for a value, see notes.md
+const ANY_READ_PRIORITY: [Dir; 4] = [Dir::Up, Dir::Left, Dir::Right, Dir::Down];
+
+/// the direction of the neighbor that gets the value a node is writing to ANY, if any are reading
+fn any_write_recipient(nodes: &Nodes, writer_loc: NodeCoord, torus: Option<Torus>) -> Option<Dir> {
+    ANY_WRITE_PRIORITY.into_iter().find(|&dir| {
+        let reading_dir = dir.inverse();
+
+        match writer_loc
+            .io_neighbor(dir, torus)
+            .and_then(|loc| nodes.get(&loc))
+        {
+            Some(Node::Exec(ExecNode {
+                exec: Some(exec), ..
+            })) if !exec.halted
+                && !matches!(exec.io, NodeIO::Outbound(_, _) | NodeIO::OutboundAny(_)) =>
+            {
+                match exec
+                    .code
+                    .get(exec.ip as usize)
+                    .and_then(|instruction| instruction.op.src())
+                {
+                    Some(Src::Any) => true,
+                    Some(Src::Dir(src_dir)) => src_dir == reading_dir,
+                    Some(Src::Imm(_) | Src::Acc | Src::Nil) | None => false,
+                }
+            }
+
+            Some(Node::Output(output_node)) => {
+                reading_dir == Dir::Up
+                    && output_node
+                        .received
+                        .as_ref()
+                        .is_some_and(|received| !received.is_full())
+            }
+
+            Some(Node::Exec(_) | Node::Input(_) | Node::Random(_)) | None => false,
+        }
+    })
+}
+
+/// takes the value the neighbor in `target_dir` is offering to `node_loc`, if there is one
+fn take_from_neighbor(
+    node_loc: NodeCoord,
+    target_dir: Dir,
+    old_nodes: &Nodes,
+    new_nodes: &mut Nodes,
+    torus: Option<Torus>,
+    report: &mut StepReport,
+) -> Option<Num> {
+    let neighbor_loc = node_loc.io_neighbor(target_dir, torus)?;
+    let neighbor = old_nodes.get(&neighbor_loc)?;
+
+    let value = match neighbor {
+        Node::Exec(exec_node) => {
+            let mut neighbor = exec_node.clone();
+            let neighbor_exec = neighbor.exec.as_mut()?;
+
+            let offered = match neighbor_exec.io {
+                NodeIO::Outbound(neighbor_outbound_dir, value)
+                    if neighbor_outbound_dir == target_dir.inverse() =>
+                {
+                    Some(value)
+                }
+                NodeIO::OutboundAny(value)
+                    if any_write_recipient(old_nodes, neighbor_loc, torus)
+                        == Some(target_dir.inverse()) =>
+                {
+                    Some(value)
+                }
+                NodeIO::None
+                | NodeIO::Outbound(_, _)
+                | NodeIO::OutboundAny(_)
+                | NodeIO::Inbound(_)
+                | NodeIO::InboundAny => None,
+            };
+
+            if offered.is_some() {
+                neighbor_exec.inc_ip();
+
+                neighbor_exec.io = NodeIO::None;
+
+                new_nodes.insert(neighbor_loc, Node::Exec(neighbor));
+            }
+
+            offered
+        }
+
+        Node::Input(input_node) if target_dir == input_node.dir.inverse() => {
+            let mut neighbor = input_node.clone();
+            let num = neighbor.read()?;
+
+            new_nodes.insert(neighbor_loc, Node::Input(neighbor));
+
+            Some(num)
+        }
+
+        Node::Random(random_node) if target_dir == Dir::Up => {
+            let mut neighbor = random_node.clone();
+            let num = neighbor.current()?;
+
+            neighbor.advance();
+
+            new_nodes.insert(neighbor_loc, Node::Random(neighbor));
+
+            Some(num)
+        }
+
+        Node::Input(_) | Node::Output(_) | Node::Random(_) => None,
+    };
+
+    if let Some(value) = value {
+        report.transfers.push(Transfer {
+            from: neighbor_loc,
+            to: node_loc,
+            value,
+        });
+    }
+
+    value
+}
+
+fn update_camera(
+    camera: Camera2D,
+    highlighted_node: NodeCoord,
+    window_dimensions: (i32, i32),
+    mouse_wheel_move: f32,
+    follow: f32,
+    layout: &Layout,
+) -> Camera2D {
+    let center = highlighted_node.center(layout);
+
+    // snapping isn't held back by the speed limit either
+    let target = if follow >= 1.0 {
+        center
+    } else {
+        camera.target + ((center - camera.target) * follow).clamp(-200.0..200.0)
+    };
+
+    let zoom = (camera.zoom + mouse_wheel_move * 0.2).clamp(MIN_ZOOM, MAX_ZOOM);
+
+    let offset = Vector2 {
+        x: window_dimensions.0 as f32 / 2.,
+        y: window_dimensions.1 as f32 / 2.,
+    };
+
+    Camera2D {
+        target,
+        zoom,
+        offset,
+        ..camera
+    }
+}
+
+/// zooms while keeping the point in the world that's under the mouse where it is on screen
+fn zoom_to_cursor(
+    camera: Camera2D,
+    window_dimensions: (i32, i32),
+    mouse_wheel_move: f32,
+    mouse_position: Vector2,
+) -> Camera2D {
+    let offset = Vector2 {
+        x: window_dimensions.0 as f32 / 2.,
+        y: window_dimensions.1 as f32 / 2.,
+    };
+
+    let camera = Camera2D { offset, ..camera };
+
+    let world_under_mouse = screen_to_world(mouse_position, camera);
+
+    let zoom = (camera.zoom + mouse_wheel_move * 0.2).clamp(MIN_ZOOM, MAX_ZOOM);
+
+    let target = world_under_mouse - (mouse_position - offset) / zoom;
+
+    Camera2D {
+        target,
+        zoom,
+        ..camera
+    }
+}
+
+/// centers the camera on the bounding box of all nodes, zoomed out far enough to see all of them
+fn fit_all_camera(
+    camera: Camera2D,
+    nodes: &Nodes,
+    window_dimensions: (i32, i32),
+    layout: &Layout,
+) -> Camera2D {
+    let offset = Vector2 {
+        x: window_dimensions.0 as f32 / 2.,
+        y: window_dimensions.1 as f32 / 2.,
+    };
+
+    let corners = nodes.keys().map(|node_loc| {
+        (
+            node_loc.top_left_corner(layout),
+            node_loc.bottom_right_corner(layout),
+        )
+    });
+
+    let Some((top_left, bottom_right)) = corners.reduce(|(min, max), (top_left, bottom_right)| {
+        (
+            Vector2::new(min.x.min(top_left.x), min.y.min(top_left.y)),
+            Vector2::new(max.x.max(bottom_right.x), max.y.max(bottom_right.y)),
+        )
+    }) else {
+        return Camera2D {
+            target: NodeCoord::at(0, 0).center(layout),
+            zoom: DEFAULT_ZOOM,
+            offset,
+            ..camera
+        };
+    };
+
+    // leave a node's worth of padding around the edges so the outermost nodes' IO arrows are visible
+    let size = bottom_right - top_left + Vector2::one().scale_by(2.0 * NODE_OUTSIDE_PADDING);
+
+    let zoom = f32::min(
+        window_dimensions.0 as f32 / size.x,
+        window_dimensions.1 as f32 / size.y,
+    )
+    .clamp(MIN_ZOOM, MAX_ZOOM);
+
+    Camera2D {
+        target: top_left + (bottom_right - top_left).scale_by(0.5),
+        zoom,
+        offset,
+        ..camera
+    }
+}
+
+type NodeCode<Label = u8> = ArrayVec<Instruction<Label>, MAX_NODE_LINES>;
+
+#[derive(Clone, Debug)]
+struct NodeExec {
+    acc: Num,
+    bak: Num,
+    code: NodeCode,
+    io: NodeIO,
+    ip: u8,
+    /// set by `HCF`, the node won't do anything else until it's stopped
+    halted: bool,
+    /// cycles spent running an instruction rather than waiting on IO
+    steps: u32,
+    /// a value poked in by hand, which the next read from that direction takes
+    /// as if the neighbor there had sent it
+    inbox: Option<(Dir, Num)>,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+enum NodeIO {
+    None,
+    Outbound(Dir, Num),
+    /// goes to whichever neighbor reads it first, see `any_write_recipient`
+    OutboundAny(Num),
+    Inbound(Dir),
+    InboundAny,
+}
+
+impl NodeExec {
+    fn init(code: NodeCode) -> Self {
+        Self {
+            acc: Num::ZERO,
+            bak: Num::ZERO,
+            code,
+            io: NodeIO::None,
+            ip: 0,
+            halted: false,
+            steps: 0,
+            inbox: None,
+        }
+    }
+
+    /// only a node that's waiting on a read from `dir` can be poked from there
+    fn poke(&mut self, dir: Dir, value: Num) -> bool {
+        let reading = match self.io {
+            NodeIO::Inbound(io_dir) => io_dir == dir,
+            NodeIO::InboundAny => true,
+            NodeIO::None | NodeIO::Outbound(_, _) | NodeIO::OutboundAny(_) => false,
+        };
+
+        if !reading || self.halted || self.inbox.is_some() {
+            return false;
+        }
+
+        self.inbox = Some((dir, value));
+
+        true
+    }
+
+    /// whether the node is stuck on a read that hasn't been answered,
+    /// or a write that hasn't been taken yet. a halted node is blocked for good
+    fn is_blocked(&self) -> bool {
+        self.halted || !matches!(self.io, NodeIO::None)
+    }
+
+    fn inc_ip(&mut self) {
+        self.ip += 1;
+        if self.ip as usize >= self.code.len() {
+            self.ip = 0;
+        }
+    }
+
+    fn jro(&mut self, offset: Num) {
+        // there's no last instruction to clamp to
+        if self.code.is_empty() {
+            self.ip = 0;
+            return;
+        }
+
+        // wide enough that no offset in range can overflow or get truncated
+        let target = self.ip as i32 + offset.get() as i32;
+
+        self.ip = target.clamp(0, self.code.len() as i32 - 1) as u8;
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct Instruction<Label: Debug + Copy = u8> {
+    op: Op<Label>,
+    src_line: u8,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Op<Label: Debug + Copy> {
+    Mov(Src, Dst),
+    Nop,
+    Swp,
+    Sav,
+    Add(Src),
+    Sub(Src),
+    Neg,
+    Jmp(Label),
+    Jez(Label),
+    Jnz(Label),
+    Jgz(Label),
+    Jlz(Label),
+    Jro(Src),
+    /// halts just the node it's in, the rest of the network keeps going
+    Hcf,
+}
+
+impl<Label: Debug + Copy> Op<Label> {
+    fn src(&self) -> Option<Src> {
+        match self {
+            Op::Mov(src, _) | Op::Add(src) | Op::Sub(src) | Op::Jro(src) => Some(*src),
+            Op::Nop
+            | Op::Swp
+            | Op::Sav
+            | Op::Neg
+            | Op::Jmp(_)
+            | Op::Jez(_)
+            | Op::Jnz(_)
+            | Op::Jgz(_)
+            | Op::Jlz(_)
+            | Op::Hcf => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Src {
+    Imm(Num),
+    Dir(Dir),
+    Any,
+    Acc,
+    Nil,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Dst {
+    Dir(Dir),
+    Any,
+    Acc,
+    Nil,
+}
+
+#[derive(Clone, Debug)]
+struct ParseErr {
+    problem: ParseProblem,
+    line: u8,
+    /// where the offending token starts on the line, in characters.
+    /// `None` when there's nothing to point at, like an argument that's missing
+    column: Option<u8>,
+}
+
+#[derive(Clone, Debug)]
+enum ParseProblem {
+    NotEnoughArgs,
+    TooManyArgs,
+    InvalidSrc,
+    InvalidDst,
+    InvalidInstruction,
+    UndefinedLabel,
+    /// empty, an opcode, or containing whitespace or `#`
+    InvalidLabel,
+}
+
+impl ParseProblem {
+    fn to_str(&self) -> &'static str {
+        match self {
+            ParseProblem::NotEnoughArgs => "NOT ENOUGH ARGS",
+            ParseProblem::TooManyArgs => "TOO MANY ARGS",
+            ParseProblem::InvalidSrc => "INVALID SOURCE ARG",
+            ParseProblem::InvalidDst => "INVALID DESTINATION ARG",
+            ParseProblem::InvalidInstruction => "INVALID OPCODE",
+            ParseProblem::UndefinedLabel => "UNDEFINED LABEL",
+            ParseProblem::InvalidLabel => "INVALID LABEL",
+        }
+    }
+}
+
+/// something that parses and runs fine, but almost certainly isn't what was meant
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Warning {
+    kind: WarningKind,
+    line: u8,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum WarningKind {
+    /// `MOV ACC ACC` or `MOV NIL NIL`
+    MovesNothing,
+    /// adding or subtracting `NIL` or `0`
+    AddsZero,
+}
+
+impl WarningKind {
+    fn to_str(self) -> &'static str {
+        match self {
+            WarningKind::MovesNothing => "MOVES NOTHING",
+            WarningKind::AddsZero => "ADDS ZERO",
+        }
+    }
+}
+
+/// looks for instructions that do nothing, which never stops the code from running
+fn lint(code: &NodeCode) -> Vec<Warning> {
+    code.iter()
+        .filter_map(|instr| {
+            let kind = match instr.op {
+                Op::Mov(Src::Acc, Dst::Acc) | Op::Mov(Src::Nil, Dst::Nil) => {
+                    WarningKind::MovesNothing
+                }
+                Op::Add(Src::Nil) | Op::Sub(Src::Nil) => WarningKind::AddsZero,
+                Op::Add(Src::Imm(num)) | Op::Sub(Src::Imm(num)) if num == Num::ZERO => {
+                    WarningKind::AddsZero
+                }
+                _ => return None,
+            };
+
+            Some(Warning {
+                kind,
+                line: instr.src_line,
+            })
+        })
+        .collect()
+}
+
+fn parse_node_text(node_text: &NodeText) -> Result<NodeCode, ParseErr> {
+    #[cfg(test)]
+    tests::PARSES.with(|parses| parses.set(parses.get() + 1));
+
+    let mut code = NodeCode::<&str>::new();
+
+    // maps labels to instruction indices
+    let mut labels: HashMap<&str, u8> = HashMap::new();
+
+    for (line_no, full_line) in node_text.split('\n').enumerate() {
+        let Some(semantic_text) = full_line.split('#').next() else {
+            continue;
+        };
+
+        let op_text = match semantic_text.split_once(':') {
+            Some((label, rest)) => {
+                let label = label.trim();
+
+                if !is_valid_label(label) {
+                    return Err(ParseErr {
+                        problem: ParseProblem::InvalidLabel,
+                        line: line_no as u8,
+                        column: Some(token_column(full_line, label)),
+                    });
+                }
+
+                // label refers to the next instruction to be pushed to the list of instructions
+                let label_dest = code.len();
+                labels.insert(label, label_dest as u8);
+                rest
+            }
+            None => semantic_text,
+        };
+
+        let tokens = &mut op_text.split_ascii_whitespace();
+
+        let Some(opcode) = tokens.next() else {
+            continue;
+        };
+
+        let line_no = line_no as u8;
+        let line = (line_no, full_line);
+
+        let op = match opcode.to_ascii_uppercase().as_str() {
+            "MOV" => Op::Mov(expect_src(tokens, line)?, expect_dst(tokens, line)?),
+            "NOP" => Op::Nop,
+            "SWP" => Op::Swp,
+            "SAV" => Op::Sav,
+            "ADD" => Op::Add(expect_src(tokens, line)?),
+            "SUB" => Op::Sub(expect_src(tokens, line)?),
+            "NEG" => Op::Neg,
+            "JMP" => Op::Jmp(expect_label(tokens, line)?),
+            "JEZ" => Op::Jez(expect_label(tokens, line)?),
+            "JNZ" => Op::Jnz(expect_label(tokens, line)?),
+            "JGZ" => Op::Jgz(expect_label(tokens, line)?),
+            "JLZ" => Op::Jlz(expect_label(tokens, line)?),
+            "JRO" => Op::Jro(expect_src(tokens, line)?),
+            "HCF" => Op::Hcf,
+
+            _ => {
+                return Err(ParseErr {
+                    problem: ParseProblem::InvalidInstruction,
+                    line: line_no,
+                    column: Some(token_column(full_line, opcode)),
+                });
+            }
+        };
+
+        if let Some(extra) = tokens.next() {
+            return Err(ParseErr {
+                problem: ParseProblem::TooManyArgs,
+                line: line_no,
+                column: Some(token_column(full_line, extra)),
+            });
+        }
+
+        code.push(Instruction {
+            op,
+            src_line: line_no,
+        });
+    }
+
+    let code_len = code.len();
+
+    code.into_iter()
+        .map(|instr| {
+            let resolve = |label: &str| {
+                labels
+                    .get(&label)
+                    .copied()
+                    // a label after the last instruction wraps around to the first one, like `inc_ip` does
+                    .map(|dest| if dest as usize >= code_len { 0 } else { dest })
+                    .ok_or_else(|| ParseErr {
+                        problem: ParseProblem::UndefinedLabel,
+                        line: instr.src_line,
+                        column: node_text
+                            .split('\n')
+                            .nth(instr.src_line as usize)
+                            .map(|line| token_column(line, label)),
+                    })
+            };
+
+            let op = match instr.op {
+                Op::Mov(src, dst) => Op::Mov(src, dst),
+                Op::Nop => Op::Nop,
+                Op::Swp => Op::Swp,
+                Op::Sav => Op::Sav,
+                Op::Add(src) => Op::Add(src),
+                Op::Sub(src) => Op::Sub(src),
+                Op::Neg => Op::Neg,
+                Op::Jmp(label) => Op::Jmp(resolve(label)?),
+                Op::Jez(label) => Op::Jez(resolve(label)?),
+                Op::Jnz(label) => Op::Jnz(resolve(label)?),
+                Op::Jgz(label) => Op::Jgz(resolve(label)?),
+                Op::Jlz(label) => Op::Jlz(resolve(label)?),
+                Op::Jro(src) => Op::Jro(src),
+                Op::Hcf => Op::Hcf,
+            };
+
+            Ok(Instruction {
+                op,
+                src_line: instr.src_line,
+            })
+        })
+        .try_collect()
+}
+
+const OPCODES: [&str; 14] = [
+    "MOV", "NOP", "SWP", "SAV", "ADD", "SUB", "NEG", "JMP", "JEZ", "JNZ", "JGZ", "JLZ", "JRO",
+    "HCF",
+];
+
+/// indentation around a label is fine, but not inside it
+fn is_valid_label(label: &str) -> bool {
+    !label.is_empty()
+        && !label.contains(|char: char| char.is_whitespace() || char == '#')
+        && !OPCODES.contains(&label.to_ascii_uppercase().as_str())
+}
+
+/// how many characters into `line` `token` starts. `token` has to be a slice of `line`
+fn token_column(line: &str, token: &str) -> u8 {
+    let offset = token.as_ptr() as usize - line.as_ptr() as usize;
+
+    line[..offset].chars().count() as u8
+}
+
+/// the number of the line the tokens come from, and its text, to say where a problem is
+type SrcLine<'txt> = (u8, &'txt str);
+
+fn expect_label<'txt>(
+    tokens: &mut impl Iterator<Item = &'txt str>,
+    (line, _): SrcLine,
+) -> Result<&'txt str, ParseErr> {
+    let Some(label) = tokens.next() else {
+        return Err(ParseErr {
+            problem: ParseProblem::NotEnoughArgs,
+            line,
+            column: None,
+        });
+    };
+
+    Ok(label)
+}
+
+/// an optional `+` or `-` followed by digits, leading zeros included, as long as it's in range
+fn parse_immediate(token: &str) -> Option<Num> {
+    let digits = token.strip_prefix(['+', '-']).unwrap_or(token);
+
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+
+    token.parse().ok()
+}
+
+fn expect_src<'txt>(
+    tokens: &mut impl Iterator<Item = &'txt str>,
+    (line, line_text): SrcLine,
+) -> Result<Src, ParseErr> {
+    let Some(arg) = tokens.next() else {
+        return Err(ParseErr {
+            problem: ParseProblem::NotEnoughArgs,
+            line,
+            column: None,
+        });
+    };
+
+    match arg.to_ascii_uppercase().as_str() {
+        "ACC" => Ok(Src::Acc),
+        "UP" => Ok(Src::Dir(Dir::Up)),
+        "DOWN" => Ok(Src::Dir(Dir::Down)),
+        "LEFT" => Ok(Src::Dir(Dir::Left)),
+        "RIGHT" => Ok(Src::Dir(Dir::Right)),
+        "ANY" => Ok(Src::Any),
+        "NIL" => Ok(Src::Nil),
+        other => {
+            if let Some(num) = parse_immediate(other) {
+                Ok(Src::Imm(num))
+            } else {
+                Err(ParseErr {
+                    problem: ParseProblem::InvalidSrc,
+                    line,
+                    column: Some(token_column(line_text, arg)),
+                })
+            }
+        }
+    }
+}
+
+fn expect_dst<'txt>(
+    tokens: &mut impl Iterator<Item = &'txt str>,
+    (line, line_text): SrcLine,
+) -> Result<Dst, ParseErr> {
+    let Some(arg) = tokens.next() else {
+        return Err(ParseErr {
+            problem: ParseProblem::NotEnoughArgs,
+            line,
+            column: None,
+        });
+    };
+
+    match arg.to_ascii_uppercase().as_str() {
+        "ACC" => Ok(Dst::Acc),
+        "UP" => Ok(Dst::Dir(Dir::Up)),
+        "DOWN" => Ok(Dst::Dir(Dir::Down)),
+        "LEFT" => Ok(Dst::Dir(Dir::Left)),
+        "RIGHT" => Ok(Dst::Dir(Dir::Right)),
+        "ANY" => Ok(Dst::Any),
+        "NIL" => Ok(Dst::Nil),
+        _ => Err(ParseErr {
+            problem: ParseProblem::InvalidDst,
+            line,
+            column: Some(token_column(line_text, arg)),
+        }),
+    }
+}
+
+#[derive(Debug)]
+enum ImportErr {
+    InvalidToml,
+    InvalidCoord,
+    TooManyLines,
+    LineTooLong,
+    InvalidRhs,
+    DuplicateCoord,
+    InvalidHighlightRhs,
+    IntOutOfRange,
+    NotAnInt,
+    InvalidName,
+    InvalidIndexHeader,
+    MissingIndexHeader,
+    TooManyValues,
+    InvalidLockedRhs,
+    UnreadableDataFile,
+    InvalidWrappingRhs,
+    InvalidTorusRhs,
+}
+
+impl ImportErr {
+    fn to_str(&self) -> &'static str {
+        match self {
+            ImportErr::InvalidToml => "INVALID TOML",
+            ImportErr::InvalidCoord => "INVALID COORD",
+            ImportErr::TooManyLines => "TOO MANY LINES",
+            ImportErr::LineTooLong => "LINE TOO LONG",
+            ImportErr::InvalidRhs => "INVALID RHS",
+            ImportErr::DuplicateCoord => "DUPLICATE COORD",
+            ImportErr::InvalidHighlightRhs => "INVALID LOC",
+            ImportErr::IntOutOfRange => "INT OVERFLOW",
+            ImportErr::NotAnInt => "NOT AN INT",
+            ImportErr::InvalidName => "INVALID NAME",
+            ImportErr::InvalidIndexHeader => "INVALID @N",
+            ImportErr::MissingIndexHeader => "MISSING @N",
+            ImportErr::TooManyValues => "TOO MANY VALUES",
+            ImportErr::InvalidLockedRhs => "INVALID LOCKED",
+            ImportErr::UnreadableDataFile => "CAN'T READ DATA FILE",
+            ImportErr::InvalidWrappingRhs => "INVALID WRAPPING",
+            ImportErr::InvalidTorusRhs => "INVALID TORUS",
+        }
+    }
+}
+
+impl From<TextOverflow> for ImportErr {
+    fn from(overflow: TextOverflow) -> ImportErr {
+        match overflow {
+            TextOverflow::TooManyLines => ImportErr::TooManyLines,
+            TextOverflow::LineTooLong => ImportErr::LineTooLong,
+        }
+    }
+}
+
+use toml::{Table, Value};
+
+const HIGHLIGHTED_NODE_KEY: &'static str = "highlighted";
+const NODE_NAMES_KEY: &str = "names";
+const LOCKED_NODES_KEY: &str = "locked";
+const WRAPPING_NODES_KEY: &str = "wrapping";
+const TORUS_KEY: &str = "torus";
+const NODE_TYPE_KEY: &str = "type";
+const INPUT_NODE_TYPE: &str = "input";
+const INPUT_DATA_KEY: &str = "data";
+const INPUT_DIR_KEY: &str = "dir";
+const INPUT_FILE_KEY: &str = "file";
+const OUTPUT_NODE_TYPE: &str = "output";
+const OUTPUT_EXPECTED_KEY: &str = "expected";
+const RANDOM_NODE_TYPE: &str = "random";
+const RANDOM_SEED_KEY: &str = "seed";
+
+/// a workspace where some cells are pinned, usually the inputs and the expected outputs.
+/// it's saved like any other workspace, plus a `locked = [ "x,y", ... ]` key,
+/// and a `torus = [ "x,y", "x,y" ]` key with the corners of the box its network wraps around in
+struct Puzzle {
+    nodes: Nodes,
+    highlighted_node: NodeCoord,
+    locked: HashSet<NodeCoord>,
+    torus: Option<Torus>,
+}
+
+/// prints every problem with a saved workspace, and returns the exit code
+fn check(path: &str) -> i32 {
+    let report = match std::fs::read_to_string(path) {
+        Ok(toml) => check_workspace(&toml, Path::new(path).parent()),
+        Err(err) => {
+            println!("{path}: {err}");
+            return 1;
+        }
+    };
+
+    match report {
+        Ok(errors) if errors.is_empty() => 0,
+
+        Ok(errors) => {
+            for error in errors {
+                println!("{error}");
+            }
+
+            1
+        }
+
+        Err(import_err) => {
+            println!("{path}: {}", import_err.to_str());
+            1
+        }
+    }
+}
+
+/// how long each benchmark runs the network for
+const BENCH_CYCLES: u32 = 20_000;
+
+/// steps `nodes` for `BENCH_CYCLES`, after starting them, and returns the cycles per second
+fn bench_network(mut nodes: Nodes, starting_node: NodeCoord) -> f64 {
+    nodes.extend(step_execution(&nodes, starting_node, None).unwrap().0);
+
+    let start = std::time::Instant::now();
+
+    for _ in 0..BENCH_CYCLES {
+        let (updated_nodes, report) = step_execution(&nodes, starting_node, None).unwrap();
+
+        nodes.extend(updated_nodes);
+        std::hint::black_box(report);
+    }
+
+    BENCH_CYCLES as f64 / start.elapsed().as_secs_f64()
+}
+
+/// the networks timed by `--bench`, each with the node to step them from
+fn bench_networks(layout: &Layout) -> [(&'static str, Nodes, NodeCoord); 2] {
+    let single = Nodes::from([(
+        NodeCoord::at(0, 0),
+        Node::exec_with_text("ADD 7\nSUB 3\nNEG\nSWP", layout).unwrap(),
+    )]);
+
+    // every node passes a value right and takes one from the left, so they all stay busy
+    let grid = (0..10)
+        .flat_map(|y| (0..10).map(move |x| NodeCoord::at(x, y)))
+        .map(|node_loc| {
+            let text = match node_loc.x {
+                0 => "ADD 1\nMOV ACC RIGHT",
+                9 => "MOV LEFT ACC\nSUB 1",
+                _ => "MOV LEFT ACC\nADD 1\nMOV ACC RIGHT",
+            };
+
+            (node_loc, Node::exec_with_text(text, layout).unwrap())
+        })
+        .collect();
+
+    [
+        ("single node", single, NodeCoord::at(0, 0)),
+        ("10x10 grid", grid, NodeCoord::at(0, 0)),
+    ]
+}
+
+fn bench() {
+    for (name, nodes, starting_node) in bench_networks(&Layout::default()) {
+        let cycles_per_second = bench_network(nodes, starting_node);
+
+        println!("{name}: {cycles_per_second:.0} cycles/s");
+    }
+}
+
+/// every exec node that doesn't parse, as `x,y:line: MESSAGE` in reading order.
+/// lines are counted from 1, like editors do
+fn check_workspace(toml: &str, base_dir: Option<&Path>) -> Result<Vec<String>, ImportErr> {
+    let puzzle = parse_puzzle(toml, base_dir)?;
+
+    let mut nodes: Vec<_> = puzzle.nodes.iter().collect();
+
+    nodes.sort_by_key(|&(node_loc, _)| node_loc);
+
+    Ok(nodes
+        .into_iter()
+        .filter_map(|(node_loc, node)| match node {
+            Node::Exec(exec_node) => {
+                let parse_err = exec_node.error()?;
+
+                Some(format!(
+                    "{},{}:{}: {}",
+                    node_loc.x,
+                    node_loc.y,
+                    parse_err.line as usize + 1,
+                    parse_err.problem.to_str()
+                ))
+            }
+            Node::Input(_) | Node::Output(_) | Node::Random(_) => None,
+        })
+        .collect())
+}
+
+/// `base_dir` is where the workspace was saved, data files are looked for relative to it
+fn parse_puzzle(toml: &str, base_dir: Option<&Path>) -> Result<Puzzle, ImportErr> {
+    let mut table: Table = match toml::from_str(toml) {
+        Ok(table) => table,
+        Err(_) => return Err(ImportErr::InvalidToml),
+    };
+
+    let locked = match table.remove(LOCKED_NODES_KEY) {
+        Some(Value::Array(arr)) => arr
+            .iter()
+            .map(|value| {
+                let coord = value.as_str().ok_or(ImportErr::InvalidLockedRhs)?;
+                parse_coord(coord)
+            })
+            .try_collect()?,
+        Some(_) => return Err(ImportErr::InvalidLockedRhs),
+        None => HashSet::new(),
+    };
+
+    let torus = match table.remove(TORUS_KEY) {
+        Some(Value::Array(arr)) => match arr.as_slice() {
+            [Value::String(a), Value::String(b)] => {
+                Some(Torus::spanning(parse_coord(a)?, parse_coord(b)?))
+            }
+            _ => return Err(ImportErr::InvalidTorusRhs),
+        },
+        Some(_) => return Err(ImportErr::InvalidTorusRhs),
+        None => None,
+    };
+
+    let (nodes, highlighted_node) = parse_table(table, base_dir)?;
+
+    Ok(Puzzle {
+        nodes,
+        highlighted_node,
+        locked,
+        torus,
+    })
+}
+
+/// swaps the left and right of the cells' bounding box, or `None` if there aren't any cells
+fn mirror_horizontally(
+    cells: &HashSet<NodeCoord>,
+) -> Option<impl Fn(NodeCoord) -> NodeCoord + use<>> {
+    let min_x = cells.iter().map(|node_loc| node_loc.x).min()?;
+    let max_x = cells.iter().map(|node_loc| node_loc.x).max()?;
+
+    Some(move |node_loc: NodeCoord| NodeCoord::at(min_x + max_x - node_loc.x, node_loc.y))
+}
+
+/// how far the nodes have to move for the top left corner of their bounding box to be `(0, 0)`
+fn origin_offset(nodes: &Nodes) -> Option<(isize, isize)> {
+    let min_x = nodes.keys().map(|node_loc| node_loc.x).min()?;
+    let min_y = nodes.keys().map(|node_loc| node_loc.y).min()?;
+
+    Some((-min_x, -min_y))
+}
+
+/// adds a copied workspace to `nodes`, shifted so its top left corner lands on `at`.
+/// nothing is pasted if any of it would land on an existing node
+fn paste_workspace(nodes: &Nodes, pasted: Nodes, at: NodeCoord) -> Option<Nodes> {
+    let min_x = pasted.keys().map(|node_loc| node_loc.x).min()?;
+    let min_y = pasted.keys().map(|node_loc| node_loc.y).min()?;
+
+    let mut merged = nodes.clone();
+
+    for (node_loc, node) in pasted {
+        let dst = NodeCoord::at(node_loc.x - min_x + at.x, node_loc.y - min_y + at.y);
+
+        if merged.insert(dst, node).is_some() {
+            return None;
+        }
+    }
+
+    Some(merged)
+}
+
+fn parse_toml(toml: &str) -> Result<(Nodes, NodeCoord), ImportErr> {
+    match toml::from_str(toml) {
+        // there's no file to be relative to, so data files can't be used
+        Ok(table) => parse_table(table, None),
+        Err(_) => Err(ImportErr::InvalidToml),
+    }
+}
+
+fn parse_table(table: Table, base_dir: Option<&Path>) -> Result<(Nodes, NodeCoord), ImportErr> {
+    let mut nodes = Nodes::new();
+    let mut highlighted = None;
+    let mut names = Table::new();
+    let mut wrapping = Vec::new();
+
+    for (key, value) in table {
+        if &key == HIGHLIGHTED_NODE_KEY {
+            if let Value::String(coord) = value {
+                highlighted = Some(parse_coord(&coord)?);
+            } else {
+                return Err(ImportErr::InvalidHighlightRhs);
+            }
+        } else if key == NODE_NAMES_KEY {
+            if let Value::Table(table) = value {
+                names = table;
+            } else {
+                return Err(ImportErr::InvalidName);
+            }
+        } else if key == WRAPPING_NODES_KEY {
+            if let Value::Array(arr) = value {
+                wrapping = arr
+                    .iter()
+                    .map(|value| {
+                        let coord = value.as_str().ok_or(ImportErr::InvalidWrappingRhs)?;
+                        parse_coord(coord)
+                    })
+                    .try_collect()?;
+            } else {
+                return Err(ImportErr::InvalidWrappingRhs);
+            }
+        } else {
+            let (node_loc, node) = parse_node(&key, value, base_dir)?;
+
+            if nodes.try_insert(node_loc, node).is_err() {
+                return Err(ImportErr::DuplicateCoord);
+            };
+        }
+    }
+
+    // names are applied after all the nodes have been read,
+    // since the names table isn't necessarily visited last
+    for (key, value) in names {
+        let node_loc = parse_coord(&key)?;
+
+        let name = value
+            .as_str()
+            .and_then(|name| NodeName::from(name).ok())
+            .ok_or(ImportErr::InvalidName)?;
+
+        if let Some(node) = nodes.get_mut(&node_loc) {
+            node.set_name(Some(name));
+        }
+    }
+
+    for node_loc in wrapping {
+        if let Some(Node::Exec(exec_node)) = nodes.get_mut(&node_loc) {
+            exec_node.arithmetic = Arithmetic::Wrapping;
+        }
+    }
+
+    Ok((nodes, highlighted.unwrap_or(NodeCoord::at(0, 0))))
+}
+
+fn parse_node(
+    key: &str,
+    value: Value,
+    base_dir: Option<&Path>,
+) -> Result<(NodeCoord, Node), ImportErr> {
+    let node_loc = parse_coord(key)?;
+
+    let node = match value {
+        Value::String(text) => Node::exec_with_text(text.trim_end(), &Layout::LARGEST)?,
+
+        Value::Array(arr) => {
+            let data = parse_nums(&arr)?;
+
+            Node::Input(InputNode::with_data(data))
+        }
+
+        Value::Table(table) => match node_type(&table) {
+            // only input nodes that don't send down, or that read a data file,
+            // need to be written out as tables
+            Some(INPUT_NODE_TYPE) => {
+                let data = match (table.get(INPUT_DATA_KEY), table.get(INPUT_FILE_KEY)) {
+                    (Some(Value::Array(arr)), None) => parse_nums(arr)?,
+                    (None, Some(Value::String(file))) => read_data_file(file, base_dir)?,
+                    _ => return Err(ImportErr::InvalidRhs),
+                };
+
+                let dir = match table.get(INPUT_DIR_KEY) {
+                    Some(value) => value
+                        .as_str()
+                        .and_then(parse_dir_name)
+                        .ok_or(ImportErr::InvalidRhs)?,
+                    None => Dir::Down,
+                };
+
+                Node::Input(InputNode {
+                    dir,
+                    ..InputNode::with_data(data)
+                })
+            }
+
+            Some(OUTPUT_NODE_TYPE) => {
+                let expected = match table.get(OUTPUT_EXPECTED_KEY) {
+                    Some(Value::Array(arr)) => parse_nums(arr)?,
+                    Some(_) => return Err(ImportErr::InvalidRhs),
+                    None => ArrayVec::new(),
+                };
+
+                Node::Output(OutputNode {
+                    expected,
+                    ..OutputNode::empty()
+                })
+            }
+
+            Some(RANDOM_NODE_TYPE) => {
+                let seed = table
+                    .get(RANDOM_SEED_KEY)
+                    .ok_or(ImportErr::InvalidRhs)?
+                    .as_integer()
+                    .ok_or(ImportErr::NotAnInt)?
+                    .try_into()
+                    .map_err(|_| ImportErr::IntOutOfRange)?;
+
+                Node::random(seed)
+            }
+
+            _ => return Err(ImportErr::InvalidRhs),
+        },
+
+        _ => return Err(ImportErr::InvalidRhs),
+    };
+
+    Ok((node_loc, node))
+}
+
+/// a node table can leave its type out if it reads a data file, since only inputs do
+fn node_type(table: &Table) -> Option<&str> {
+    match table.get(NODE_TYPE_KEY) {
+        Some(node_type) => node_type.as_str(),
+        None => table
+            .contains_key(INPUT_FILE_KEY)
+            .then_some(INPUT_NODE_TYPE),
+    }
+}
+
+/// reads an input node's values from a file, separated by commas or newlines.
+/// the data is only read when the workspace is loaded, saving writes it out inline
+fn read_data_file(
+    file: &str,
+    base_dir: Option<&Path>,
+) -> Result<ArrayVec<Num, INPUT_NODE_CAP>, ImportErr> {
+    let path = base_dir.ok_or(ImportErr::UnreadableDataFile)?.join(file);
+
+    let contents = std::fs::read_to_string(path).map_err(|_| ImportErr::UnreadableDataFile)?;
+
+    let nums: Vec<Num> = contents
+        .split([',', '\n'])
+        .map(str::trim)
+        .filter(|num| !num.is_empty())
+        .map(|num| {
+            let int: i64 = num.parse().map_err(|_| ImportErr::NotAnInt)?;
+            int.try_into().map_err(|_| ImportErr::IntOutOfRange)
+        })
+        .try_collect()?;
+
+    ArrayVec::try_from(nums.as_slice()).map_err(|_| ImportErr::TooManyValues)
+}
+
+fn parse_nums<const CAP: usize>(arr: &[Value]) -> Result<ArrayVec<Num, CAP>, ImportErr> {
+    let nums: Vec<Num> = arr
+        .iter()
+        .map(|value| {
+            let int = value.as_integer().ok_or(ImportErr::NotAnInt)?;
+            int.try_into().map_err(|_| ImportErr::IntOutOfRange)
+        })
+        .try_collect()?;
+
+    ArrayVec::try_from(nums.as_slice()).map_err(|_| ImportErr::TooManyValues)
+}
+
+/// typing and backspace for the one line prompts, says whether the text changed
+fn edit_prompt_text(text: &mut PromptText, mods: Modifiers, key: Key) -> bool {
+    match (mods, key) {
+        (Modifiers::None | Modifiers::Shift, Key::Char(char)) => text.try_push(char).is_ok(),
+        (_, Key::Backspace) => text.pop().is_some(),
+        _ => false,
+    }
+}
+
+/// a value and the direction it comes from, like `5 LEFT` or `-3 up`
+fn parse_poke(text: &str) -> Option<(Num, Dir)> {
+    let mut tokens = text.split_ascii_whitespace();
+
+    let value = parse_immediate(tokens.next()?)?;
+    let dir = parse_dir_name(&tokens.next()?.to_ascii_lowercase())?;
+
+    tokens.next().is_none().then_some((value, dir))
+}
+
+/// like `parse_coord`, but also takes the parenthesized form shown on screen
+fn parse_go_to(text: &str) -> Result<NodeCoord, ImportErr> {
+    let text = text.trim();
+    let text = text
+        .strip_prefix('(')
+        .and_then(|text| text.strip_suffix(')'))
+        .unwrap_or(text);
+
+    parse_coord(text)
+}
+
+fn parse_coord(str: &str) -> Result<NodeCoord, ImportErr> {
+    let mut coords = str.split(',');
+
+    let x = coords
+        .next()
+        .ok_or(ImportErr::InvalidCoord)?
+        .trim()
+        .parse()
+        .map_err(|_| ImportErr::InvalidCoord)?;
+
+    let y = coords
+        .next()
+        .ok_or(ImportErr::InvalidCoord)?
+        .trim()
+        .parse()
+        .map_err(|_| ImportErr::InvalidCoord)?;
+
+    Ok(NodeCoord::at(x, y))
+}
+
+/// the number of nodes per row in TIS-100 puzzles
+const TIS100_GRID_WIDTH: usize = 4;
+
+/// reads a TIS-100 save file, where each node's code comes after an `@n` header,
+/// `n` being the node's index in the puzzle's grid
+fn import_tis100(save: &str, grid_width: usize) -> Result<Nodes, ImportErr> {
+    let mut blocks: Vec<(usize, Vec<&str>)> = Vec::new();
+
+    for line in save.lines() {
+        if let Some(index) = line.strip_prefix('@') {
+            let index = index
+                .trim()
+                .parse()
+                .map_err(|_| ImportErr::InvalidIndexHeader)?;
+
+            blocks.push((index, Vec::new()));
+        } else if let Some((_, lines)) = blocks.last_mut() {
+            lines.push(line);
+        } else if !line.trim().is_empty() {
+            return Err(ImportErr::MissingIndexHeader);
+        }
+    }
+
+    let mut nodes = Nodes::new();
+
+    for (index, lines) in blocks {
+        let node_loc = NodeCoord::at((index % grid_width) as _, (index / grid_width) as _);
+
+        let node = Node::exec_with_text(lines.join("\n").trim_end(), &Layout::LARGEST)?;
+
+        if nodes.try_insert(node_loc, node).is_err() {
+            return Err(ImportErr::DuplicateCoord);
+        }
+    }
+
+    Ok(nodes)
+}
+
+/// the inverse of `import_tis100`. nodes that can't be represented in a TIS-100 save,
+/// either because of their kind or because they're outside the grid, are skipped and returned
+fn export_tis100(nodes: &Nodes, grid_width: usize) -> (String, Vec<NodeCoord>) {
+    let mut indexed = Vec::new();
+    let mut skipped = Vec::new();
+
+    for (node_loc, node) in nodes {
+        let index = usize::try_from(node_loc.x)
+            .ok()
+            .zip(usize::try_from(node_loc.y).ok())
+            .filter(|&(x, _)| x < grid_width)
+            .map(|(x, y)| y * grid_width + x);
+
+        match (index, node) {
+            (Some(index), Node::Exec(exec_node)) => indexed.push((index, exec_node)),
+            (_, Node::Exec(_) | Node::Input(_) | Node::Output(_) | Node::Random(_)) => {
+                skipped.push(*node_loc)
+            }
+        }
+    }
+
+    indexed.sort_by_key(|&(index, _)| index);
+    skipped.sort();
+
+    let mut save = String::new();
+
+    for (index, exec_node) in indexed {
+        save += &format!("@{index}\n{}\n\n", exec_node.text);
+    }
+
+    (save, skipped)
+}
+
+/// lays out the values received by each output node in a column,
+/// headed by the node's coordinate. columns are ordered by y, then x
+fn export_outputs_csv(nodes: &Nodes) -> String {
+    let mut outputs: Vec<_> = nodes
+        .iter()
+        .filter_map(|(node_loc, node)| match node {
+            Node::Output(output_node) => Some((node_loc, output_node.received())),
+            Node::Exec(_) | Node::Input(_) | Node::Random(_) => None,
+        })
+        .collect();
+
+    if outputs.is_empty() {
+        return String::new();
+    }
+
+    outputs.sort_by_key(|&(node_loc, _)| node_loc);
+
+    let header = outputs
+        .iter()
+        .map(|(node_loc, _)| format!("\"{}\"", fmt_coord(node_loc)))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let rows = outputs
+        .iter()
+        .map(|(_, received)| received.len())
+        .max()
+        .unwrap_or(0);
+
+    let mut csv = header + "\n";
+
+    for row in 0..rows {
+        csv += &outputs
+            .iter()
+            .map(|(_, received)| received.get(row).map_or(String::new(), Num::to_string))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        csv += "\n";
+    }
+
+    csv
+}
+
+fn fmt_coord(node_loc: &NodeCoord) -> String {
+    format!("{}, {}", node_loc.x, node_loc.y)
+}
+
+fn serialize_toml(
+    nodes: &Nodes,
+    highlighted_node: Option<NodeCoord>,
+    locked: &HashSet<NodeCoord>,
+    torus: Option<Torus>,
+) -> String {
+    let mut toml = String::new();
+
+    // a hashmap's order changes from run to run, which would make saves churn in version control
+    let mut nodes: Vec<_> = nodes.iter().collect();
+
+    nodes.sort_by_key(|&(node_loc, _)| node_loc);
+
+    for &(node_loc, node) in &nodes {
+        let key = fmt_coord(node_loc);
+
+        toml += &match node {
+            Node::Exec(exec_node) => {
+                format!("\"{}\" = \"\"\"\n{}\n\"\"\"\n\n", key, &exec_node.text)
+            }
+            Node::Input(input_node) if input_node.dir == Dir::Down => {
+                let mut fmt = format!("\"{}\" = [ ", key);
+
+                for num in input_node.data() {
+                    fmt += &format!("{num}, ");
+                }
+
+                fmt + "]\n\n"
+            }
+            Node::Input(input_node) => {
+                format!(
+                    "\"{}\" = {{ {NODE_TYPE_KEY} = \"{INPUT_NODE_TYPE}\", {INPUT_DATA_KEY} = [ {} ], {INPUT_DIR_KEY} = \"{}\" }}\n\n",
+                    key,
+                    fmt_nums(input_node.data()),
+                    dir_name(input_node.dir)
+                )
+            }
+            Node::Output(output_node) if output_node.expected.is_empty() => {
+                format!(
+                    "\"{}\" = {{ {NODE_TYPE_KEY} = \"{OUTPUT_NODE_TYPE}\" }}\n\n",
+                    key
+                )
+            }
+            Node::Output(output_node) => {
+                format!(
+                    "\"{}\" = {{ {NODE_TYPE_KEY} = \"{OUTPUT_NODE_TYPE}\", {OUTPUT_EXPECTED_KEY} = [ {} ] }}\n\n",
+                    key,
+                    fmt_nums(&output_node.expected)
+                )
+            }
+            Node::Random(random_node) => {
+                format!(
+                    "\"{}\" = {{ {NODE_TYPE_KEY} = \"{RANDOM_NODE_TYPE}\", {RANDOM_SEED_KEY} = {} }}\n\n",
+                    key, random_node.seed
+                )
+            }
+        };
+    }
+
+    if let Some(highlighted) = highlighted_node {
+        toml += &format!(
+            "{HIGHLIGHTED_NODE_KEY} = \"{}\"\n\n",
+            fmt_coord(&highlighted)
+        );
+    }
+
+    if !locked.is_empty() {
+        let mut locked: Vec<_> = locked.iter().collect();
+
+        locked.sort();
+
+        let coords = locked
+            .into_iter()
+            .map(|node_loc| format!("\"{}\"", fmt_coord(node_loc)))
+            .intersperse(", ".to_string())
+            .collect::<String>();
+
+        toml += &format!("{LOCKED_NODES_KEY} = [ {coords} ]\n\n");
+    }
+
+    if let Some(Torus { min, max }) = torus {
+        toml += &format!(
+            "{TORUS_KEY} = [ \"{}\", \"{}\" ]\n\n",
+            fmt_coord(&min),
+            fmt_coord(&max)
+        );
+    }
+
+    // saturating is the default, so only the nodes that wrap are listed
+    let wrapping = nodes
+        .iter()
+        .filter(|&(_, node)| {
+            matches!(node, Node::Exec(exec_node) if exec_node.arithmetic == Arithmetic::Wrapping)
+        })
+        .map(|&(node_loc, _)| format!("\"{}\"", fmt_coord(node_loc)))
+        .intersperse(", ".to_string())
+        .collect::<String>();
+
+    if !wrapping.is_empty() {
+        toml += &format!("{WRAPPING_NODES_KEY} = [ {wrapping} ]\n\n");
+    }
+
+    // tables have to come after all of the top-level keys
+    let mut names = nodes
+        .iter()
+        .filter_map(|&(node_loc, node)| Some((node_loc, node.name()?)))
+        .peekable();
+
+    if names.peek().is_some() {
+        toml += &format!("[{NODE_NAMES_KEY}]\n");
+
+        for (node_loc, name) in names {
+            // names can contain quotes, so let toml take care of escaping them
+            toml += &format!(
+                "\"{}\" = {}\n",
+                fmt_coord(node_loc),
+                Value::from(name.as_str())
+            );
+        }
+    }
+
+    toml
+}
+
+/// exports the whole network, including the state of running nodes, as JSON.
+/// the schema is as follows, and should only ever be extended:
+///
+/// ```text
+/// {
+///   "highlighted": { "x": int, "y": int },
+///   "nodes": [                      // ordered by y, then x
+///     {
+///       "x": int,
+///       "y": int,
+///       "name": string | null,
+///       "kind": "exec",
+///       "text": string,
+///       "runtime": null | {         // null while the node is being edited
+///         "acc": int,
+///         "bak": int,
+///         "ip": int,
+///         "io": null | { "sending": dir, "value": int } | { "receiving": dir },
+///         "halted": bool
+///       }
+///     },
+///     {
+///       "x": int,
+///       "y": int,
+///       "name": string | null,
+///       "kind": "input",
+///       "data": [int],
+///       "dir": dir,                 // the side the values are sent from
+///       "index": int | null         // the index of the next value to be sent, if running
+///     },
+///     {
+///       "x": int,
+///       "y": int,
+///       "name": string | null,
+///       "kind": "output",
+///       "received": [int] | null,   // null while the node isn't running
+///       "expected": [int]           // empty if the node isn't being graded
+///     },
+///     {
+///       "x": int,
+///       "y": int,
+///       "name": string | null,
+///       "kind": "random",
+///       "seed": int,
+///       "state": int | null         // the generator's state, if running
+///     }
+///   ]
+/// }
+/// ```
+///
+/// where `dir` is one of `"up"`, `"down"`, `"left"`, `"right"` or `"any"`
+fn to_json(model: &Model) -> String {
+    let mut nodes: Vec<_> = model.nodes.iter().collect();
+
+    nodes.sort_by_key(|&(node_loc, _)| node_loc);
+
+    let nodes = nodes
+        .into_iter()
+        .map(|(node_loc, node)| {
+            let name = node
+                .name()
+                .map_or("null".to_string(), |name| json_string(name));
+
+            let fields = match node {
+                Node::Exec(exec_node) => {
+                    let runtime = match &exec_node.exec {
+                        Some(exec) => {
+                            let io = match exec.io {
+                                NodeIO::None => "null".to_string(),
+                                NodeIO::Outbound(dir, value) => format!(
+                                    r#"{{ "sending": "{}", "value": {value} }}"#,
+                                    dir_name(dir)
+                                ),
+                                NodeIO::OutboundAny(value) => {
+                                    format!(r#"{{ "sending": "any", "value": {value} }}"#)
+                                }
+                                NodeIO::Inbound(dir) => {
+                                    format!(r#"{{ "receiving": "{}" }}"#, dir_name(dir))
+                                }
+                                NodeIO::InboundAny => r#"{ "receiving": "any" }"#.to_string(),
+                            };
+
+                            format!(
+                                r#"{{ "acc": {}, "bak": {}, "ip": {}, "io": {io}, "halted": {} }}"#,
+                                exec.acc, exec.bak, exec.ip, exec.halted
+                            )
+                        }
+                        None => "null".to_string(),
+                    };
+
+                    format!(
+                        r#""kind": "exec", "text": {}, "runtime": {runtime}"#,
+                        json_string(&exec_node.text)
+                    )
+                }
+
+                Node::Input(input_node) => {
+                    let index = input_node
+                        .index
+                        .map_or("null".to_string(), |index| index.to_string());
+
+                    format!(
+                        r#""kind": "input", "data": [{}], "dir": "{}", "index": {index}"#,
+                        fmt_nums(input_node.data()),
+                        dir_name(input_node.dir)
+                    )
+                }
+
+                Node::Output(output_node) => {
+                    let received = match &output_node.received {
+                        Some(received) => format!("[{}]", fmt_nums(received)),
+                        None => "null".to_string(),
+                    };
+
+                    format!(
+                        r#""kind": "output", "received": {received}, "expected": [{}]"#,
+                        fmt_nums(&output_node.expected)
+                    )
+                }
+
+                Node::Random(random_node) => {
+                    let state = random_node
+                        .state
+                        .map_or("null".to_string(), |state| state.to_string());
+
+                    format!(
+                        r#""kind": "random", "seed": {}, "state": {state}"#,
+                        random_node.seed
+                    )
+                }
+            };
+
+            format!(
+                r#"    {{ "x": {}, "y": {}, "name": {name}, {fields} }}"#,
+                node_loc.x, node_loc.y
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",\n");
+
+    format!(
+        "{{\n  \"highlighted\": {{ \"x\": {}, \"y\": {} }},\n  \"nodes\": [\n{nodes}\n  ]\n}}\n",
+        model.highlighted_node.x, model.highlighted_node.y
+    )
+}
+
+/// how directions are spelled in saves and exports
+fn dir_name(dir: Dir) -> &'static str {
+    match dir {
+        Dir::Up => "up",
+        Dir::Down => "down",
+        Dir::Left => "left",
+        Dir::Right => "right",
+    }
+}
+
+fn parse_dir_name(name: &str) -> Option<Dir> {
+    Dir::ALL.into_iter().find(|&dir| dir_name(dir) == name)
+}
+
+fn json_string(str: &str) -> String {
+    let mut json = String::from('"');
+
+    for char in str.chars() {
+        match char {
+            '"' => json += "\\\"",
+            '\\' => json += "\\\\",
+            '\n' => json += "\\n",
+            char if char.is_control() => json += &format!("\\u{:04x}", char as u32),
+            char => json.push(char),
+        }
+    }
+
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::Cell;
+
+    thread_local! {
+        /// how many times `parse_node_text` has run on this thread
+        pub(super) static PARSES: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// somewhere in the temp dir that no other test (or test run) uses at the same time
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("tis-net-{}-{name}", std::process::id()))
+    }
+
+    /// runs a single key press through `handle_input`
+    fn press(model: Model, mods: Modifiers, key: Key) -> Model {
+        let input = Input {
+            mods,
+            pressed: Some(key),
+            window_dimensions: (1024, 1024),
+            mouse_wheel_move: 0.0,
+            mouse_position: Vector2::zero(),
+            clicked: false,
+            drag: None,
+            clipboard: String::new(),
+        };
+
+        match handle_input(
+            model,
+            &input,
+            &Keymap::default(),
+            &Layout::default(),
+            &NodeText::new(),
+        ) {
+            Update::Update { new, .. } => new,
+            Update::Exit => panic!("did not request exit"),
+        }
+    }
+
+    #[test]
+    fn navigation() {
+        let initial_state = init();
+
+        let move_direction = Dir::Down;
+
+        let arrow_key = Key::Arrow(move_direction);
+
+        let initial_highlighted_node = initial_state.model.highlighted_node;
+
+        let input = Input {
+            mods: Modifiers::Ctrl,
+            pressed: Some(arrow_key),
+            window_dimensions: (1024, 1024),
+            mouse_wheel_move: 0.0,
+            mouse_position: Vector2::zero(),
+            clicked: false,
+            drag: None,
+            clipboard: String::new(),
+        };
+
+        let update = update(initial_state, input);
+
+        let (new_state, output) = match update {
+            Update::Exit => panic!("did not request exit"),
+            Update::Update { new, output } => (new, output),
+        };
+
+        assert_eq!(
+            output,
+            Output {
+                clipboard: None,
+                screenshot: None,
+            }
+        );
+
+        let expected_highlighted_node = initial_highlighted_node.neighbor(move_direction);
+
+        assert_eq!(new_state.model.highlighted_node, expected_highlighted_node)
+    }
+
+    #[test]
+    fn lowercase_code() {
+        let code = parse_node_text(&NodeText::from("mov acc down\nAdd Left\njro -1").unwrap())
+            .expect("lowercase code should parse");
+
+        assert!(matches!(code[0].op, Op::Mov(Src::Acc, Dst::Dir(Dir::Down))));
+        assert!(matches!(code[1].op, Op::Add(Src::Dir(Dir::Left))));
+        assert!(matches!(code[2].op, Op::Jro(Src::Imm(num)) if num == Num::from(-1)));
+    }
+
+    #[test]
+    fn smart_home() {
+        let mut node = ExecNode {
+            text: NodeText::from("  MOV ACC DOWN").unwrap(),
+            ..ExecNode::empty()
+        };
+
+        node.end(false);
+
+        node.home(false);
+        assert_eq!(node.cursor, 2);
+
+        node.home(false);
+        assert_eq!(node.cursor, 0);
+
+        node.home(true);
+        assert_eq!(node.cursor, 2);
+        assert_eq!(node.selection(), "  ");
+    }
+
+    #[test]
+    fn empty_code() {
+        let mut exec = NodeExec {
+            acc: Num::ZERO,
+            bak: Num::ZERO,
+            code: NodeCode::new(),
+            io: NodeIO::None,
+            ip: 0,
+            halted: false,
+            steps: 0,
+            inbox: None,
+        };
+
+        exec.inc_ip();
+        assert_eq!(exec.ip, 0);
+
+        exec.jro(Num::from(5));
+        assert_eq!(exec.ip, 0);
+
+        exec.jro(Num::from(-5));
+        assert_eq!(exec.ip, 0);
+    }
+
+    #[test]
+    fn tis100_round_trip() {
+        let nodes = Nodes::from([
+            (
+                NodeCoord::at(0, 0),
+                Node::exec_with_text("MOV UP DOWN", &Layout::default()).unwrap(),
+            ),
+            (
+                NodeCoord::at(3, 1),
+                Node::exec_with_text("L: ADD 1\nJMP L", &Layout::default()).unwrap(),
+            ),
+            (NodeCoord::at(1, 2), Node::empty_exec()),
+            (
+                NodeCoord::at(4, 0),
+                Node::exec_with_text("NOP", &Layout::default()).unwrap(),
+            ),
+            (NodeCoord::at(2, 0), Node::empty_input()),
+        ]);
+
+        let (save, skipped) = export_tis100(&nodes, TIS100_GRID_WIDTH);
+
+        assert_eq!(skipped, [NodeCoord::at(2, 0), NodeCoord::at(4, 0)]);
+
+        let imported = import_tis100(&save, TIS100_GRID_WIDTH).unwrap();
+
+        assert_eq!(imported.len(), 3);
+
+        for (node_loc, node) in imported {
+            let (Node::Exec(imported), Some(Node::Exec(original))) = (node, nodes.get(&node_loc))
+            else {
+                panic!("node at {} didn't round-trip", fmt_coord(&node_loc));
+            };
+
+            assert_eq!(imported.text, original.text);
+        }
+    }
+
+    #[test]
+    fn outputs_csv() {
+        assert_eq!(export_outputs_csv(&Nodes::new()), "");
+
+        let output_with = |received: &[i8]| {
+            Node::Output(OutputNode {
+                received: Some(received.iter().copied().map(Num::from).collect()),
+                ..OutputNode::empty()
+            })
+        };
+
+        let nodes = Nodes::from([
+            (NodeCoord::at(1, 2), output_with(&[4, -5])),
+            (NodeCoord::at(0, 2), output_with(&[1, 2, 3])),
+            (NodeCoord::at(0, 0), Node::empty_exec()),
+        ]);
+
+        assert_eq!(
+            export_outputs_csv(&nodes),
+            "\"0, 2\",\"1, 2\"\n1,4\n2,-5\n3,\n"
+        );
+    }
+
+    #[test]
+    fn deterministic_serialization() {
+        let entries = [
+            (
+                NodeCoord::at(1, 0),
+                Node::exec_with_text("MOV UP DOWN", &Layout::default()).unwrap(),
+            ),
+            (NodeCoord::at(0, 1), Node::empty_input()),
+            (NodeCoord::at(-1, 0), Node::empty_output()),
+            (NodeCoord::at(0, 0), Node::empty_exec()),
+        ];
+
+        // separately built maps iterate in different orders
+        let nodes = Nodes::from(entries.clone());
+        let reversed = entries.into_iter().rev().collect::<Nodes>();
+
+        assert_eq!(
+            serialize_toml(&nodes, Some(NodeCoord::at(0, 0)), &HashSet::new(), None),
+            serialize_toml(&reversed, Some(NodeCoord::at(0, 0)), &HashSet::new(), None)
+        );
+    }
+
+    #[test]
+    fn trailing_label() {
+        let text = NodeText::from("MOV 1 ACC\nJMP END\nEND:").unwrap();
+
+        let code = parse_node_text(&text).unwrap();
+
+        assert!(matches!(code[1].op, Op::Jmp(0)));
+
+        let node_loc = NodeCoord::at(0, 0);
+        let mut nodes = Nodes::from([(
+            node_loc,
+            Node::exec_with_text(&text, &Layout::default()).unwrap(),
+        )]);
+
+        for _ in 0..4 {
+            nodes.extend(step_execution(&nodes, node_loc, None).unwrap().0);
+        }
+
+        let Node::Exec(ExecNode {
+            exec: Some(exec), ..
+        }) = &nodes[&node_loc]
+        else {
+            panic!("node should be running");
+        };
+
+        assert_eq!(exec.ip, 1);
+    }
+
+    #[test]
+    fn invalid_labels() {
+        let problem = |text: &str| {
+            parse_node_text(&NodeText::from(text).unwrap())
+                .err()
+                .map(|err| (err.problem, err.line))
+        };
+
+        for text in ["MOV: NOP", "nop:", ": NOP", "MY LOOP: NOP"] {
+            assert!(matches!(
+                problem(text),
+                Some((ParseProblem::InvalidLabel, 0))
+            ));
+        }
+
+        assert!(matches!(
+            problem("ADD 1\nJRO :"),
+            Some((ParseProblem::InvalidLabel, 1))
+        ));
+
+        // indentation isn't part of the label
+        assert!(problem("  LOOP: ADD 1\n  JMP LOOP").is_none());
+        assert!(problem("MOVE: NOP").is_none());
+    }
+
+    #[test]
+    fn hcf() {
+        let text = NodeText::from("ADD 1\nHCF\nADD 1").unwrap();
+
+        let code = parse_node_text(&text).unwrap();
+
+        assert!(matches!(code[1].op, Op::Hcf));
+
+        let halting = NodeCoord::at(0, 0);
+        let neighbor = NodeCoord::at(1, 0);
+
+        let mut nodes = Nodes::from([
+            (
+                halting,
+                Node::exec_with_text(&text, &Layout::default()).unwrap(),
+            ),
+            (
+                neighbor,
+                Node::exec_with_text("ADD 1", &Layout::default()).unwrap(),
+            ),
+        ]);
+
+        for _ in 0..6 {
+            nodes.extend(step_execution(&nodes, halting, None).unwrap().0);
+        }
+
+        let exec = |node_loc| match &nodes[&node_loc] {
+            Node::Exec(ExecNode {
+                exec: Some(exec), ..
+            }) => exec.clone(),
+            _ => panic!("node should be running"),
+        };
+
+        // the halted node stays on the HCF, but its neighbor keeps running
+        assert!(exec(halting).halted);
+        assert_eq!(exec(halting).ip, 1);
+        assert_eq!(exec(halting).acc, Num::from(1));
+        assert_eq!(exec(neighbor).acc, Num::from(5));
+    }
+
+    #[test]
+    fn input_node_reads() {
+        let mut input_node =
+            InputNode::with_data(ArrayVec::try_from([1, 2].map(Num::from).as_slice()).unwrap());
+
+        assert_eq!(input_node.read(), Some(Num::from(1)));
+        assert_eq!(input_node.read(), Some(Num::from(2)));
+        assert_eq!(input_node.read(), None);
+        assert_eq!(input_node.read(), None);
+
+        assert!(input_node.stop());
+        assert_eq!(input_node.index, None);
+        assert!(!input_node.stop());
+
+        assert_eq!(input_node.read(), Some(Num::from(1)));
+    }
+
+    #[test]
+    fn input_node_direction() {
+        let input = NodeCoord::at(0, 0);
+        let reader = NodeCoord::at(1, 0);
+        let below = NodeCoord::at(0, 1);
+
+        let toml = "\"0,0\" = { type = \"input\", data = [ 5, 6 ], dir = \"right\" }\n\
+                    \"1,0\" = \"MOV LEFT ACC\\nHCF\"\n\
+                    \"0,1\" = \"MOV UP ACC\\nHCF\"\n";
+
+        let (mut nodes, _) = parse_toml(toml).unwrap();
+
+        // it only round-trips as a table if it's not sending down
+        let saved = serialize_toml(&nodes, None, &HashSet::new(), None);
+        assert!(saved.contains(r#""0, 0" = { type = "input", data = [ 5, 6 ], dir = "right" }"#));
+        assert!(matches!(
+            parse_toml(&saved).unwrap().0[&input],
+            Node::Input(InputNode {
+                dir: Dir::Right,
+                ..
+            })
+        ));
+
+        for _ in 0..4 {
+            nodes.extend(step_execution(&nodes, input, None).unwrap().0);
+        }
+
+        let acc = |node_loc| match &nodes[&node_loc] {
+            Node::Exec(ExecNode {
+                exec: Some(exec), ..
+            }) => exec.acc,
+            _ => panic!("node should be running"),
+        };
+
+        assert_eq!(acc(reader), Num::from(5));
+        // the node below is still waiting, since nothing is sent its way
+        assert_eq!(acc(below), Num::ZERO);
+    }
+
+    #[test]
+    fn jro_clamping() {
+        let node_loc = NodeCoord::at(0, 0);
+
+        // the first step only starts the network
+        let ip_after = |text: &str, steps: usize| {
+            let mut nodes = Nodes::from([(
+                node_loc,
+                Node::exec_with_text(text, &Layout::default()).unwrap(),
+            )]);
+
+            for _ in 0..steps {
+                nodes.extend(step_execution(&nodes, node_loc, None).unwrap().0);
+            }
+
+            match &nodes[&node_loc] {
+                Node::Exec(ExecNode {
+                    exec: Some(exec), ..
+                }) => exec.ip,
+                _ => panic!("node should be running"),
+            }
+        };
+
+        assert_eq!(ip_after("NOP\nJRO 100\nNOP\nNOP\nNOP", 3), 4);
+        assert_eq!(ip_after("NOP\nNOP\nNOP\nJRO -100\nNOP", 5), 0);
+        assert_eq!(ip_after("JRO 999", 2), 0);
+        assert_eq!(ip_after("NOP\nNOP\nJRO -1", 4), 1);
+    }
+
+    #[test]
+    fn text_dimensions() {
+        let layout = Layout::default();
+        let full_line = "É".repeat(layout.line_length);
+
+        assert_eq!(validate(&full_line, &layout), Ok(()));
+        assert_eq!(
+            validate(&(full_line.clone() + "É"), &layout),
+            Err(TextOverflow::LineTooLong)
+        );
+
+        // a trailing newline starts another (empty) line
+        let lines = "NOP\n".repeat(layout.lines - 1);
+
+        assert_eq!(validate(&lines, &layout), Ok(()));
+        assert_eq!(
+            validate(&(lines.clone() + "NOP\n"), &layout),
+            Err(TextOverflow::TooManyLines)
+        );
+
+        // loading only stops at what the text buffers can hold, whatever the layout
+        assert!(parse_toml(&format!("\"0,0\" = \"\"\"{lines}NOP\nNOP\"\"\"")).is_ok());
+        assert!(parse_toml(&format!("\"0,0\" = \"{full_line}É\"")).is_ok());
+
+        let too_many_lines = "NOP\n".repeat(MAX_NODE_LINES);
+        let too_long_line = "É".repeat(MAX_NODE_LINE_LENGTH + 1);
+
+        assert!(matches!(
+            parse_toml(&format!("\"0,0\" = \"\"\"{too_many_lines}NOP\"\"\"")),
+            Err(ImportErr::TooManyLines)
+        ));
+        assert!(matches!(
+            parse_toml(&format!("\"0,0\" = \"{too_long_line}\"")),
+            Err(ImportErr::LineTooLong)
+        ));
+
+        // the refused edit is explained until one goes through
+        let mut exec_node = ExecNode {
+            text: NodeText::from(&lines).unwrap(),
+            ..ExecNode::empty()
+        };
+        exec_node.cursor = exec_node.text.len();
+        exec_node.deselect();
+
+        exec_node.insert("\n", &layout);
+        assert_eq!(exec_node.overflow, Some(TextOverflow::TooManyLines));
+        assert_eq!(exec_node.text.as_str(), lines);
+
+        exec_node.insert(&"A".repeat(layout.line_length + 1), &layout);
+        assert_eq!(exec_node.overflow, Some(TextOverflow::LineTooLong));
+
+        // even a line too long for the text buffer itself
+        exec_node.insert(&"A".repeat(NODE_TEXT_BUFFER_SIZE + 1), &layout);
+        assert_eq!(exec_node.overflow, Some(TextOverflow::LineTooLong));
+        assert_eq!(exec_node.text.as_str(), lines);
+
+        exec_node.insert("NOP", &layout);
+        assert_eq!(exec_node.overflow, None);
+
+        // a smaller layout is stricter, but can't outgrow the text buffers
+        let small = Layout::new(3, 2).unwrap();
+
+        assert_eq!(validate("NOP\nNOP", &small), Ok(()));
+        assert_eq!(
+            validate("NOP\nNOP\n", &small),
+            Err(TextOverflow::TooManyLines)
+        );
+        assert_eq!(
+            validate("HCF\nNEG 1", &small),
+            Err(TextOverflow::LineTooLong)
+        );
+        assert_eq!(Layout::new(MAX_NODE_LINE_LENGTH + 1, 15), None);
+        assert_eq!(Layout::new(18, 0), None);
+    }
+
+    #[test]
+    fn node_size() {
+        assert_eq!(Layout::parse("18x15"), Some(Layout::default()));
+        assert_eq!(Layout::parse("36x15"), Layout::new(36, 15));
+        assert_eq!(Layout::parse("37x15"), None);
+        assert_eq!(Layout::parse("18"), None);
+        assert_eq!(Layout::parse("x15"), None);
+
+        // the default is as tall as its text
+        let layout = Layout::default();
+        assert_eq!(
+            layout.inside_side_length(),
+            layout.lines as f32 * NODE_FONT_SIZE
+        );
+
+        // wide or short nodes grow to fit their text and gizmos side by side
+        for (line_length, lines) in [(36, 15), (18, 5), (1, 1), (36, 30)] {
+            let layout = Layout::new(line_length, lines).unwrap();
+
+            assert!(layout.gizmo_width() >= MIN_GIZMO_WIDTH, "{layout:?}");
+            assert!(layout.gizmo_height() >= MIN_GIZMO_HEIGHT, "{layout:?}");
+            assert!(layout.inside_side_length() >= lines as f32 * NODE_FONT_SIZE);
+        }
+    }
+
+    #[test]
+    fn oversized_text_scrolls() {
+        let layout = Layout::default();
+        let node_loc = NodeCoord::at(0, 0);
+
+        // saved with --node-size=36x30, loaded at the default size
+        let long_line = "A".repeat(30);
+        let text = format!("{long_line}\n{}", "NOP\n".repeat(18) + "NOP");
+        let (nodes, _) = parse_toml(&format!("\"0,0\" = \"\"\"{text}\"\"\"")).unwrap();
+
+        let model = Model {
+            nodes,
+            highlighted_node: node_loc,
+            ..init().model
+        };
+
+        let exec_node = |model: &Model| match &model.nodes[&node_loc] {
+            Node::Exec(exec_node) => exec_node.clone(),
+            Node::Input(_) | Node::Output(_) | Node::Random(_) => unreachable!(),
+        };
+
+        assert_eq!(
+            exec_node(&model).room(&layout),
+            Layout::new(30, 20).unwrap()
+        );
+        assert_eq!(exec_node(&model).scroll, (0, 0));
+
+        // the text follows the cursor down, and across the long line
+        let model = (0..16).fold(model, |model, _| {
+            press(model, Modifiers::None, Key::Arrow(Dir::Down))
+        });
+        assert_eq!(exec_node(&model).scroll, (2, 0));
+
+        let model = (0..16).fold(model, |model, _| {
+            press(model, Modifiers::None, Key::Arrow(Dir::Up))
+        });
+        let model = press(model, Modifiers::None, Key::End);
+        assert_eq!(exec_node(&model).scroll, (0, 30 - layout.line_length));
+
+        let model = press(model, Modifiers::None, Key::Home);
+        assert_eq!(exec_node(&model).scroll, (0, 0));
+
+        // the wheel stops at either end
+        let mut scrolled = exec_node(&model);
+        scrolled.scroll_by(100, &layout);
+        assert_eq!(scrolled.scroll, (20 - layout.lines, 0));
+        scrolled.scroll_by(-100, &layout);
+        assert_eq!(scrolled.scroll, (0, 0));
+
+        // it can still be edited, just not made any bigger
+        let model = press(model, Modifiers::None, Key::Arrow(Dir::Down));
+        let model = press(model, Modifiers::None, Key::Char('B'));
+        assert_eq!(exec_node(&model).overflow, None);
+
+        let model = press(model, Modifiers::None, Key::Enter);
+        assert_eq!(exec_node(&model).overflow, Some(TextOverflow::TooManyLines));
+
+        let model = press(model, Modifiers::None, Key::Arrow(Dir::Up));
+        let model = press(model, Modifiers::None, Key::Char('B'));
+        assert_eq!(exec_node(&model).overflow, Some(TextOverflow::LineTooLong));
+        assert_eq!(
+            exec_node(&model).text.as_str(),
+            text.replacen("\nNOP", "\nBNOP", 1)
+        );
+    }
+
+    #[test]
+    fn multibyte_cursor() {
+        let node_loc = NodeCoord::at(0, 0);
+
+        let model = Model {
+            nodes: Nodes::from([(
+                node_loc,
+                Node::exec_with_text("AÉ\n  ÜB", &Layout::default()).unwrap(),
+            )]),
+            highlighted_node: node_loc,
+            ..init().model
+        };
+
+        let state = |model: &Model| match &model.nodes[&node_loc] {
+            Node::Exec(exec_node) => (
+                exec_node.text.to_string(),
+                line_column(&exec_node.text, exec_node.cursor),
+            ),
+            _ => unreachable!(),
+        };
+
+        let arrow = |model, dir| press(model, Modifiers::None, Key::Arrow(dir));
+
+        // one press per char, and the end of the line stops it
+        let model = arrow(arrow(arrow(model, Dir::Right), Dir::Right), Dir::Right);
+        assert_eq!(state(&model).1, (1, 0));
+
+        let model = arrow(model, Dir::Left);
+        assert_eq!(state(&model).1, (0, 2));
+
+        let model = press(model, Modifiers::None, Key::Backspace);
+        assert_eq!(state(&model), ("A\n  ÜB".to_string(), (0, 1)));
+
+        let model = arrow(model, Dir::Down);
+        let model = press(model, Modifiers::None, Key::Home);
+        assert_eq!(state(&model).1, (1, 2));
+
+        let model = press(model, Modifiers::None, Key::Delete);
+        assert_eq!(state(&model), ("A\n  B".to_string(), (1, 2)));
+
+        let model = press(model, Modifiers::None, Key::End);
+        let model = arrow(model, Dir::Up);
+        assert_eq!(state(&model).1, (0, 1));
+    }
+
+    #[test]
+    fn random_node_determinism() {
+        let values = |seed| {
+            let mut random_node = RandomNode::with_seed(seed);
+
+            random_node.start();
+
+            (0..100)
+                .map(|_| {
+                    let num = random_node.current().unwrap();
+                    random_node.advance();
+                    num
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(values(1234), values(1234));
+        assert_ne!(values(1234), values(4321));
+        assert!(values(0).iter().all(|num| (0..100).contains(&num.get())));
+    }
+
+    #[test]
+    fn verify_outputs() {
+        let output = |expected: &[i8], received: &[i8]| {
+            Node::Output(OutputNode {
+                expected: expected.iter().copied().map(Num::from).collect(),
+                received: Some(received.iter().copied().map(Num::from).collect()),
+                ..OutputNode::empty()
+            })
+        };
+
+        let correct = NodeCoord::at(0, 0);
+        let short = NodeCoord::at(1, 0);
+        let wrong = NodeCoord::at(2, 0);
+
+        let nodes = Nodes::from([
+            (correct, output(&[1, 2, 3], &[1, 2, 3])),
+            (short, output(&[1, 2, 3], &[1])),
+            (wrong, output(&[1, 2, 3], &[1, 5])),
+            (NodeCoord::at(3, 0), Node::empty_output()),
+        ]);
+
+        let result = verify(&nodes);
+
+        assert!(!result.passed());
+        assert_eq!(
+            result.checks,
+            [
+                (
+                    correct,
+                    OutputCheck {
+                        first_mismatch: None,
+                        complete: true
+                    }
+                ),
+                (
+                    short,
+                    OutputCheck {
+                        first_mismatch: None,
+                        complete: false
+                    }
+                ),
+                (
+                    wrong,
+                    OutputCheck {
+                        first_mismatch: Some(1),
+                        complete: false
+                    }
+                ),
+            ]
+        );
+
+        assert!(verify(&Nodes::from([(correct, output(&[4], &[4]))])).passed());
+    }
+
+    #[test]
+    fn locked_node_rejects_delete() {
+        let toml = r#"
+            "0,0" = [ 1, 2, 3 ]
+            "0,1" = "MOV UP DOWN"
+            locked = [ "0,0" ]
+        "#;
+
+        let puzzle = parse_puzzle(toml, None).unwrap();
+
+        assert_eq!(puzzle.locked, HashSet::from([NodeCoord::at(0, 0)]));
+
+        let model = Model {
+            nodes: puzzle.nodes,
+            highlighted_node: NodeCoord::at(0, 0),
+            locked: puzzle.locked,
+            ..init().model
+        };
+
+        let (delete_node_mods, delete_node_key) = Action::DeleteNode.default_binding();
+
+        let model = press(model, Modifiers::None, Key::Delete);
+        let model = press(model, delete_node_mods, delete_node_key);
+
+        assert!(matches!(
+            model.nodes.get(&NodeCoord::at(0, 0)),
+            Some(Node::Input(_))
+        ));
+
+        // the exec node below isn't part of the puzzle, so it can still be deleted
+        let model = press(
+            Model {
+                highlighted_node: NodeCoord::at(0, 1),
+                ..model
+            },
+            delete_node_mods,
+            delete_node_key,
+        );
+
+        assert!(!model.nodes.contains_key(&NodeCoord::at(0, 1)));
+    }
+
+    #[test]
+    fn step_report_transfer() {
+        let sender = NodeCoord::at(0, 0);
+        let receiver = NodeCoord::at(0, 1);
+
+        let mut nodes = Nodes::from([
+            (
+                sender,
+                Node::exec_with_text("MOV 5 DOWN\nHCF", &Layout::default()).unwrap(),
+            ),
+            (
+                receiver,
+                Node::exec_with_text("MOV UP ACC\nHCF", &Layout::default()).unwrap(),
+            ),
+        ]);
+
+        let mut transfers = Vec::new();
+        let mut status = NetworkStatus::Running;
+
+        for _ in 0..6 {
+            let (updated_nodes, report) = step_execution(&nodes, sender, None).unwrap();
+
+            nodes.extend(updated_nodes);
+            transfers.extend(report.transfers);
+            status = report.status;
+        }
+
+        assert_eq!(
+            transfers,
+            [Transfer {
+                from: sender,
+                to: receiver,
+                value: Num::from(5)
+            }]
+        );
+
+        // both nodes end up halted, which isn't a deadlock
+        assert_eq!(status, NetworkStatus::Quiescent);
+    }
+
+    #[test]
+    fn snapshot_restore() {
+        let node_loc = NodeCoord::at(0, 0);
+
+        let step = |model: Model| {
+            let mut nodes = model.nodes;
+
+            nodes.extend(step_execution(&nodes, node_loc, None).unwrap().0);
+
+            Model { nodes, ..model }
+        };
+
+        let registers = |model: &Model| match &model.nodes[&node_loc] {
+            Node::Exec(exec_node) => exec_node.registers().expect("node should be running"),
+            _ => unreachable!(),
+        };
+
+        let model = Model {
+            nodes: Nodes::from([(
+                node_loc,
+                Node::exec_with_text("ADD 1\nSAV\nADD 2", &Layout::default()).unwrap(),
+            )]),
+            highlighted_node: node_loc,
+            ..init().model
+        };
+
+        let model = step(step(model));
+        let snapshot = model.snapshot();
+        let before = registers(&model);
+
+        let model = step(step(step(model)));
+
+        assert_ne!(registers(&model), before);
+
+        let model = model.restore(snapshot);
+
+        assert_eq!(registers(&model), before);
+    }
+
+    #[test]
+    fn step_back() {
+        let sender = NodeCoord::at(0, 0);
+        let receiver = NodeCoord::at(0, 1);
+
+        let registers = |model: &Model| {
+            [sender, receiver].map(|node_loc| match &model.nodes[&node_loc] {
+                Node::Exec(exec_node) => exec_node.registers().expect("node should be running"),
+                _ => unreachable!(),
+            })
+        };
+
+        let model = Model {
+            nodes: Nodes::from([
+                (
+                    sender,
+                    Node::exec_with_text("MOV 5 DOWN\nHCF", &Layout::default()).unwrap(),
+                ),
+                (
+                    receiver,
+                    Node::exec_with_text("MOV UP ACC\nADD 1\nHCF", &Layout::default()).unwrap(),
+                ),
+            ]),
+            highlighted_node: sender,
+            ..init().model
+        };
+
+        // the first step only starts the network
+        let model = press(model, Modifiers::None, Key::Tab);
+        let before = registers(&model);
+        let cycle = model.cycle;
+
+        let mut model = model;
+        for _ in 0..3 {
+            model = press(model, Modifiers::None, Key::Tab);
+        }
+
+        assert_ne!(registers(&model), before);
+
+        for _ in 0..3 {
+            model = press(model, Modifiers::Shift, Key::Tab);
+        }
+
+        assert_eq!(registers(&model), before);
+        assert_eq!(model.cycle, cycle);
+
+        // stepping forward again replays the same exchange
+        for _ in 0..3 {
+            model = press(model, Modifiers::None, Key::Tab);
+        }
+
+        assert_eq!(registers(&model)[1].0, Num::from(6));
+
+        let model = press(model, Modifiers::None, Key::Esc);
+
+        assert!(model.history.is_empty());
+    }
+
+    #[test]
+    fn step_back_keeps_edits() {
+        let layout = Layout::default();
+        let running = NodeCoord::at(0, 0);
+        let idle = NodeCoord::at(3, 0);
+        let added = NodeCoord::at(6, 0);
+
+        let model = Model {
+            nodes: Nodes::from([
+                (running, Node::exec_with_text("ADD 1", &layout).unwrap()),
+                (idle, Node::exec_with_text("NOP", &layout).unwrap()),
+            ]),
+            highlighted_node: running,
+            ..init().model
+        };
+
+        let model = press(model, Modifiers::None, Key::Tab);
+        let mut model = press(model, Modifiers::None, Key::Tab);
+
+        // edits made while the network is paused
+        model
+            .nodes
+            .insert(idle, Node::exec_with_text("SWP", &layout).unwrap());
+        model.nodes.insert(added, Node::empty_exec());
+        model.dirty = true;
+
+        let model = press(model, Modifiers::Shift, Key::Tab);
+
+        let text = |node_loc| match &model.nodes[&node_loc] {
+            Node::Exec(exec_node) => exec_node.text.as_str().to_owned(),
+            _ => unreachable!(),
+        };
+
+        assert_eq!(text(idle), "SWP");
+        assert!(model.nodes.contains_key(&added));
+        assert!(model.dirty);
+
+        // while the running node is still rewound
+        match &model.nodes[&running] {
+            Node::Exec(exec_node) => assert_eq!(exec_node.registers().unwrap().0, Num::ZERO),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn delete_cluster() {
+        let node = || Node::Exec(ExecNode::empty());
+
+        // two nodes touching, one touching only diagonally, and one further away
+        let nodes = Nodes::from([
+            (NodeCoord::at(0, 0), node()),
+            (NodeCoord::at(1, 0), node()),
+            (NodeCoord::at(2, 1), node()),
+            (NodeCoord::at(5, 5), node()),
+        ]);
+
+        assert_eq!(
+            connected_nodes(&nodes, NodeCoord::at(1, 0), None),
+            HashSet::from([NodeCoord::at(0, 0), NodeCoord::at(1, 0)])
+        );
+        assert_eq!(
+            connected_nodes(&nodes, NodeCoord::at(5, 5), None),
+            HashSet::from([NodeCoord::at(5, 5)])
+        );
+        assert!(connected_nodes(&nodes, NodeCoord::at(3, 3), None).is_empty());
+
+        let model = Model {
+            nodes,
+            highlighted_node: NodeCoord::at(0, 0),
+            ..init().model
+        };
+
+        // escape backs out without deleting anything
+        let model = press(model, Modifiers::CtrlShift, Key::Delete);
+        assert!(matches!(model.prompt, Some(Prompt::DeleteCluster)));
+
+        let model = press(model, Modifiers::None, Key::Esc);
+        assert!(model.prompt.is_none());
+        assert_eq!(model.nodes.len(), 4);
+
+        let model = press(model, Modifiers::CtrlShift, Key::Delete);
+        let model = press(model, Modifiers::None, Key::Enter);
+
+        assert!(model.prompt.is_none());
+        assert!(model.dirty);
+        assert_eq!(model.highlighted_node, NodeCoord::at(0, 0));
+        assert_eq!(
+            model.nodes.keys().copied().collect::<HashSet<_>>(),
+            HashSet::from([NodeCoord::at(2, 1), NodeCoord::at(5, 5)])
+        );
+
+        // a cluster that's all locked has nothing to delete, so nothing to save either
+        let model = Model {
+            highlighted_node: NodeCoord::at(5, 5),
+            locked: HashSet::from([NodeCoord::at(5, 5)]),
+            dirty: false,
+            ..model
+        };
+        assert!(removable_cluster(&model).is_empty());
+
+        let model = press(model, Modifiers::CtrlShift, Key::Delete);
+        let model = press(model, Modifiers::None, Key::Enter);
+
+        assert!(!model.dirty);
+        assert_eq!(model.nodes.len(), 2);
+    }
+
+    #[test]
+    fn rubber_band() {
+        let layout = Layout::default();
+        let cell_size = layout.cell_size();
+        let node = || Node::Exec(ExecNode::empty());
+
+        let camera = Camera2D {
+            offset: Vector2::zero(),
+            target: Vector2::zero(),
+            rotation: 0.0,
+            zoom: 1.0,
+        };
+
+        // the middle of a cell, in world space, which is also screen space for this camera
+        let middle = |x: f32, y: f32| Vector2::new((x + 0.5) * cell_size, (y + 0.5) * cell_size);
+
+        let model = Model {
+            nodes: Nodes::from([
+                (NodeCoord::at(1, 1), node()),
+                (NodeCoord::at(2, 2), node()),
+                (NodeCoord::at(4, 1), node()),
+            ]),
+            selection: HashSet::from([NodeCoord::at(4, 1)]),
+            ..init().model
+        };
+
+        let drag = |end, released| Drag {
+            start: middle(0.0, 0.0),
+            end,
+            released,
+        };
+
+        let model = handle_drag(
+            model,
+            drag(middle(2.0, 3.0), false),
+            Modifiers::None,
+            camera,
+            &layout,
+        );
+
+        assert!(model.rubber_band.is_some());
+        assert_eq!(model.selection, HashSet::from([NodeCoord::at(4, 1)]));
+
+        let model = handle_drag(
+            model,
+            drag(middle(2.0, 3.0), true),
+            Modifiers::None,
+            camera,
+            &layout,
+        );
+
+        assert!(model.rubber_band.is_none());
+        assert_eq!(
+            model.selection,
+            HashSet::from([NodeCoord::at(1, 1), NodeCoord::at(2, 2)])
+        );
+
+        // dragging backwards works the same, and Shift adds on to the selection
+        let model = handle_drag(
+            model,
+            Drag {
+                start: middle(5.0, 2.0),
+                end: middle(3.0, 1.0),
+                released: true,
+            },
+            Modifiers::Shift,
+            camera,
+            &layout,
+        );
+
+        assert_eq!(model.selection.len(), 3);
+
+        // starting on a node isn't a rubber band
+        let model = handle_drag(
+            model,
+            Drag {
+                start: middle(1.0, 1.0),
+                end: middle(3.0, 3.0),
+                released: false,
+            },
+            Modifiers::None,
+            camera,
+            &layout,
+        );
+
+        assert!(model.rubber_band.is_none());
+        assert_eq!(model.selection.len(), 3);
+    }
+
+    #[test]
+    fn new_workspace() {
+        // the example workspace starts out clean, so it needs an edit to have anything to lose
+        assert!(!init().model.dirty);
+
+        let model = Model {
+            node_clipboard: Some(Node::Exec(ExecNode::empty())),
+            dirty: true,
+            ..init().model
+        };
+
+        let model = press(model, Modifiers::None, Key::Tab);
+        assert!(model.dirty);
+        assert!(is_running(&model.nodes));
+
+        let model = press(model, Modifiers::Ctrl, Key::Char('N'));
+        assert!(matches!(model.prompt, Some(Prompt::NewWorkspace)));
+
+        let model = press(model, Modifiers::None, Key::Esc);
+        assert!(model.prompt.is_none());
+        assert!(!model.nodes.is_empty());
+
+        // saving first, but backing out of the file dialog, loses nothing either
+        let model = press(model, Modifiers::Ctrl, Key::Char('N'));
+        let model = press(model, Modifiers::None, Key::Char('S'));
+        assert!(model.prompt.is_none());
+        assert!(!model.nodes.is_empty());
+        assert!(model.dirty);
+
+        let model = press(model, Modifiers::Ctrl, Key::Char('N'));
+        let model = press(model, Modifiers::None, Key::Enter);
+
+        assert!(model.prompt.is_none());
+        assert!(model.nodes.is_empty());
+        assert_eq!(model.highlighted_node, NodeCoord::at(0, 0));
+        assert!(model.node_clipboard.is_none());
+        assert!(model.history.is_empty());
+        assert_eq!(model.cycle, 0);
+        assert!(!model.dirty);
+
+        // nothing to lose this time, so it doesn't ask
+        let model = press(model, Modifiers::Ctrl, Key::Char('N'));
+        assert!(model.prompt.is_none());
+
+        let state = State {
+            camera: Camera2D {
+                zoom: MAX_ZOOM,
+                ..init().camera
+            },
+            model,
+            ..init()
+        };
+
+        let input = Input {
+            mods: Modifiers::Ctrl,
+            pressed: Some(Key::Char('N')),
+            window_dimensions: (1024, 1024),
+            mouse_wheel_move: 0.0,
+            mouse_position: Vector2::zero(),
+            clicked: false,
+            drag: None,
+            clipboard: String::new(),
+        };
+
+        let Update::Update { new: state, .. } = update(state, input) else {
+            panic!("did not request exit");
+        };
+
+        assert_eq!(state.camera.zoom, DEFAULT_ZOOM);
+        assert_eq!(state.model.view, View::FollowHighlight);
+    }
+
+    #[test]
+    fn no_op_warnings() {
+        let node = Node::exec_with_lines(
+            [
+                "MOV ACC ACC",
+                "MOV 1 ACC",
+                "ADD NIL",
+                "SUB 0",
+                "ADD 1",
+                "MOV NIL NIL # NO",
+            ],
+            &Layout::default(),
+        )
+        .unwrap();
+
+        let Node::Exec(exec_node) = node else {
+            unreachable!()
+        };
+
+        assert!(exec_node.error().is_none());
+        assert_eq!(
+            exec_node
+                .warnings
+                .iter()
+                .map(|warning| (warning.kind, warning.line))
+                .collect::<Vec<_>>(),
+            [
+                (WarningKind::MovesNothing, 0),
+                (WarningKind::AddsZero, 2),
+                (WarningKind::AddsZero, 3),
+                (WarningKind::MovesNothing, 5),
+            ]
+        );
+
+        // they're only hints, the node still runs
+        let nodes = Nodes::from([(NodeCoord::at(0, 0), Node::Exec(exec_node))]);
+        let (nodes, _) = step_execution(&nodes, NodeCoord::at(0, 0), None).unwrap();
+        assert!(is_running(&nodes));
+
+        // code that doesn't parse has an error instead
+        let Node::Exec(broken) =
+            Node::exec_with_text("MOV ACC ACC\nJMP NOWHERE", &Layout::default()).unwrap()
+        else {
+            unreachable!()
+        };
+
+        assert!(broken.error().is_some());
+        assert!(broken.warnings.is_empty());
+    }
 
-enum RepeatKey {
-    None,
-    Held { key: KeyboardKey, repeat_delay: f32 },
-}
+    #[test]
+    fn step_until_change() {
+        let sender = NodeCoord::at(0, 0);
+        let receiver = NodeCoord::at(0, 1);
+
+        let model = Model {
+            nodes: Nodes::from([
+                (
+                    sender,
+                    Node::exec_with_text("NOP\nNOP\nNOP\nMOV 5 DOWN", &Layout::default()).unwrap(),
+                ),
+                (
+                    receiver,
+                    Node::exec_with_text("MOV UP ACC\nADD 1", &Layout::default()).unwrap(),
+                ),
+            ]),
+            highlighted_node: receiver,
+            ..init().model
+        };
 
-fn get_input(rl: &mut RaylibHandle, repeat: &mut RepeatKey) -> Input {
-    let ctrl_held = rl.is_key_down(KeyboardKey::KEY_LEFT_CONTROL)
-        || rl.is_key_down(KeyboardKey::KEY_RIGHT_CONTROL);
+        let acc = |model: &Model| match &model.nodes[&receiver] {
+            Node::Exec(exec_node) => exec_node.registers().unwrap().0,
+            _ => unreachable!(),
+        };
 
-    let shift_held =
-        rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT) || rl.is_key_down(KeyboardKey::KEY_RIGHT_SHIFT);
+        // starting the network changes every node
+        let model = press(model, Modifiers::None, Key::Tab);
+
+        // the receiver sits on its read while the sender idles
+        let model = press(model, Modifiers::CtrlShift, Key::Tab);
+        assert_eq!(acc(&model), Num::ZERO);
+        assert_eq!(model.watch_cycles, Some(1));
+
+        let model = press(model, Modifiers::CtrlShift, Key::Tab);
+        assert_eq!(acc(&model), Num::from(5));
+        assert_eq!(model.watch_cycles, Some(4));
+        assert_eq!(model.cycle, 6);
+
+        // every cycle can be stepped back through
+        assert_eq!(model.history.len(), 6);
+
+        let model = press(model, Modifiers::None, Key::Tab);
+        assert_eq!(model.watch_cycles, None);
+
+        // starting a stopped network already counts as a change
+        let model = Model {
+            nodes: Nodes::from([(
+                sender,
+                Node::exec_with_text("MOV 1 ACC\nHCF", &Layout::default()).unwrap(),
+            )]),
+            highlighted_node: sender,
+            ..init().model
+        };
 
-    let mods = match (ctrl_held, shift_held) {
-        (true, true) => Modifiers::CtrlShift,
-        (true, false) => Modifiers::Ctrl,
-        (false, true) => Modifiers::Shift,
-        (false, false) => Modifiers::None,
-    };
+        let model = press(model, Modifiers::CtrlShift, Key::Tab);
+        assert_eq!(model.watch_cycles, Some(1));
+    }
 
-    let raylib_key_pressed = rl.get_key_pressed();
+    #[test]
+    fn camera_follow() {
+        let layout = Layout::default();
+        let camera = init().camera;
+        let node_loc = NodeCoord::at(0, 1);
+        let center = node_loc.center(&layout);
 
-    let pressed = if let Some(key) = raylib_key_pressed {
-        *repeat = RepeatKey::Held {
-            key,
-            repeat_delay: KEY_REPEAT_DELAY_S,
+        let follow = |follow| update_camera(camera, node_loc, (1024, 1024), 0.0, follow, &layout);
+
+        assert_eq!(follow(1.0).target, center);
+
+        let smooth = follow(DEFAULT_CAMERA_FOLLOW).target;
+        assert!(0.0 < smooth.y && smooth.y < center.y);
+        assert!(follow(MIN_CAMERA_FOLLOW).target.y < smooth.y);
+    }
+
+    #[test]
+    fn copy_workspace() {
+        let model = Model {
+            nodes: Nodes::from([
+                (
+                    NodeCoord::at(0, 0),
+                    Node::exec_with_text("mov up down", &Layout::default()).unwrap(),
+                ),
+                (NodeCoord::at(1, 0), Node::empty_output()),
+            ]),
+            highlighted_node: NodeCoord::at(0, 0),
+            ..init().model
         };
-        raylib_key_pressed.and_then(|rk| Key::from(rk, shift_held))
-    } else if let RepeatKey::Held { key, repeat_delay } = repeat
-        && rl.is_key_down(*key)
-    {
-        *repeat_delay -= rl.get_frame_time();
-        if *repeat_delay <= 0.0 {
-            *repeat_delay = KEY_REPEAT_INTERVAL_S;
-            Key::from(*key, shift_held)
-        } else {
-            None
-        }
-    } else {
-        *repeat = RepeatKey::None;
-        raylib_key_pressed.and_then(|rk| Key::from(rk, shift_held))
-    };
 
-    let clipboard = match rl.get_clipboard_text() {
-        Ok(text) if text.is_ascii() => text.to_ascii_uppercase(),
+        let input = |mods, key, clipboard: &str| Input {
+            mods,
+            pressed: Some(key),
+            window_dimensions: (1024, 1024),
+            mouse_wheel_move: 0.0,
+            mouse_position: Vector2::zero(),
+            clicked: false,
+            drag: None,
+            clipboard: clipboard.to_string(),
+        };
 
-        Ok(_) | Err(_) => String::new(),
-    };
+        let send = |model, input| match handle_input(
+            model,
+            &input,
+            &Keymap::default(),
+            &Layout::default(),
+            &NodeText::new(),
+        ) {
+            Update::Update { new, output } => (new, output.clipboard),
+            Update::Exit => panic!("did not request exit"),
+        };
 
-    Input {
-        mods,
-        pressed,
-        window_dimensions: (rl.get_screen_width(), rl.get_screen_height()),
-        mouse_wheel_move: rl.get_mouse_wheel_move(),
-        clipboard,
+        let (model, copied) = send(model, input(Modifiers::CtrlShift, Key::Char('C'), ""));
+        let copied = copied.unwrap();
+
+        assert!(parse_toml(&copied).is_ok());
+
+        // a vacant cell takes the whole network, with its corner on the highlight
+        let model = Model {
+            highlighted_node: NodeCoord::at(3, 3),
+            ..model
+        };
+
+        let (model, _) = send(model, input(Modifiers::Ctrl, Key::Char('V'), &copied));
+
+        assert_eq!(model.nodes.len(), 4);
+        assert!(matches!(model.nodes[&NodeCoord::at(3, 3)], Node::Exec(_)));
+        assert!(matches!(model.nodes[&NodeCoord::at(4, 3)], Node::Output(_)));
+
+        // it isn't pasted over anything
+        let model = Model {
+            highlighted_node: NodeCoord::at(2, 3),
+            ..model
+        };
+
+        let (model, _) = send(model, input(Modifiers::Ctrl, Key::Char('V'), &copied));
+
+        assert_eq!(model.nodes.len(), 4);
+
+        // while an exec node takes it as code
+        let model = Model {
+            highlighted_node: NodeCoord::at(0, 0),
+            ..model
+        };
+
+        let (model, _) = send(model, input(Modifiers::Ctrl, Key::Char('V'), "add 1"));
+
+        let Node::Exec(exec_node) = &model.nodes[&NodeCoord::at(0, 0)] else {
+            unreachable!()
+        };
+
+        assert!(exec_node.text.contains("ADD 1"));
     }
-}
 
-#[derive(Debug, PartialEq, Eq)]
-struct Output {
-    clipboard: Option<String>,
-}
+    #[test]
+    fn io_log() {
+        let input = NodeCoord::at(0, 0);
+        let relay = NodeCoord::at(0, 1);
+        let output = NodeCoord::at(0, 2);
+
+        let model = Model {
+            nodes: Nodes::from([
+                (
+                    input,
+                    Node::Input(InputNode::from_data_str("4, 5").unwrap()),
+                ),
+                (
+                    relay,
+                    Node::exec_with_text("MOV UP DOWN", &Layout::default()).unwrap(),
+                ),
+                (output, Node::empty_output()),
+            ]),
+            highlighted_node: relay,
+            ..init().model
+        };
 
-enum Update<T> {
-    Exit,
-    Update { new: T, output: Output },
-}
+        let model = (0..6).fold(model, |model, _| press(model, Modifiers::None, Key::Tab));
+
+        let log: Vec<_> = model
+            .io_log
+            .iter()
+            .map(|(cycle, transfer)| fmt_transfer(*cycle, transfer))
+            .collect();
+
+        assert_eq!(
+            log.iter()
+                .filter(|line| line.contains("(0, 1) -> (0, 2)"))
+                .collect::<Vec<_>>(),
+            [
+                "CYCLE 3: (0, 1) -> (0, 2): 4",
+                "CYCLE 5: (0, 1) -> (0, 2): 5"
+            ]
+        );
+        assert!(model.io_log.iter().all(|&(cycle, _)| cycle <= model.cycle));
+
+        // stepping back forgets what arrived in the undone cycles
+        let model = press(model, Modifiers::Shift, Key::Tab);
+        let model = press(model, Modifiers::Shift, Key::Tab);
+        assert!(model.io_log.iter().all(|&(cycle, _)| cycle <= 4));
+        assert!(!model.io_log.is_empty());
+
+        let model = press(model, Modifiers::None, Key::Esc);
+        assert!(model.io_log.is_empty());
+
+        // only so much is kept, the oldest goes first
+        let mut io_log = VecDeque::new();
+        let transfer = Transfer {
+            from: input,
+            to: relay,
+            value: Num::ZERO,
+        };
 
-impl<T> Update<T> {
-    fn no_output(new: T) -> Self {
-        Update::Update {
-            new,
-            output: Output { clipboard: None },
+        for cycle in 0..IO_LOG_DEPTH as u32 + 50 {
+            log_transfers(&mut io_log, cycle, &[transfer]);
         }
+
+        assert_eq!(io_log.len(), IO_LOG_DEPTH);
+        assert_eq!(io_log.front().unwrap().0, 50);
+        assert_eq!(io_log.back().unwrap().0, IO_LOG_DEPTH as u32 + 49);
     }
-}
 
-fn update(state: State, input: Input) -> Update<State> {
-    match handle_input(state.model, &input) {
-        Update::Exit => {
-            return Update::Exit;
+    #[test]
+    fn io_log_scroll() {
+        let input = NodeCoord::at(0, 0);
+        let relay = NodeCoord::at(0, 1);
+
+        let transfer = Transfer {
+            from: input,
+            to: relay,
+            value: Num::ZERO,
+        };
+        let mut io_log = VecDeque::new();
+
+        for cycle in 0..50 {
+            log_transfers(&mut io_log, cycle, &[transfer]);
         }
 
-        Update::Update { new, output } => {
-            let camera = update_camera(
-                state.camera,
-                new.highlighted_node,
-                input.window_dimensions,
-                input.mouse_wheel_move,
-            );
+        let model = Model {
+            nodes: Nodes::from([
+                (
+                    input,
+                    Node::Input(InputNode::from_data_str("4, 5").unwrap()),
+                ),
+                (
+                    relay,
+                    Node::exec_with_text("MOV UP DOWN", &Layout::default()).unwrap(),
+                ),
+            ]),
+            highlighted_node: relay,
+            io_log,
+            ..init().model
+        };
 
-            Update::Update {
-                new: State { camera, model: new },
-                output,
+        // PageUp still cycles through the nodes while the log is hidden
+        let model = press(model, Modifiers::None, Key::PageUp);
+        assert_eq!(model.highlighted_node, input);
+        assert_eq!(model.io_log_scroll, 0);
+
+        // and scrolls no further back than the oldest entry at the top of the panel once it's shown
+        let model = press(model, Modifiers::None, Key::F(5));
+        let model = press(model, Modifiers::None, Key::PageUp);
+        assert_eq!(model.io_log_scroll, IO_LOG_LINES);
+        let model = press(model, Modifiers::None, Key::PageUp);
+        assert_eq!(model.io_log_scroll, 2 * IO_LOG_LINES);
+        let model = press(model, Modifiers::None, Key::PageUp);
+        assert_eq!(model.io_log_scroll, 50 - IO_LOG_LINES);
+        assert_eq!(model.highlighted_node, input);
+
+        let model = press(model, Modifiers::None, Key::PageDown);
+        assert_eq!(model.io_log_scroll, 50 - 2 * IO_LOG_LINES);
+        let model = press(model, Modifiers::None, Key::PageDown);
+        let model = press(model, Modifiers::None, Key::PageDown);
+        assert_eq!(model.io_log_scroll, 0);
+
+        // a new entry scrolls it back to the bottom
+        let model = press(model, Modifiers::None, Key::PageUp);
+        let model = press(model, Modifiers::None, Key::Tab);
+        let model = press(model, Modifiers::None, Key::Tab);
+        assert_eq!(model.io_log.back().unwrap().0, model.cycle);
+        assert_eq!(model.io_log_scroll, 0);
+
+        // a log shorter than the panel doesn't scroll at all
+        assert_eq!(super::scroll_io_log(0, 5, IO_LOG_LINES - 3), 0);
+        assert_eq!(super::scroll_io_log(3, -5, 40), 0);
+        assert_eq!(super::scroll_io_log(3, 50, 40), 40 - IO_LOG_LINES);
+    }
+
+    #[test]
+    fn tab_indents_while_editing() {
+        let node_loc = NodeCoord::at(0, 0);
+
+        let model = Model {
+            nodes: Nodes::from([(
+                node_loc,
+                Node::exec_with_text("ADD 1", &Layout::default()).unwrap(),
+            )]),
+            highlighted_node: node_loc,
+            ..init().model
+        };
+
+        let text = |model: &Model| match &model.nodes[&node_loc] {
+            Node::Exec(exec_node) => {
+                let (_, column) = line_column(&exec_node.text, exec_node.cursor);
+                (exec_node.text.to_string(), column)
             }
-        }
+            _ => unreachable!(),
+        };
+
+        // typing puts the node in focus
+        let model = press(model, Modifiers::None, Key::Char('X'));
+        let model = press(model, Modifiers::None, Key::Backspace);
+        let model = press(model, Modifiers::None, Key::Home);
+        assert_eq!(model.editing, Some(node_loc));
+
+        let model = press(model, Modifiers::None, Key::Tab);
+        assert_eq!(text(&model), ("  ADD 1".to_string(), 2));
+
+        // from an odd column, only as far as the next multiple of the indent
+        let model = (0..3).fold(model, |model, _| {
+            press(model, Modifiers::None, Key::Arrow(Dir::Right))
+        });
+        let model = press(model, Modifiers::None, Key::Tab);
+        assert_eq!(text(&model), ("  ADD  1".to_string(), 6));
+        assert!(!is_running(&model.nodes));
+
+        // Esc leaves the node, and then Tab steps again
+        let model = press(model, Modifiers::None, Key::Esc);
+        assert_eq!(model.editing, None);
+
+        let model = press(model, Modifiers::None, Key::Tab);
+        assert!(is_running(&model.nodes));
+        assert_eq!(text(&model).0, "  ADD  1");
+
+        // moving the highlight away also leaves the node
+        let model = press(model, Modifiers::None, Key::Esc);
+        let model = press(model, Modifiers::None, Key::Char('X'));
+        assert_eq!(model.editing, Some(node_loc));
+
+        let model = press(model, Modifiers::Ctrl, Key::Arrow(Dir::Right));
+        let model = press(model, Modifiers::Ctrl, Key::Arrow(Dir::Left));
+        assert_eq!(model.editing, None);
     }
-}
 
-fn handle_input(model: Model, input: &Input) -> Update<Model> {
-    // the old ghosts value should not be reused, this enforces it
-    std::mem::drop(model.ghosts);
+    #[test]
+    fn only_typing_starts_editing() {
+        let node_loc = NodeCoord::at(0, 0);
+
+        let model = Model {
+            nodes: Nodes::from([(
+                node_loc,
+                Node::exec_with_text("ADD 1\nADD 2", &Layout::default()).unwrap(),
+            )]),
+            highlighted_node: node_loc,
+            ..init().model
+        };
 
-    let ghosts = match input.mods {
-        Modifiers::Ctrl => Ghosts::MoveView,
-        Modifiers::CtrlShift => Ghosts::MoveNode,
-        Modifiers::Shift | Modifiers::None => Ghosts::None,
-    };
+        // the text changes, but nothing was typed, so Tab still steps
+        let model = press(model, Modifiers::Ctrl, Key::Char('/'));
+        assert_eq!(model.editing, None);
 
-    let Some(pressed) = input.pressed else {
-        return Update::no_output(Model { ghosts, ..model });
-    };
+        let model = press(model, Modifiers::None, Key::Tab);
+        assert!(is_running(&model.nodes));
 
-    match (input.mods, pressed) {
-        (_, Key::Esc) => {
-            if let Some(updated_nodes) = stop_execution(&model.nodes, model.highlighted_node) {
-                let mut nodes = model.nodes;
+        // a line that's already full doesn't change, so it isn't marked unsaved either
+        let layout = Layout::default();
+        let full = "#".repeat(layout.line_length);
 
-                nodes.extend(updated_nodes);
+        let model = Model {
+            nodes: Nodes::from([(node_loc, Node::exec_with_text(&full, &layout).unwrap())]),
+            highlighted_node: node_loc,
+            dirty: false,
+            editing: Some(node_loc),
+            ..init().model
+        };
 
-                Update::no_output(Model {
-                    ghosts,
-                    nodes,
-                    ..model
-                })
-            } else {
-                Update::Exit
-            }
+        let model = press(model, Modifiers::None, Key::Home);
+        let model = press(model, Modifiers::None, Key::Tab);
+        assert!(!model.dirty);
+        assert_eq!(model.editing, Some(node_loc));
+
+        // a locked node is never typed into, and isn't indented either
+        let model = Model {
+            locked: HashSet::from([node_loc]),
+            nodes: Nodes::from([(
+                node_loc,
+                Node::exec_with_text("ADD 1", &Layout::default()).unwrap(),
+            )]),
+            editing: Some(node_loc),
+            ..model
+        };
+
+        let model = press(model, Modifiers::None, Key::Tab);
+
+        match &model.nodes[&node_loc] {
+            Node::Exec(exec_node) => assert_eq!(exec_node.text.as_str(), "ADD 1"),
+            _ => unreachable!(),
         }
+        assert!(!model.dirty);
+    }
 
-        (Modifiers::None, Key::Tab) => {
-            if let Some(updated_nodes) = step_execution(&model.nodes, model.highlighted_node) {
-                let mut nodes = model.nodes;
+    #[test]
+    fn shift_tab_dedents_while_editing() {
+        let node_loc = NodeCoord::at(0, 0);
+
+        let model = Model {
+            nodes: Nodes::from([(
+                node_loc,
+                Node::exec_with_text("    ADD 1\nSUB 1\n ADD 2\n  NEG", &Layout::default())
+                    .unwrap(),
+            )]),
+            highlighted_node: node_loc,
+            ..init().model
+        };
 
-                nodes.extend(updated_nodes);
+        let text = |model: &Model| match &model.nodes[&node_loc] {
+            Node::Exec(exec_node) => exec_node.text.to_string(),
+            _ => unreachable!(),
+        };
 
-                Update::no_output(Model {
-                    nodes,
-                    ghosts,
-                    ..model
-                })
-            } else {
-                Update::no_output(Model { ghosts, ..model })
-            }
-        }
+        let model = press(model, Modifiers::None, Key::Char('X'));
+        let model = press(model, Modifiers::None, Key::Backspace);
+
+        // just the cursor's line, by one indent
+        let model = press(model, Modifiers::Shift, Key::Tab);
+        assert_eq!(text(&model), "  ADD 1\nSUB 1\n ADD 2\n  NEG");
+
+        // every selected line, as far as they're indented
+        let model = press(model, Modifiers::Ctrl, Key::Char('A'));
+        let model = press(model, Modifiers::Shift, Key::Tab);
+        assert_eq!(text(&model), "ADD 1\nSUB 1\nADD 2\nNEG");
+
+        let model = press(model, Modifiers::Shift, Key::Tab);
+        assert_eq!(text(&model), "ADD 1\nSUB 1\nADD 2\nNEG");
+
+        // once it's not being typed into, it's stepping back again
+        let model = press(model, Modifiers::None, Key::Esc);
+        let model = press(model, Modifiers::None, Key::Tab);
+        let model = press(model, Modifiers::None, Key::Tab);
+        assert_eq!(model.cycle, 2);
+
+        let model = press(model, Modifiers::Shift, Key::Tab);
+        assert_eq!(model.cycle, 1);
+        assert_eq!(text(&model), "ADD 1\nSUB 1\nADD 2\nNEG");
+
+        // nothing left to take off, so nothing to save
+        let model = press(model, Modifiers::None, Key::Esc);
+        let model = press(model, Modifiers::None, Key::Char('X'));
+        let model = press(model, Modifiers::None, Key::Backspace);
+        let model = Model {
+            dirty: false,
+            ..model
+        };
 
-        (mods @ (Modifiers::None | Modifiers::Shift), Key::Arrow(dir)) => {
-            let mut nodes = model.nodes;
-            match nodes.get_mut(&model.highlighted_node) {
-                Some(Node::Exec(exec_node)) => {
-                    let select = mods == Modifiers::Shift;
+        let model = press(model, Modifiers::Shift, Key::Tab);
+        assert_eq!(model.editing, Some(node_loc));
+        assert!(!model.dirty);
+
+        // and a locked node isn't dedented
+        let model = Model {
+            nodes: Nodes::from([(
+                node_loc,
+                Node::exec_with_text("  ADD 1", &Layout::default()).unwrap(),
+            )]),
+            locked: HashSet::from([node_loc]),
+            ..model
+        };
 
-                    match dir {
-                        Dir::Up => exec_node.up(select),
-                        Dir::Down => exec_node.down(select),
-                        Dir::Left => exec_node.left(select),
-                        Dir::Right => exec_node.right(select),
-                    };
+        let model = press(model, Modifiers::Shift, Key::Tab);
+        assert_eq!(text(&model), "  ADD 1");
+        assert!(!model.dirty);
+    }
 
-                    if !select {
-                        exec_node.deselect();
-                    }
+    #[test]
+    fn parse_once_per_edit() {
+        let parses = || PARSES.with(Cell::get);
 
-                    Update::no_output(Model {
-                        nodes,
-                        ghosts,
-                        ..model
-                    })
-                }
+        let node_loc = NodeCoord::at(0, 0);
 
-                None | Some(Node::Input(_)) => Update::no_output(Model {
-                    nodes,
-                    ghosts,
-                    ..model
-                }),
-            }
-        }
+        let model = Model {
+            nodes: Nodes::from([(node_loc, Node::Exec(ExecNode::empty()))]),
+            highlighted_node: node_loc,
+            ..init().model
+        };
 
-        (Modifiers::Ctrl, Key::Arrow(dir)) => Update::no_output(Model {
-            highlighted_node: model.highlighted_node.neighbor(dir),
-            ghosts,
-            ..model
-        }),
+        let before = parses();
+        let model = "ADD 1".chars().fold(model, |model, char| {
+            press(model, Modifiers::None, Key::Char(char))
+        });
+        assert_eq!(parses() - before, 5);
 
-        (Modifiers::CtrlShift, Key::Arrow(dir)) => {
-            let mut nodes = model.nodes;
-            let src = model.highlighted_node;
-            let dst = model.highlighted_node.neighbor(dir);
-            if nodes.contains_key(&src) && !nodes.contains_key(&dst) {
-                let node = nodes.remove(&src).unwrap();
+        // moving the cursor and looking at the code don't parse it again
+        let before = parses();
+        let model = press(model, Modifiers::None, Key::Arrow(Dir::Left));
+        let model = press(model, Modifiers::Shift, Key::Arrow(Dir::Left));
 
-                nodes.try_insert(dst, node).unwrap();
+        let Node::Exec(exec_node) = &model.nodes[&node_loc] else {
+            unreachable!();
+        };
+        assert!(exec_node.error().is_none());
+        assert_eq!(exec_node.instruction_count(), 1);
+        assert_eq!(network_stats(&model.nodes).instructions, 1);
+        assert_eq!(super::next_error(&model.nodes, node_loc), None);
+
+        // and neither does starting it, once it's no longer being typed into
+        let model = press(model, Modifiers::None, Key::Esc);
+        let model = press(model, Modifiers::None, Key::Tab);
+        assert!(is_running(&model.nodes));
+        assert_eq!(parses(), before);
+    }
 
-                Update::no_output(Model {
-                    nodes,
-                    ghosts,
-                    highlighted_node: dst,
-                    ..model
-                })
-            } else {
-                Update::no_output(Model {
-                    nodes,
-                    ghosts,
-                    ..model
-                })
+    #[test]
+    fn long_chain() {
+        let nodes: Nodes = (0..1000)
+            .map(|x| {
+                (
+                    NodeCoord::at(x, 0),
+                    Node::exec_with_text("ADD 1", &Layout::default()).unwrap(),
+                )
+            })
+            .collect();
+
+        let (nodes, report) = step_execution(&nodes, NodeCoord::at(500, 0), None).unwrap();
+
+        assert_eq!(nodes.len(), 1000);
+        assert_eq!(report.executed.len(), 1000);
+        assert_eq!(
+            connected_nodes(&nodes, NodeCoord::at(0, 0), None).len(),
+            1000
+        );
+
+        let nodes = stop_execution(&nodes, NodeCoord::at(999, 0), None).unwrap();
+
+        assert!(!is_running(&nodes));
+    }
+
+    #[test]
+    fn bench_networks_run() {
+        for (name, mut nodes, starting_node) in bench_networks(&Layout::default()) {
+            for _ in 0..100 {
+                let (updated_nodes, report) = step_execution(&nodes, starting_node, None).unwrap();
+
+                assert_eq!(report.status, NetworkStatus::Running, "{name}");
+                nodes.extend(updated_nodes);
             }
+
+            // every node is part of the network and keeps doing something
+            assert!(
+                nodes.values().all(|node| matches!(
+                    node,
+                    Node::Exec(exec_node) if exec_node.steps() > Some(0)
+                )),
+                "{name}"
+            );
         }
+    }
 
-        (Modifiers::None, Key::Delete) => {
-            let mut nodes = model.nodes;
+    #[test]
+    fn immediates() {
+        assert_eq!(parse_immediate("+5"), Some(Num::from(5)));
+        assert_eq!(parse_immediate("-5"), Some(Num::from(-5)));
+        assert_eq!(parse_immediate("-0"), Some(Num::ZERO));
+        assert_eq!(parse_immediate("007"), Some(Num::from(7)));
+        assert_eq!(parse_immediate("-0999"), Some(Num::MIN));
+
+        for invalid in ["5-", "--5", "+-5", "+", "-", "", "1000", "5A"] {
+            assert_eq!(parse_immediate(invalid), None, "{invalid:?}");
+        }
 
-            nodes.remove(&model.highlighted_node);
+        let code = parse_node_text(&NodeText::from("ADD +5\nSUB 007").unwrap()).unwrap();
 
-            Update::no_output(Model {
-                nodes,
-                ghosts,
-                ..model
+        assert!(matches!(code[0].op, Op::Add(Src::Imm(num)) if num == Num::from(5)));
+        assert!(matches!(code[1].op, Op::Sub(Src::Imm(num)) if num == Num::from(7)));
+
+        assert!(matches!(
+            parse_node_text(&NodeText::from("ADD 5-").unwrap()),
+            Err(ParseErr {
+                problem: ParseProblem::InvalidSrc,
+                line: 0,
+                column: Some(4),
             })
-        }
+        ));
+    }
 
-        (Modifiers::Ctrl, Key::Char('A')) => {
-            let mut nodes = model.nodes;
+    #[test]
+    fn reorder_input_data() {
+        let node_loc = NodeCoord::at(0, 0);
 
-            if let Some(Node::Exec(exec_node)) = nodes.get_mut(&model.highlighted_node) {
-                exec_node.select_all();
+        let mut input_node = InputNode::from_data_str("3, -1, 7, 3").unwrap();
+        input_node.index = Some(2);
 
-                Update::no_output(Model {
-                    nodes,
-                    ghosts,
-                    ..model
-                })
-            } else {
-                Update::no_output(Model {
-                    nodes,
-                    ghosts,
-                    ..model
-                })
-            }
-        }
+        let model = Model {
+            nodes: Nodes::from([(node_loc, Node::Input(input_node))]),
+            highlighted_node: node_loc,
+            dirty: false,
+            ..init().model
+        };
 
-        (Modifiers::Ctrl, Key::Char('C')) => {
-            if let Some(node) = model.nodes.get(&model.highlighted_node) {
-                match node {
-                    Node::Exec(exec_node) if exec_node.text_selected() => {
-                        let selection = exec_node.selection().to_string();
-                        Update::Update {
-                            new: Model {
-                                ghosts,
-                                node_clipboard: None,
-                                ..model
-                            },
-                            output: Output {
-                                clipboard: Some(selection),
-                            },
-                        }
-                    }
+        let data = |model: &Model| match &model.nodes[&node_loc] {
+            Node::Input(input_node) => (fmt_nums(input_node.data()), input_node.index),
+            _ => unreachable!(),
+        };
 
-                    Node::Exec(exec_node) => {
-                        let node_text = exec_node.text.to_string();
+        let model = press(model, Modifiers::Shift, Key::Char('<'));
+        assert_eq!(data(&model), ("-1, 3, 3, 7".to_string(), None));
+        assert!(model.dirty);
 
-                        Update::Update {
-                            new: Model {
-                                ghosts,
-                                node_clipboard: Some(node.clone()),
-                                ..model
-                            },
-                            output: Output {
-                                clipboard: Some(node_text),
-                            },
-                        }
-                    }
+        let model = press(model, Modifiers::Shift, Key::Char('>'));
+        assert_eq!(data(&model), ("7, 3, 3, -1".to_string(), None));
 
-                    // TODO: maybe this should copy the input data to
-                    // the system clipboard too?
-                    Node::Input(_input_node) => Update::no_output(Model {
-                        ghosts,
-                        node_clipboard: Some(node.clone()),
-                        ..model
-                    }),
-                }
-            } else {
-                Update::no_output(Model { ghosts, ..model })
-            }
-        }
+        let model = press(model, Modifiers::None, Key::Char('R'));
+        assert_eq!(data(&model), ("-1, 3, 3, 7".to_string(), None));
 
-        (Modifiers::Ctrl, Key::Char('X')) => {
-            let mut nodes = model.nodes;
+        // anything else is still ignored
+        let model = press(model, Modifiers::None, Key::Char('X'));
+        assert_eq!(data(&model), ("-1, 3, 3, 7".to_string(), None));
+    }
 
-            match nodes.entry(model.highlighted_node) {
-                Entry::Vacant(_) => Update::no_output(Model {
-                    ghosts,
-                    nodes,
-                    ..model
-                }),
+    #[test]
+    fn selection_columns() {
+        let text = "NOP\n\nADD 1";
+
+        let highlight = Highlight::Selected {
+            start_line: 0,
+            start_col: 1,
+            end_line: 2,
+            end_col: 2,
+        };
 
-                Entry::Occupied(mut entry) => match entry.get_mut() {
-                    Node::Exec(exec_node) if exec_node.text_selected() => {
-                        let selection = exec_node.selection().to_string();
+        let columns: Vec<_> = text
+            .split('\n')
+            .enumerate()
+            .map(|(line_no, line_text)| highlight.selected_columns(line_no, line_text))
+            .collect();
+
+        // the empty middle line still shows its newline is selected
+        assert_eq!(columns, [Some((1, 4)), Some((0, 1)), Some((0, 2))]);
+
+        // starting right at the end of a line only selects its newline
+        let highlight = Highlight::Selected {
+            start_line: 0,
+            start_col: 3,
+            end_line: 1,
+            end_col: 0,
+        };
 
-                        exec_node.insert("");
+        assert_eq!(highlight.selected_columns(0, "NOP"), Some((3, 4)));
+        assert_eq!(highlight.selected_columns(1, ""), Some((0, 0)));
+        assert_eq!(highlight.selected_columns(2, "ADD 1"), None);
 
-                        Update::Update {
-                            new: Model {
-                                ghosts,
-                                nodes,
-                                ..model
-                            },
-                            output: Output {
-                                clipboard: Some(selection),
-                            },
-                        }
-                    }
+        // the last line never goes past its text
+        let highlight = Highlight::Selected {
+            start_line: 0,
+            start_col: 0,
+            end_line: 0,
+            end_col: 9,
+        };
 
-                    Node::Exec(_) | Node::Input(_) => {
-                        let cut_node = entry.remove();
+        assert_eq!(highlight.selected_columns(0, "NOP"), Some((0, 3)));
+        assert_eq!(Highlight::None.selected_columns(0, "NOP"), None);
+    }
 
-                        Update::no_output(Model {
-                            ghosts,
-                            nodes,
-                            node_clipboard: Some(cut_node),
-                            ..model
-                        })
-                    }
-                },
+    #[test]
+    fn wrapping_nodes() {
+        let saturating = NodeCoord::at(0, 0);
+        let wrapping = NodeCoord::at(2, 0);
+
+        let mut nodes = parse_toml(
+            r#"
+"0,0" = "ADD 999\nADD 1"
+"2,0" = "ADD 999\nADD 1"
+wrapping = [ "2,0" ]
+"#,
+        )
+        .unwrap()
+        .0;
+
+        for node_loc in [saturating, wrapping] {
+            for _ in 0..3 {
+                nodes.extend(step_execution(&nodes, node_loc, None).unwrap().0);
             }
         }
 
-        (Modifiers::Ctrl, Key::Char('V')) => {
-            let mut nodes = model.nodes;
+        let acc = |node_loc| match &nodes[&node_loc] {
+            Node::Exec(exec_node) => exec_node.registers().unwrap().0,
+            _ => unreachable!(),
+        };
 
-            match (&model.node_clipboard, nodes.entry(model.highlighted_node)) {
-                (Some(copied_node), Entry::Vacant(vacant_entry)) => {
-                    vacant_entry.insert(copied_node.clone());
+        assert_eq!(acc(saturating), Num::MAX);
+        assert_eq!(acc(wrapping), Num::MIN);
 
-                    Update::no_output(Model {
-                        nodes,
-                        ghosts,
-                        ..model
-                    })
-                }
+        let saved = serialize_toml(&nodes, None, &HashSet::new(), None);
+        assert!(saved.contains("wrapping = [ \"2, 0\" ]"));
 
-                (_, Entry::Occupied(mut occupied_entry)) => match occupied_entry.get_mut() {
-                    Node::Exec(exec_node) => {
-                        exec_node.insert(&input.clipboard);
+        let (loaded, _) = parse_toml(&saved).unwrap();
 
-                        Update::no_output(Model {
-                            ghosts,
-                            nodes,
-                            ..model
-                        })
-                    }
+        let arithmetic = |node_loc| match &loaded[&node_loc] {
+            Node::Exec(exec_node) => exec_node.arithmetic,
+            _ => unreachable!(),
+        };
 
-                    Node::Input(_) => Update::no_output(Model {
-                        ghosts,
-                        nodes,
-                        ..model
-                    }),
-                },
+        assert_eq!(arithmetic(saturating), Arithmetic::Saturating);
+        assert_eq!(arithmetic(wrapping), Arithmetic::Wrapping);
 
-                (None, Entry::Vacant(_)) => Update::no_output(Model {
-                    ghosts,
-                    nodes,
-                    ..model
-                }),
-            }
-        }
+        assert!(matches!(
+            parse_toml("wrapping = \"2,0\""),
+            Err(ImportErr::InvalidWrappingRhs)
+        ));
+    }
 
-        (Modifiers::Ctrl, Key::Char('O')) => {
-            if let Some(path) = rfd::FileDialog::new()
-                .set_title("Load TIS workspace from file")
-                .add_filter("TIS workspace", &["toml"])
-                .pick_file()
-            {
-                match std::fs::read_to_string(path) {
-                    Ok(toml) => match parse_toml(&toml) {
-                        Ok((nodes, highlighted_node)) => Update::no_output(Model {
-                            nodes,
-                            highlighted_node,
-                            ghosts,
-                            ..model
-                        }),
+    #[test]
+    fn next_error() {
+        let exec = |text| Node::exec_with_text(text, &Layout::default()).unwrap();
+
+        let model = Model {
+            nodes: Nodes::from([
+                (NodeCoord::at(0, 0), exec("NOP")),
+                (NodeCoord::at(2, 0), exec("JMP NOWHERE")),
+                (NodeCoord::at(0, 1), exec("FOO")),
+                (NodeCoord::at(1, 1), Node::empty_input()),
+            ]),
+            highlighted_node: NodeCoord::at(5, 5),
+            ..init().model
+        };
 
-                        Err(import_err) => {
-                            let origin = NodeCoord::at(0, 0);
-                            let description = match import_err {
-                                ImportErr::InvalidToml => "# INVALID TOML",
-                                ImportErr::InvalidCoord => "# INVALID COORD",
-                                ImportErr::NodeTextDoesntFit => "# CODE DOESN'T FIT",
-                                ImportErr::InvalidRhs => "# INVALID RHS",
-                                ImportErr::DuplicateCoord => "# DUPLICATE COORD",
-                                ImportErr::InvalidHighlightRhs => "# INVALID LOC",
-                                ImportErr::IntOutOfRange => "# INT OVERFLOW",
-                                ImportErr::NotAnInt => "# NOT AN INT",
-                            };
+        // from past the last error it wraps back to the first
+        let model = press(model, Modifiers::None, Key::F(8));
+        assert_eq!(model.highlighted_node, NodeCoord::at(2, 0));
 
-                            let node =
-                                Node::exec_with_lines(["## ERROR", "", description]).unwrap();
+        let model = press(model, Modifiers::None, Key::F(8));
+        assert_eq!(model.highlighted_node, NodeCoord::at(0, 1));
 
-                            let nodes = Nodes::from([(origin, node)]);
+        let model = press(model, Modifiers::None, Key::F(8));
+        assert_eq!(model.highlighted_node, NodeCoord::at(2, 0));
 
-                            Update::no_output(Model {
-                                nodes,
-                                ghosts,
-                                ..model
-                            })
-                        }
-                    },
+        let model = Model {
+            nodes: Nodes::from([(NodeCoord::at(0, 0), exec("NOP"))]),
+            highlighted_node: NodeCoord::at(3, 3),
+            ..model
+        };
 
-                    Err(_) => {
-                        let origin = NodeCoord::at(0, 0);
+        let model = press(model, Modifiers::None, Key::F(8));
+        assert_eq!(model.highlighted_node, NodeCoord::at(3, 3));
+    }
 
-                        let node = Node::exec_with_lines([
-                            "## ERROR",
-                            "",
-                            "# COULD NOT OPEN",
-                            "# SPECIFIED FILE",
-                        ])
-                        .unwrap();
+    #[test]
+    fn snap_to_nearest() {
+        assert_eq!(NodeCoord::at(0, 0).distance(NodeCoord::at(0, 0)), 0);
+        assert_eq!(NodeCoord::at(-2, 1).distance(NodeCoord::at(1, -3)), 7);
+        assert_eq!(NodeCoord::at(1, -3).distance(NodeCoord::at(-2, 1)), 7);
+
+        let node = || Node::Exec(ExecNode::empty());
+
+        let nodes = Nodes::from([
+            (NodeCoord::at(0, 0), node()),
+            (NodeCoord::at(10, 0), node()),
+            (NodeCoord::at(6, 4), node()),
+        ]);
+
+        assert_eq!(
+            nearest_node(&nodes, NodeCoord::at(8, 0)),
+            Some(NodeCoord::at(10, 0))
+        );
+        assert_eq!(
+            nearest_node(&nodes, NodeCoord::at(6, 4)),
+            Some(NodeCoord::at(6, 4))
+        );
+        // both are 4 away, the one in the higher row wins
+        assert_eq!(
+            nearest_node(&nodes, NodeCoord::at(8, 2)),
+            Some(NodeCoord::at(10, 0))
+        );
+        assert_eq!(nearest_node(&Nodes::new(), NodeCoord::at(3, 3)), None);
 
-                        let nodes = Nodes::from([(origin, node)]);
+        let model = Model {
+            nodes,
+            highlighted_node: NodeCoord::at(-50, 20),
+            ..init().model
+        };
 
-                        Update::no_output(Model {
-                            nodes,
-                            ghosts,
-                            ..model
-                        })
-                    }
-                }
-            } else {
-                Update::no_output(Model { ghosts, ..model })
-            }
-        }
+        let model = press(model, Modifiers::Ctrl, Key::Home);
+        assert_eq!(model.highlighted_node, NodeCoord::at(0, 0));
+    }
 
-        (Modifiers::Ctrl, Key::Char('S')) => {
-            if let Some(path) = rfd::FileDialog::new()
-                .set_title("Save TIS workspace to file")
-                .add_filter("TIS workspace", &["toml"])
-                .set_file_name("my_tis_workspace.toml")
-                .save_file()
-            {
-                let toml = serialize_toml(&model.nodes, Some(model.highlighted_node));
+    #[test]
+    fn check_workspace_report() {
+        let toml = r#"
+"0, 0" = """
+MOV UP DOWN
+"""
+
+"1, -2" = """
+# FINE SO FAR
+ADD 1
+JMP NOWHERE
+"""
+
+"2, 0" = [ 1, 2, 3 ]
+"#;
+
+        assert_eq!(
+            check_workspace(toml, None).unwrap(),
+            ["1,-2:3: UNDEFINED LABEL"]
+        );
 
-                match std::fs::write(path, toml) {
-                    Ok(()) => Update::no_output(Model { ghosts, ..model }),
+        assert!(
+            check_workspace(
+                &serialize_toml(&init().model.nodes, None, &HashSet::new(), None),
+                None,
+            )
+            .unwrap()
+            .is_empty()
+        );
 
-                    Err(err) => {
-                        // TODO: show this to the user
-                        println!("io error while saving file: {:?}", err);
-                        Update::no_output(Model { ghosts, ..model })
-                    }
-                }
-            } else {
-                Update::no_output(Model { ghosts, ..model })
-            }
-        }
+        assert!(matches!(
+            check_workspace("not toml", None),
+            Err(ImportErr::InvalidToml)
+        ));
 
-        (Modifiers::None | Modifiers::Shift, Key::Char(char)) => {
-            let mut nodes = model.nodes;
+        let path = temp_path("check-test.toml");
+        std::fs::write(&path, toml).unwrap();
 
-            match nodes.entry(model.highlighted_node) {
-                Entry::Occupied(mut occupied) => {
-                    match occupied.get_mut() {
-                        Node::Exec(exec_node) => {
-                            // apparently this is the easiest way to turn a `char` into a `&str`
-                            // (without allocating a single-char `String` first`)
-                            let mut buf = [0; std::mem::size_of::<char>()];
+        assert_eq!(check(path.to_str().unwrap()), 1);
 
-                            exec_node.insert(char.encode_utf8(&mut buf));
-                        }
+        std::fs::remove_file(&path).unwrap();
+    }
 
-                        Node::Input(_) => {
-                            // TODO: handle direct node input?
-                        }
-                    }
-                }
+    #[test]
+    fn open_line() {
+        let layout = Layout::default();
 
-                Entry::Vacant(vacant) => match char {
-                    'E' => {
-                        vacant.insert(Node::empty_exec());
-                    }
-                    'I' => {
-                        vacant.insert(Node::empty_input());
-                    }
-                    _ => {}
-                },
-            }
+        let Node::Exec(mut exec_node) = Node::exec_with_text("ADD 1\nSUB 2\nNEG", &layout).unwrap()
+        else {
+            unreachable!()
+        };
 
-            Update::no_output(Model {
-                nodes,
-                ghosts,
-                ..model
-            })
-        }
+        // the middle of "SUB 2"
+        exec_node.cursor = 8;
+        exec_node.deselect();
+
+        exec_node.open_line_below(&layout);
+        assert_eq!(exec_node.text.as_str(), "ADD 1\nSUB 2\n\nNEG");
+        assert_eq!(line_column(&exec_node.text, exec_node.cursor), (2, 0));
+
+        exec_node.open_line_above(&layout);
+        assert_eq!(exec_node.text.as_str(), "ADD 1\nSUB 2\n\n\nNEG");
+        assert_eq!(line_column(&exec_node.text, exec_node.cursor), (2, 0));
+
+        // at either end of the text
+        exec_node.cursor = 0;
+        exec_node.open_line_above(&layout);
+        assert!(exec_node.text.starts_with("\nADD 1"));
+        assert_eq!(exec_node.cursor, 0);
+
+        exec_node.cursor = exec_node.text.len();
+        exec_node.open_line_below(&layout);
+        assert!(exec_node.text.ends_with("NEG\n"));
+        assert_eq!(exec_node.cursor, exec_node.text.len());
+
+        // a full node refuses, and stays as it was
+        let full = vec!["NOP"; layout.lines].join("\n");
+
+        let model = Model {
+            nodes: Nodes::from([(
+                NodeCoord::at(0, 0),
+                Node::exec_with_text(&full, &layout).unwrap(),
+            )]),
+            highlighted_node: NodeCoord::at(0, 0),
+            ..init().model
+        };
 
-        (mods @ (Modifiers::None | Modifiers::Shift), Key::Home) => {
-            let mut nodes = model.nodes;
+        let model = press(model, Modifiers::Ctrl, Key::Enter);
+        let model = press(model, Modifiers::CtrlShift, Key::Enter);
+
+        let Node::Exec(exec_node) = &model.nodes[&NodeCoord::at(0, 0)] else {
+            unreachable!()
+        };
+
+        assert_eq!(exec_node.text.as_str(), full);
+        assert!(matches!(
+            exec_node.overflow,
+            Some(TextOverflow::TooManyLines)
+        ));
+        assert!(!model.dirty);
+
+        // and so do the other edits, when there's nothing for them to do
+        let model = press(model, Modifiers::None, Key::Enter);
+        let model = press(model, Modifiers::Ctrl, Key::Char('A'));
+        let model = press(model, Modifiers::Ctrl, Key::Char('/'));
+        let model = press(model, Modifiers::Ctrl, Key::Char('/'));
+        let model = press(model, Modifiers::None, Key::Home);
+        assert!(model.dirty);
+
+        let model = Model {
+            dirty: false,
+            ..model
+        };
+
+        // the start of the text, then the end
+        let model = (0..layout.lines).fold(model, |model, _| {
+            press(model, Modifiers::None, Key::Arrow(Dir::Up))
+        });
+        let model = press(model, Modifiers::None, Key::Home);
+        let model = press(model, Modifiers::None, Key::Backspace);
+        let model = (0..layout.lines).fold(model, |model, _| {
+            press(model, Modifiers::None, Key::Arrow(Dir::Down))
+        });
+        let model = press(model, Modifiers::None, Key::End);
+        let model = press(model, Modifiers::None, Key::Delete);
+        let model = press(model, Modifiers::None, Key::Enter);
+
+        let model = press(model, Modifiers::Ctrl, Key::Char('H'));
+        let model = press(model, Modifiers::None, Key::Char('X'));
+        let model = press(model, Modifiers::None, Key::Enter);
+        let model = press(model, Modifiers::None, Key::Char('Y'));
+        let model = press(model, Modifiers::None, Key::Enter);
+
+        let Node::Exec(exec_node) = &model.nodes[&NodeCoord::at(0, 0)] else {
+            unreachable!()
+        };
+
+        assert_eq!(exec_node.text.as_str(), full);
+        assert!(!model.dirty);
+    }
+
+    #[test]
+    fn input_data_file() {
+        let dir = temp_path("data-file-test");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        std::fs::write(dir.join("data.txt"), "1, 2\n-3\n\n999\n").unwrap();
+        std::fs::write(dir.join("big.txt"), "1\n1000\n").unwrap();
+        std::fs::write(dir.join("long.txt"), "1,".repeat(INPUT_NODE_CAP + 1)).unwrap();
+
+        let parse = |toml: &str| parse_puzzle(toml, Some(&dir));
+
+        let puzzle = parse(
+            r#"
+"0,0" = { file = "data.txt" }
+"1,0" = { type = "input", file = "data.txt", dir = "right" }
+"#,
+        )
+        .unwrap();
+
+        let Node::Input(input_node) = &puzzle.nodes[&NodeCoord::at(0, 0)] else {
+            panic!("not an input node");
+        };
+        assert_eq!(
+            input_node.data(),
+            [Num::from(1), Num::from(2), Num::from(-3), Num::MAX]
+        );
+        assert_eq!(input_node.dir, Dir::Down);
+
+        let Node::Input(input_node) = &puzzle.nodes[&NodeCoord::at(1, 0)] else {
+            panic!("not an input node");
+        };
+        assert_eq!(input_node.data().len(), 4);
+        assert_eq!(input_node.dir, Dir::Right);
+
+        assert!(matches!(
+            parse(r#""0,0" = { file = "missing.txt" }"#),
+            Err(ImportErr::UnreadableDataFile)
+        ));
+        assert!(matches!(
+            parse(r#""0,0" = { file = "big.txt" }"#),
+            Err(ImportErr::IntOutOfRange)
+        ));
+        assert!(matches!(
+            parse(r#""0,0" = { file = "long.txt" }"#),
+            Err(ImportErr::TooManyValues)
+        ));
+        // there's nowhere to look for the file without a workspace path
+        assert!(matches!(
+            parse_toml(r#""0,0" = { file = "data.txt" }"#),
+            Err(ImportErr::UnreadableDataFile)
+        ));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 
-            if let Some(Node::Exec(exec_node)) = nodes.get_mut(&model.highlighted_node) {
-                let select = mods == Modifiers::Shift;
+    #[test]
+    fn keymap_from_toml() {
+        let default = Keymap::default();
+
+        // anything that can't be used keeps the default binding
+        assert_eq!(Keymap::from_toml("not toml =").bindings, default.bindings);
+
+        let keymap = Keymap::from_toml(
+            r#"
+fly = "F10"
+step = "Ctrl+Nope"
+save = 5
+fit_all = "Alt+F"
+"#,
+        );
 
-                exec_node.home(select);
+        assert_eq!(keymap.action(Modifiers::None, Key::Tab), Some(Action::Step));
+        assert_eq!(
+            keymap.action(Modifiers::Ctrl, Key::Char('S')),
+            Some(Action::Save)
+        );
+        assert_eq!(
+            keymap.action(Modifiers::Alt, Key::Char('F')),
+            Some(Action::FitAll)
+        );
+        assert_eq!(keymap.action(Modifiers::Ctrl, Key::Char('0')), None);
 
-                if !select {
-                    exec_node.deselect();
-                }
-            }
+        // taking another action's combo is refused, rather than leaving that action unbound
+        let keymap = Keymap::from_toml(r#"step = "Ctrl+S""#);
 
-            Update::no_output(Model {
-                nodes,
-                ghosts,
-                ..model
-            })
-        }
+        assert_eq!(keymap.bindings, default.bindings);
 
-        (mods @ (Modifiers::None | Modifiers::Shift), Key::End) => {
-            let mut nodes = model.nodes;
+        // unless that action is moved as well
+        let keymap = Keymap::from_toml(
+            r#"
+save = "Tab"
+step = "Ctrl+S"
+"#,
+        );
 
-            if let Some(Node::Exec(exec_node)) = nodes.get_mut(&model.highlighted_node) {
-                let select = mods == Modifiers::Shift;
+        assert_eq!(keymap.action(Modifiers::None, Key::Tab), Some(Action::Save));
+        assert_eq!(
+            keymap.action(Modifiers::Ctrl, Key::Char('S')),
+            Some(Action::Step)
+        );
 
-                exec_node.end(select);
+        // but not onto the same combo as each other
+        let keymap = Keymap::from_toml(
+            r#"
+save = "F10"
+step = "F10"
+"#,
+        );
 
-                if !select {
-                    exec_node.deselect();
-                }
-            }
+        assert_eq!(
+            keymap.action(Modifiers::None, Key::F(10)),
+            Some(Action::Save)
+        );
+        assert_eq!(keymap.action(Modifiers::None, Key::Tab), Some(Action::Step));
+        assert_eq!(keymap.bindings.len(), default.bindings.len());
+    }
 
-            Update::no_output(Model {
-                nodes,
-                ghosts,
-                ..model
-            })
-        }
+    #[test]
+    fn every_key_converts() {
+        use KeyboardKey as RK;
 
-        (Modifiers::None, Key::Backspace) => {
-            let mut nodes = model.nodes;
+        let raylib_keys = [
+            RK::KEY_NULL,
+            RK::KEY_APOSTROPHE,
+            RK::KEY_COMMA,
+            RK::KEY_MINUS,
+            RK::KEY_PERIOD,
+            RK::KEY_SLASH,
+            RK::KEY_SEMICOLON,
+            RK::KEY_EQUAL,
+            RK::KEY_ZERO,
+            RK::KEY_ONE,
+            RK::KEY_TWO,
+            RK::KEY_THREE,
+            RK::KEY_FOUR,
+            RK::KEY_FIVE,
+            RK::KEY_SIX,
+            RK::KEY_SEVEN,
+            RK::KEY_EIGHT,
+            RK::KEY_NINE,
+            RK::KEY_A,
+            RK::KEY_B,
+            RK::KEY_C,
+            RK::KEY_D,
+            RK::KEY_E,
+            RK::KEY_F,
+            RK::KEY_G,
+            RK::KEY_H,
+            RK::KEY_I,
+            RK::KEY_J,
+            RK::KEY_K,
+            RK::KEY_L,
+            RK::KEY_M,
+            RK::KEY_N,
+            RK::KEY_O,
+            RK::KEY_P,
+            RK::KEY_Q,
+            RK::KEY_R,
+            RK::KEY_S,
+            RK::KEY_T,
+            RK::KEY_U,
+            RK::KEY_V,
+            RK::KEY_W,
+            RK::KEY_X,
+            RK::KEY_Y,
+            RK::KEY_Z,
+            RK::KEY_LEFT_BRACKET,
+            RK::KEY_BACKSLASH,
+            RK::KEY_RIGHT_BRACKET,
+            RK::KEY_GRAVE,
+            RK::KEY_SPACE,
+            RK::KEY_ESCAPE,
+            RK::KEY_ENTER,
+            RK::KEY_TAB,
+            RK::KEY_BACKSPACE,
+            RK::KEY_INSERT,
+            RK::KEY_DELETE,
+            RK::KEY_RIGHT,
+            RK::KEY_LEFT,
+            RK::KEY_DOWN,
+            RK::KEY_UP,
+            RK::KEY_PAGE_UP,
+            RK::KEY_PAGE_DOWN,
+            RK::KEY_HOME,
+            RK::KEY_END,
+            RK::KEY_CAPS_LOCK,
+            RK::KEY_SCROLL_LOCK,
+            RK::KEY_NUM_LOCK,
+            RK::KEY_PRINT_SCREEN,
+            RK::KEY_PAUSE,
+            RK::KEY_F1,
+            RK::KEY_F2,
+            RK::KEY_F3,
+            RK::KEY_F4,
+            RK::KEY_F5,
+            RK::KEY_F6,
+            RK::KEY_F7,
+            RK::KEY_F8,
+            RK::KEY_F9,
+            RK::KEY_F10,
+            RK::KEY_F11,
+            RK::KEY_F12,
+            RK::KEY_LEFT_SHIFT,
+            RK::KEY_LEFT_CONTROL,
+            RK::KEY_LEFT_ALT,
+            RK::KEY_LEFT_SUPER,
+            RK::KEY_RIGHT_SHIFT,
+            RK::KEY_RIGHT_CONTROL,
+            RK::KEY_RIGHT_ALT,
+            RK::KEY_RIGHT_SUPER,
+            RK::KEY_KB_MENU,
+            RK::KEY_KP_0,
+            RK::KEY_KP_1,
+            RK::KEY_KP_2,
+            RK::KEY_KP_3,
+            RK::KEY_KP_4,
+            RK::KEY_KP_5,
+            RK::KEY_KP_6,
+            RK::KEY_KP_7,
+            RK::KEY_KP_8,
+            RK::KEY_KP_9,
+            RK::KEY_KP_DECIMAL,
+            RK::KEY_KP_DIVIDE,
+            RK::KEY_KP_MULTIPLY,
+            RK::KEY_KP_SUBTRACT,
+            RK::KEY_KP_ADD,
+            RK::KEY_KP_ENTER,
+            RK::KEY_KP_EQUAL,
+            RK::KEY_BACK,
+            RK::KEY_VOLUME_UP,
+            RK::KEY_VOLUME_DOWN,
+        ];
 
-            if let Some(Node::Exec(exec_node)) = nodes.get_mut(&model.highlighted_node) {
-                exec_node.backspace();
+        for raylib_key in raylib_keys {
+            for shift_held in [false, true] {
+                // only checking it doesn't panic
+                let _ = Key::from(raylib_key, shift_held);
             }
-
-            Update::no_output(Model {
-                nodes,
-                ghosts,
-                ..model
-            })
         }
 
-        (mods @ (Modifiers::None | Modifiers::Shift), Key::Enter) => {
-            let mut nodes = model.nodes;
+        assert_eq!(Key::from(RK::KEY_BACK, false), None);
+    }
 
-            if let Some(Node::Exec(exec_node)) = nodes.get_mut(&model.highlighted_node) {
-                let select = mods == Modifiers::Shift;
+    #[test]
+    fn step_counts() {
+        let writer = NodeCoord::at(0, 0);
+        let reader = NodeCoord::at(1, 0);
+
+        let mut nodes = Nodes::from([
+            (
+                writer,
+                Node::exec_with_text("NOP\nNOP\nMOV 1 RIGHT\nHCF", &Layout::default()).unwrap(),
+            ),
+            (
+                reader,
+                Node::exec_with_text("MOV LEFT ACC", &Layout::default()).unwrap(),
+            ),
+        ]);
+
+        let steps = |nodes: &Nodes| match &nodes[&reader] {
+            Node::Exec(exec_node) => exec_node.steps(),
+            _ => unreachable!(),
+        };
 
-                exec_node.enter(select);
-            }
+        assert_eq!(steps(&nodes), None);
 
-            Update::no_output(Model {
-                nodes,
-                ghosts,
-                ..model
-            })
-        }
+        let mut counts = Vec::new();
 
-        (
-            Modifiers::Ctrl | Modifiers::CtrlShift,
-            Key::Backspace
-            | Key::Delete
-            | Key::Enter
-            | Key::Home
-            | Key::End
-            | Key::Tab
-            | Key::Char(_),
-        )
-        | (Modifiers::Shift, Key::Backspace | Key::Delete | Key::Tab) => {
-            Update::no_output(Model { ghosts, ..model })
+        for _ in 0..6 {
+            nodes.extend(step_execution(&nodes, writer, None).unwrap().0);
+            counts.push(steps(&nodes).unwrap());
         }
-    }
-}
 
-fn stop_execution(nodes: &Nodes, starting_node: NodeCoord) -> Option<Nodes> {
-    let new_nodes = Nodes::new();
+        // starting doesn't count, then three cycles blocked on the read, then the read itself
+        assert_eq!(counts, [0, 0, 0, 0, 1, 1]);
+        assert_eq!(busiest_node(&nodes), Some((writer, 4)));
 
-    match seek_nodes(nodes, new_nodes, starting_node, &mut stop_node_execution) {
-        Ok(new_nodes) => Some(new_nodes),
-        Err(_) => None,
+        let nodes = stop_execution(&nodes, writer, None).unwrap();
+
+        assert_eq!(steps(&nodes), None);
+        assert_eq!(busiest_node(&nodes), None);
     }
-}
 
-fn stop_node_execution(
-    old_nodes: &Nodes,
-    mut new_nodes: Nodes,
-    node_loc: NodeCoord,
-) -> Result<Nodes, Nodes> {
-    let Some(mut node) = old_nodes.get(&node_loc).cloned() else {
-        return Err(new_nodes);
-    };
+    #[test]
+    fn read_only() {
+        let model = Model {
+            read_only: true,
+            dirty: false,
+            ..init().model
+        };
 
-    if new_nodes.contains_key(&node_loc) {
-        return Err(new_nodes);
-    }
+        let nodes_before = serialize_toml(&model.nodes, None, &model.locked, None);
+        let highlighted_node = model.highlighted_node;
+
+        let edits = [
+            (Modifiers::None, Key::Char('X')),
+            (Modifiers::None, Key::Enter),
+            (Modifiers::None, Key::Backspace),
+            (Modifiers::None, Key::Delete),
+            (Modifiers::Ctrl, Key::Delete),
+            (Modifiers::CtrlShift, Key::Delete),
+            (Modifiers::Ctrl, Key::Char('X')),
+            (Modifiers::Ctrl, Key::Char('V')),
+            (Modifiers::Ctrl, Key::Enter),
+            (Modifiers::Ctrl, Key::Char('N')),
+            (Modifiers::CtrlShift, Key::Arrow(Dir::Right)),
+        ];
 
-    match &mut node {
-        Node::Exec(exec_node) => {
-            if exec_node.exec.is_some() {
-                exec_node.exec = None;
-                new_nodes.insert(node_loc, node);
-                Ok(new_nodes)
-            } else {
-                Err(new_nodes)
-            }
-        }
+        let model = edits
+            .into_iter()
+            .fold(model, |model, (mods, key)| press(model, mods, key));
 
-        Node::Input(input_node) => {
-            if input_node.index.is_some() {
-                input_node.index = None;
-                new_nodes.insert(node_loc, node);
-                Ok(new_nodes)
-            } else {
-                Err(new_nodes)
-            }
-        }
-    }
-}
+        // even on an empty cell, nothing gets added
+        let model = press(model, Modifiers::Ctrl, Key::Arrow(Dir::Up));
+        let model = press(model, Modifiers::None, Key::Char('X'));
 
-fn step_execution(nodes: &Nodes, starting_node: NodeCoord) -> Option<Nodes> {
-    let new_nodes = Nodes::new();
+        assert_eq!(
+            serialize_toml(&model.nodes, None, &model.locked, None),
+            nodes_before
+        );
+        assert!(!model.dirty);
+        assert!(model.prompt.is_none());
 
-    match seek_nodes(nodes, new_nodes, starting_node, &mut step_node_execution) {
-        Ok(new_nodes) => Some(new_nodes),
-        Err(_) => None,
-    }
-}
+        // but it can still be looked around and run
+        assert_eq!(model.highlighted_node, highlighted_node.neighbor(Dir::Up));
 
-fn seek_nodes(
-    old_nodes: &Nodes,
-    mut new_nodes: Nodes,
-    start_loc: NodeCoord,
-    transform: &mut impl FnMut(&Nodes, Nodes, NodeCoord) -> Result<Nodes, Nodes>,
-) -> Result<Nodes, Nodes> {
-    new_nodes = transform(old_nodes, new_nodes, start_loc)?;
+        let model = press(model, Modifiers::Ctrl, Key::Arrow(Dir::Down));
+        let model = press(model, Modifiers::None, Key::Tab);
 
-    for neighbor_dir in Dir::ALL {
-        let neighbor_loc = start_loc.neighbor(neighbor_dir);
+        assert!(is_running(&model.nodes));
+    }
 
-        new_nodes = match seek_nodes(old_nodes, new_nodes, neighbor_loc, transform) {
-            Ok(nodes) => nodes,
-            Err(nodes) => nodes,
+    #[test]
+    fn any_write_priority() {
+        let writer = NodeCoord::at(1, 1);
+        let left = NodeCoord::at(0, 1);
+        let below = NodeCoord::at(1, 2);
+
+        let mut nodes = Nodes::from([
+            (
+                writer,
+                Node::exec_with_text("MOV 7 ANY\nHCF", &Layout::default()).unwrap(),
+            ),
+            (
+                left,
+                Node::exec_with_text("MOV RIGHT ACC\nHCF", &Layout::default()).unwrap(),
+            ),
+            (
+                below,
+                Node::exec_with_text("MOV UP ACC\nHCF", &Layout::default()).unwrap(),
+            ),
+        ]);
+
+        let mut transfers = Vec::new();
+
+        for _ in 0..6 {
+            let (updated_nodes, report) = step_execution(&nodes, writer, None).unwrap();
+
+            nodes.extend(updated_nodes);
+            transfers.extend(report.transfers);
         }
+
+        // LEFT comes before DOWN when writing to ANY
+        assert_eq!(
+            transfers,
+            [Transfer {
+                from: writer,
+                to: left,
+                value: Num::from(7)
+            }]
+        );
     }
 
-    Ok(new_nodes)
-}
+    #[test]
+    fn any_up_versus_left() {
+        let middle = NodeCoord::at(1, 1);
+        let above = NodeCoord::at(1, 0);
+        let left = NodeCoord::at(0, 1);
+
+        let first_transfer = |texts: [&str; 3]| {
+            let [middle_text, above_text, left_text] = texts;
+
+            let mut nodes = Nodes::from(
+                [
+                    (middle, middle_text),
+                    (above, above_text),
+                    (left, left_text),
+                ]
+                .map(|(node_loc, text)| {
+                    (
+                        node_loc,
+                        Node::exec_with_text(text, &Layout::default()).unwrap(),
+                    )
+                }),
+            );
 
-fn step_node_execution(
-    old_nodes: &Nodes,
-    mut new_nodes: Nodes,
-    node_loc: NodeCoord,
-) -> Result<Nodes, Nodes> {
-    let Some(mut node) = old_nodes.get(&node_loc).cloned() else {
-        return Err(new_nodes);
-    };
+            let mut transfers = Vec::new();
 
-    if new_nodes.contains_key(&node_loc) {
-        return Err(new_nodes);
+            for _ in 0..4 {
+                let (updated_nodes, report) = step_execution(&nodes, middle, None).unwrap();
+
+                nodes.extend(updated_nodes);
+                transfers.extend(report.transfers);
+            }
+
+            transfers[0]
+        };
+
+        // writing to ANY offers LEFT the value before UP
+        let transfer = first_transfer(["MOV 7 ANY\nHCF", "MOV DOWN ACC", "MOV RIGHT ACC"]);
+        assert_eq!((transfer.from, transfer.to), (middle, left));
+
+        // while reading from ANY takes UP's value before LEFT's
+        let transfer = first_transfer(["MOV ANY ACC\nHCF", "MOV 1 DOWN", "MOV 2 RIGHT"]);
+        assert_eq!((transfer.from, transfer.to), (above, middle));
+        assert_eq!(transfer.value, Num::from(1));
     }
 
-    match &mut node {
-        Node::Exec(exec_node) => {
-            let Some(ref mut exec) = exec_node.exec else {
-                if let Ok(exec) = NodeExec::init(&exec_node.text)
-                    && !exec.code.is_empty()
-                {
-                    exec_node.exec = Some(exec);
-                    new_nodes.insert(node_loc, node);
-                    return Ok(new_nodes);
-                } else {
-                    new_nodes.insert(node_loc, node);
-                    return Err(new_nodes);
-                }
-            };
+    #[test]
+    fn running_node_rejects_typing() {
+        let node_loc = NodeCoord::at(0, 0);
+
+        let model = Model {
+            nodes: Nodes::from([(
+                node_loc,
+                Node::exec_with_text("ADD 1", &Layout::default()).unwrap(),
+            )]),
+            highlighted_node: node_loc,
+            ..init().model
+        };
 
-            if let NodeIO::Outbound(_, _) = exec.io {
-                new_nodes.try_insert(node_loc, node).unwrap();
-                return Ok(new_nodes);
-            }
+        let model = press(model, Modifiers::None, Key::Tab);
 
-            match exec.code[exec.ip as usize].op {
-                Op::Mov(src, dst) => {
-                    if let Some(value) =
-                        get_src_value(exec, node_loc, old_nodes, &mut new_nodes, src)
-                    {
-                        match dst {
-                            Dst::Acc => {
-                                exec.acc = value;
-                                exec.inc_ip();
-                            }
-                            Dst::Dir(target_dir) => exec.io = NodeIO::Outbound(target_dir, value),
-                            Dst::Nil => exec.inc_ip(),
-                        }
-                    }
-                }
-                Op::Nop => exec.inc_ip(),
-                Op::Swp => {
-                    (exec.acc, exec.bak) = (exec.bak, exec.acc);
-                    exec.inc_ip();
-                }
-                Op::Sav => {
-                    exec.bak = exec.acc;
-                    exec.inc_ip();
-                }
-                Op::Add(src) => {
-                    if let Some(value) =
-                        get_src_value(exec, node_loc, old_nodes, &mut new_nodes, src)
-                    {
-                        exec.acc = exec.acc.saturating_add(value);
-                        exec.inc_ip();
-                    }
-                }
-                Op::Sub(src) => {
-                    if let Some(value) =
-                        get_src_value(exec, node_loc, old_nodes, &mut new_nodes, src)
-                    {
-                        exec.acc = exec.acc.saturating_sub(value);
-                        exec.inc_ip();
-                    }
-                }
-                Op::Neg => {
-                    exec.acc = -exec.acc;
-                    exec.inc_ip();
-                }
-                Op::Jmp(target) => exec.ip = target,
-                Op::Jez(target) => {
-                    if exec.acc == 0 {
-                        exec.ip = target
-                    } else {
-                        exec.inc_ip();
-                    }
-                }
-                Op::Jnz(target) => {
-                    if exec.acc != 0 {
-                        exec.ip = target
-                    } else {
-                        exec.inc_ip();
-                    }
-                }
-                Op::Jgz(target) => {
-                    if exec.acc > 0 {
-                        exec.ip = target
-                    } else {
-                        exec.inc_ip();
-                    }
-                }
-                Op::Jlz(target) => {
-                    if exec.acc < 0 {
-                        exec.ip = target
-                    } else {
-                        exec.inc_ip();
-                    }
-                }
-                Op::Jro(src) => {
-                    if let Some(value) =
-                        get_src_value(exec, node_loc, old_nodes, &mut new_nodes, src)
-                    {
-                        exec.jro(value);
-                    }
-                }
-            }
-        }
+        let model = [Key::Char('X'), Key::Enter, Key::Backspace, Key::Delete]
+            .into_iter()
+            .fold(model, |model, key| press(model, Modifiers::None, key));
 
-        Node::Input(input_node) => {
-            if input_node.index.is_none() {
-                input_node.index = Some(0);
-            }
-        }
+        let Node::Exec(exec_node) = &model.nodes[&node_loc] else {
+            panic!("node should still be an exec node");
+        };
+
+        assert_eq!(exec_node.text.as_str(), "ADD 1");
+        assert!(exec_node.exec.is_some());
     }
 
-    new_nodes.try_insert(node_loc, node).unwrap();
+    #[test]
+    fn breakpoints() {
+        let node_loc = NodeCoord::at(0, 0);
 
-    Ok(new_nodes)
-}
+        let mut exec_node = ExecNode {
+            text: NodeText::from("ADD 1\nADD 2\nADD 3").unwrap(),
+            ..ExecNode::empty()
+        };
+        exec_node.reparse();
 
-fn get_src_value(
-    exec: &mut NodeExec,
-    node_loc: NodeCoord,
-    old_nodes: &Nodes,
-    new_nodes: &mut Nodes,
-    src: Src,
-) -> Option<Num> {
-    match src {
-        Src::Imm(num) => Some(num),
-        Src::Acc => Some(exec.acc),
-        Src::Dir(target_dir) => {
-            exec.io = NodeIO::Inbound(target_dir);
+        exec_node.cursor = exec_node.target(2, 0);
+        exec_node.toggle_breakpoint();
+
+        assert_eq!(exec_node.breakpoints, HashSet::from([2]));
+
+        let model = Model {
+            nodes: Nodes::from([(node_loc, Node::Exec(exec_node))]),
+            highlighted_node: node_loc,
+            ..init().model
+        };
 
-            let neighbor_loc = node_loc.neighbor(target_dir);
-            let neighbor = old_nodes.get(&neighbor_loc)?;
+        let (mods, key) = Action::FastForward.default_binding();
+        let model = press(model, mods, key);
 
-            match neighbor {
-                Node::Exec(exec_node) => {
-                    let mut neighbor = exec_node.clone();
-                    let neighbor_exec = neighbor.exec.as_mut()?;
+        let Node::Exec(exec_node) = &model.nodes[&node_loc] else {
+            panic!("node should still be an exec node");
+        };
 
-                    if let NodeIO::Outbound(neighbor_outbound_dir, value) = neighbor_exec.io
-                        && neighbor_outbound_dir == target_dir.inverse()
-                    {
-                        neighbor_exec.inc_ip();
+        // starting, then the first two instructions
+        assert_eq!(model.cycle, 3);
+        assert_eq!(exec_node.exec.as_ref().unwrap().ip, 2);
+
+        // stopping keeps the breakpoint, but adding a line drops it
+        let (mods, key) = Action::Stop.default_binding();
+        let mut model = press(model, mods, key);
 
-                        neighbor_exec.io = NodeIO::None;
-                        exec.io = NodeIO::None;
+        let Some(Node::Exec(exec_node)) = model.nodes.get_mut(&node_loc) else {
+            panic!("node should still be an exec node");
+        };
 
-                        new_nodes.insert(neighbor_loc, Node::Exec(neighbor));
+        assert_eq!(exec_node.breakpoints, HashSet::from([2]));
 
-                        Some(value)
-                    } else {
-                        None
-                    }
-                }
+        exec_node.insert("\n", &Layout::default());
 
-                Node::Input(input_node) if target_dir == Dir::Up => {
-                    let mut neighbor = input_node.clone();
-                    let current = neighbor.current();
-                    let neighbor_index = neighbor.index.as_mut()?;
+        assert!(exec_node.breakpoints.is_empty());
+    }
 
-                    if let Some(num) = current {
-                        *neighbor_index += 1;
+    #[test]
+    fn insert_normalizes_whitespace() {
+        let mut exec_node = ExecNode::empty();
 
-                        new_nodes.insert(neighbor_loc, Node::Input(neighbor));
+        exec_node.insert("\tMOV UP DOWN\r\nADD 1\r\n", &Layout::default());
 
-                        Some(num)
-                    } else {
-                        None
-                    }
-                }
+        assert_eq!(exec_node.text.as_str(), "  MOV UP DOWN\nADD 1\n");
+        assert_eq!(exec_node.cursor, exec_node.text.len());
+        assert!(exec_node.error().is_none());
+    }
 
-                Node::Input(_) => None,
-            }
+    #[test]
+    fn page_through_nodes() {
+        let first = NodeCoord::at(5, -2);
+        let second = NodeCoord::at(-3, 0);
+        let third = NodeCoord::at(1, 0);
+
+        let mut model = Model {
+            nodes: Nodes::from([
+                (third, Node::empty_exec()),
+                (first, Node::empty_input()),
+                (second, Node::empty_output()),
+            ]),
+            // an empty cell in between the nodes
+            highlighted_node: NodeCoord::at(0, -1),
+            ..init().model
+        };
+
+        let mut highlights = Vec::new();
+
+        for key in [Key::PageDown; 4].into_iter().chain([Key::PageUp; 4]) {
+            model = press(model, Modifiers::None, key);
+            highlights.push(model.highlighted_node);
         }
-        Src::Nil => Some(0),
+
+        assert_eq!(
+            highlights,
+            [second, third, first, second, first, third, second, first]
+        );
     }
-}
 
-fn update_camera(
-    camera: Camera2D,
-    highlighted_node: NodeCoord,
-    window_dimensions: (i32, i32),
-    mouse_wheel_move: f32,
-) -> Camera2D {
-    let target =
-        camera.target + ((highlighted_node.center() - camera.target) * 0.7).clamp(-200.0..200.0);
+    #[test]
+    fn corrupt_cursors() {
+        let mut exec_node = ExecNode {
+            text: NodeText::from("ADD 1").unwrap(),
+            cursor: 40,
+            select_cursor: 90,
+            ..ExecNode::empty()
+        };
 
-    let zoom = (camera.zoom + mouse_wheel_move * 0.2).clamp(0.5, 4.0);
+        exec_node.insert("\nNEG", &Layout::default());
 
-    let offset = Vector2 {
-        x: window_dimensions.0 as f32 / 2.,
-        y: window_dimensions.1 as f32 / 2.,
-    };
+        assert_eq!(exec_node.text.as_str(), "ADD 1\nNEG");
+        assert_eq!(exec_node.cursor, exec_node.text.len());
+        assert_eq!(exec_node.select_cursor, exec_node.cursor);
 
-    Camera2D {
-        target,
-        zoom,
-        offset,
-        ..camera
+        exec_node.cursor = 100;
+        exec_node.backspace(&Layout::default());
+        exec_node.select_cursor = 100;
+        exec_node.delete_forward(&Layout::default());
+
+        assert_eq!(exec_node.text.as_str(), "ADD 1\nNE");
     }
-}
 
-type Num = i8;
+    #[test]
+    fn delete_key() {
+        let layout = Layout::default();
 
-type NodeCode<Label = u8> = ArrayVec<Instruction<Label>, NODE_LINES>;
+        let Node::Exec(mut exec_node) = Node::exec_with_text("ADD 1\nSUB 2", &layout).unwrap()
+        else {
+            unreachable!()
+        };
 
-#[derive(Clone, Debug)]
-struct NodeExec {
-    acc: Num,
-    bak: Num,
-    code: NodeCode,
-    io: NodeIO,
-    ip: u8,
-}
+        // nothing after the end to delete
+        exec_node.cursor = exec_node.text.len();
+        exec_node.deselect();
+        exec_node.delete_forward(&layout);
+        assert_eq!(exec_node.text.as_str(), "ADD 1\nSUB 2");
+        assert_eq!(exec_node.overflow, None);
+
+        // just the selection, and nothing after it
+        exec_node.select_cursor = 2;
+        exec_node.cursor = 8;
+        exec_node.delete_forward(&layout);
+        assert_eq!(exec_node.text.as_str(), "ADB 2");
+        assert_eq!(exec_node.cursor, 2);
+        assert!(!exec_node.text_selected());
+
+        // joining two lines that won't fit on one is refused
+        let half = "A".repeat(layout.line_length / 2 + 1);
+        let Node::Exec(mut exec_node) =
+            Node::exec_with_text(&format!("{half}\n{half}"), &layout).unwrap()
+        else {
+            unreachable!()
+        };
 
-#[derive(PartialEq, Eq, Clone, Debug)]
-enum NodeIO {
-    None,
-    Outbound(Dir, Num),
-    Inbound(Dir),
-}
+        exec_node.cursor = half.len();
+        exec_node.deselect();
+        exec_node.delete_forward(&layout);
+        assert_eq!(exec_node.text.as_str(), format!("{half}\n{half}"));
+        assert_eq!(exec_node.overflow, Some(TextOverflow::LineTooLong));
 
-impl NodeExec {
-    fn init(node_text: &NodeText) -> Result<Self, ParseErr> {
-        let code = parse_node_text(node_text)?;
+        // an input node goes, as a whole
+        let node_loc = NodeCoord::at(0, 0);
 
-        Ok(Self {
-            acc: 0,
-            bak: 0,
-            code,
-            io: NodeIO::None,
-            ip: 0,
-        })
-    }
+        let model = Model {
+            nodes: Nodes::from([(node_loc, Node::empty_input())]),
+            highlighted_node: node_loc,
+            ..init().model
+        };
 
-    fn inc_ip(&mut self) {
-        self.ip += 1;
-        if self.ip as usize >= self.code.len() {
-            self.ip = 0;
-        }
-    }
+        let model = press(model, Modifiers::None, Key::Delete);
+        assert!(model.nodes.is_empty());
+        assert!(model.dirty);
+
+        // but a running exec node stays as it is
+        let model = Model {
+            nodes: Nodes::from([(
+                node_loc,
+                Node::exec_with_text("ADD 1\nSUB 2", &layout).unwrap(),
+            )]),
+            dirty: false,
+            ..model
+        };
 
-    fn jro(&mut self, offset: Num) {
-        if offset < 0 {
-            self.ip = self.ip.saturating_sub(offset.abs() as u8);
-        } else {
-            self.ip = self.ip.saturating_add(offset as u8);
-            if self.ip as usize >= self.code.len() {
-                self.ip = (self.code.len() - 1) as u8;
+        let model = press(model, Modifiers::None, Key::Tab);
+        let model = press(model, Modifiers::None, Key::Delete);
+
+        match &model.nodes[&node_loc] {
+            Node::Exec(exec_node) => {
+                assert!(exec_node.exec.is_some());
+                assert_eq!(exec_node.text.as_str(), "ADD 1\nSUB 2");
             }
+            _ => unreachable!(),
         }
+        assert!(!model.dirty);
     }
-}
 
-#[derive(Clone, Copy, Debug)]
-struct Instruction<Label: Debug + Copy = u8> {
-    op: Op<Label>,
-    src_line: u8,
-}
+    #[test]
+    fn comment_toggle() {
+        let layout = Layout::default();
 
-#[derive(Clone, Copy, Debug)]
-enum Op<Label: Debug + Copy> {
-    Mov(Src, Dst),
-    Nop,
-    Swp,
-    Sav,
-    Add(Src),
-    Sub(Src),
-    Neg,
-    Jmp(Label),
-    Jez(Label),
-    Jnz(Label),
-    Jgz(Label),
-    Jlz(Label),
-    Jro(Src),
-}
+        let Node::Exec(mut exec_node) =
+            Node::exec_with_text("ADD 1\nSUB 2\n\nNEG\nSWP", &layout).unwrap()
+        else {
+            unreachable!()
+        };
 
-#[derive(Clone, Copy, Debug)]
-enum Src {
-    Imm(Num),
-    Dir(Dir),
-    Acc,
-    Nil,
-}
+        let selected_lines = |exec_node: &ExecNode| {
+            let (start, end) = exec_node.selection_range();
 
-#[derive(Clone, Copy, Debug)]
-enum Dst {
-    Dir(Dir),
-    Acc,
-    Nil,
-}
+            (
+                line_column(&exec_node.text, start),
+                line_column(&exec_node.text, end),
+            )
+        };
 
-#[derive(Clone, Debug)]
-struct ParseErr {
-    problem: ParseProblem,
-    line: u8,
-}
+        // from the start of "SUB 2" into the middle of "NEG", across a blank line
+        exec_node.select_cursor = 6;
+        exec_node.cursor = 15;
+        assert_eq!(selected_lines(&exec_node), ((1, 0), (3, 2)));
+
+        exec_node.toggle_comment(&layout);
+        assert_eq!(exec_node.text.as_str(), "ADD 1\n# SUB 2\n\n# NEG\nSWP");
+        assert_eq!(selected_lines(&exec_node), ((1, 0), (3, 4)));
+
+        // every line that isn't blank is commented, so they're all uncommented
+        exec_node.toggle_comment(&layout);
+        assert_eq!(exec_node.text.as_str(), "ADD 1\nSUB 2\n\nNEG\nSWP");
+        assert_eq!(selected_lines(&exec_node), ((1, 0), (3, 2)));
+
+        // with and without the space
+        let Node::Exec(mut exec_node) =
+            Node::exec_with_text("#ADD 1\n# SUB 2\n  ", &layout).unwrap()
+        else {
+            unreachable!()
+        };
 
-#[derive(Clone, Debug)]
-enum ParseProblem {
-    NotEnoughArgs,
-    TooManyArgs,
-    InvalidSrc,
-    InvalidDst,
-    InvalidInstruction,
-    UndefinedLabel,
-}
+        exec_node.select_all();
+        exec_node.toggle_comment(&layout);
+        assert_eq!(exec_node.text.as_str(), "ADD 1\nSUB 2\n  ");
 
-impl ParseProblem {
-    fn to_str(&self) -> &'static str {
-        match self {
-            ParseProblem::NotEnoughArgs => "NOT ENOUGH ARGS",
-            ParseProblem::TooManyArgs => "TOO MANY ARGS",
-            ParseProblem::InvalidSrc => "INVALID SOURCE ARG",
-            ParseProblem::InvalidDst => "INVALID DESTINATION ARG",
-            ParseProblem::InvalidInstruction => "INVALID OPCODE",
-            ParseProblem::UndefinedLabel => "UNDEFINED LABEL",
-        }
+        // a line that's already as long as it can be has no room for the #
+        let full = "A".repeat(layout.line_length);
+        let Node::Exec(mut exec_node) =
+            Node::exec_with_text(&format!("NOP\n{full}"), &layout).unwrap()
+        else {
+            unreachable!()
+        };
+
+        exec_node.select_all();
+        exec_node.toggle_comment(&layout);
+        assert_eq!(exec_node.text.as_str(), format!("NOP\n{full}"));
+        assert_eq!(exec_node.overflow, Some(TextOverflow::LineTooLong));
     }
-}
 
-fn parse_node_text(node_text: &NodeText) -> Result<NodeCode, ParseErr> {
-    let mut code = NodeCode::<&str>::new();
+    #[test]
+    fn error_wrapping() {
+        let fits = |text: &str| text.len() <= 12;
 
-    // maps labels to instruction indices
-    let mut labels: HashMap<&str, u8> = HashMap::new();
+        assert_eq!(wrap_in_two("INVALID SRC", fits), ["INVALID SRC"]);
+        assert_eq!(
+            wrap_in_two("INVALID DESTINATION ARG", fits),
+            ["INVALID", "DESTINATION ARG"]
+        );
+        assert_eq!(wrap_in_two("NOT ENOUGH ARGS", fits), ["NOT ENOUGH", "ARGS"]);
+        assert_eq!(wrap_in_two("UNBREAKABLEWORD", fits), ["UNBREAKABLEWORD"]);
+    }
 
-    for (line_no, full_line) in node_text.split('\n').enumerate() {
-        let Some(semantic_text) = full_line.split('#').next() else {
-            continue;
+    #[test]
+    fn go_to() {
+        assert_eq!(parse_go_to("3, -2").unwrap(), NodeCoord::at(3, -2));
+        assert_eq!(parse_go_to(" (3,-2) ").unwrap(), NodeCoord::at(3, -2));
+        assert!(parse_go_to("").is_err());
+        assert!(parse_go_to("3").is_err());
+        assert!(parse_go_to("x, 2").is_err());
+
+        let type_text = |model: Model, text: &str| {
+            text.chars().fold(model, |model, char| {
+                press(model, Modifiers::None, Key::Char(char))
+            })
         };
 
-        let op_text = match semantic_text.split_once(':') {
-            Some((label, rest)) => {
-                // label refers to the next instruction to be pushed to the list of instructions
-                let label_dest = code.len();
-                labels.insert(label, label_dest as u8);
-                rest
+        let model = press(init().model, Modifiers::Ctrl, Key::Char('G'));
+        let start = model.highlighted_node;
+
+        // a bad coordinate keeps the prompt open and the highlight where it was
+        let model = press(type_text(model, "3; 4"), Modifiers::None, Key::Enter);
+        assert_eq!(model.highlighted_node, start);
+        assert!(matches!(
+            model.prompt,
+            Some(Prompt::GoTo { invalid: true, .. })
+        ));
+
+        let model = press(model, Modifiers::None, Key::Backspace);
+        assert!(matches!(
+            model.prompt,
+            Some(Prompt::GoTo { invalid: false, .. })
+        ));
+
+        let model = (0..2).fold(model, |model, _| {
+            press(model, Modifiers::None, Key::Backspace)
+        });
+        let model = press(type_text(model, ", 7"), Modifiers::None, Key::Enter);
+        assert_eq!(model.highlighted_node, NodeCoord::at(3, 7));
+        assert!(model.prompt.is_none());
+    }
+
+    #[test]
+    fn stats() {
+        let nodes = Nodes::from([
+            (
+                NodeCoord::at(0, 0),
+                Node::exec_with_text("L: ADD 1\n# comment\nJMP L", &Layout::default()).unwrap(),
+            ),
+            (
+                NodeCoord::at(1, 0),
+                Node::exec_with_text("MOV UP", &Layout::default()).unwrap(),
+            ),
+            (NodeCoord::at(0, 1), Node::empty_input()),
+            (NodeCoord::at(1, 1), Node::empty_output()),
+        ]);
+
+        assert_eq!(
+            network_stats(&nodes),
+            NetworkStats {
+                exec_nodes: 2,
+                // the second node doesn't parse
+                instructions: 2,
+                input_nodes: 1,
+                output_nodes: 1,
             }
-            None => semantic_text,
-        };
+        );
+    }
 
-        let tokens = &mut op_text.split_ascii_whitespace();
+    #[test]
+    fn torus() {
+        let top = NodeCoord::at(0, 0);
+        let bottom = NodeCoord::at(0, 2);
+
+        let puzzle = parse_puzzle(
+            r#"
+"0,0" = "MOV 5 UP"
+"0,1" = "NOP"
+"0,2" = "MOV DOWN ACC"
+torus = [ "1,2", "0,0" ]
+"#,
+            None,
+        )
+        .unwrap();
 
-        let Some(opcode) = tokens.next() else {
-            continue;
-        };
+        assert_eq!(
+            puzzle.torus,
+            Some(Torus {
+                min: top,
+                max: NodeCoord::at(1, 2),
+            })
+        );
 
-        let line_no = line_no as u8;
+        let run = |torus| {
+            let mut nodes = puzzle.nodes.clone();
 
-        let op = match opcode {
-            "MOV" => Op::Mov(expect_src(tokens, line_no)?, expect_dst(tokens, line_no)?),
-            "NOP" => Op::Nop,
-            "SWP" => Op::Swp,
-            "SAV" => Op::Sav,
-            "ADD" => Op::Add(expect_src(tokens, line_no)?),
-            "SUB" => Op::Sub(expect_src(tokens, line_no)?),
-            "NEG" => Op::Neg,
-            "JMP" => Op::Jmp(expect_label(tokens, line_no)?),
-            "JEZ" => Op::Jez(expect_label(tokens, line_no)?),
-            "JNZ" => Op::Jnz(expect_label(tokens, line_no)?),
-            "JGZ" => Op::Jgz(expect_label(tokens, line_no)?),
-            "JLZ" => Op::Jlz(expect_label(tokens, line_no)?),
-            "JRO" => Op::Jro(expect_src(tokens, line_no)?),
+            for _ in 0..4 {
+                nodes.extend(step_execution(&nodes, top, torus).unwrap().0);
+            }
 
-            _ => {
-                return Err(ParseErr {
-                    problem: ParseProblem::InvalidInstruction,
-                    line: line_no,
-                });
+            match &nodes[&bottom] {
+                Node::Exec(exec_node) => exec_node.registers().unwrap().0,
+                _ => unreachable!(),
             }
         };
 
-        if tokens.next().is_some() {
-            return Err(ParseErr {
-                problem: ParseProblem::TooManyArgs,
-                line: line_no,
-            });
-        }
+        // the top row's UP is the bottom row, which reads it from DOWN.
+        // that's off by default, so the value has nowhere to go
+        assert_eq!(run(None), Num::ZERO);
+        assert_eq!(run(puzzle.torus), Num::from(5));
 
-        code.push(Instruction {
-            op,
-            src_line: line_no,
-        });
+        // only the cells in the box wrap
+        let torus = puzzle.torus.unwrap();
+
+        assert_eq!(
+            top.io_neighbor(Dir::Left, Some(torus)),
+            Some(NodeCoord::at(1, 0))
+        );
+        assert_eq!(
+            NodeCoord::at(5, 0).io_neighbor(Dir::Up, Some(torus)),
+            Some(NodeCoord::at(5, -1))
+        );
+
+        let saved = serialize_toml(&puzzle.nodes, None, &HashSet::new(), puzzle.torus);
+        assert!(saved.contains("torus = [ \"0, 0\", \"1, 2\" ]"));
+        assert_eq!(parse_puzzle(&saved, None).unwrap().torus, puzzle.torus);
+
+        assert!(matches!(
+            parse_puzzle("torus = [ \"0,0\" ]", None),
+            Err(ImportErr::InvalidTorusRhs)
+        ));
     }
 
-    code.into_iter()
-        .map(|instr| {
-            let resolve = |label: &str| {
-                labels.get(&label).copied().ok_or(ParseErr {
-                    problem: ParseProblem::UndefinedLabel,
-                    line: instr.src_line,
-                })
-            };
+    #[test]
+    fn torus_outside_neighbor() {
+        let torus = Some(Torus::spanning(NodeCoord::at(0, 0), NodeCoord::at(0, 1)));
+        let sender = NodeCoord::at(0, 0);
+        let inside = NodeCoord::at(0, 1);
+        let outside = NodeCoord::at(0, -1);
+
+        // the edge cell's UP wraps, so the cell just above it can't reach it from below
+        assert_eq!(sender.io_neighbor(Dir::Up, torus), Some(inside));
+        assert_eq!(outside.io_neighbor(Dir::Down, torus), None);
+        assert_eq!(
+            outside.io_neighbor(Dir::Up, torus),
+            Some(NodeCoord::at(0, -2))
+        );
 
-            let op = match instr.op {
-                Op::Mov(src, dst) => Op::Mov(src, dst),
-                Op::Nop => Op::Nop,
-                Op::Swp => Op::Swp,
-                Op::Sav => Op::Sav,
-                Op::Add(src) => Op::Add(src),
-                Op::Sub(src) => Op::Sub(src),
-                Op::Neg => Op::Neg,
-                Op::Jmp(label) => Op::Jmp(resolve(label)?),
-                Op::Jez(label) => Op::Jez(resolve(label)?),
-                Op::Jnz(label) => Op::Jnz(resolve(label)?),
-                Op::Jgz(label) => Op::Jgz(resolve(label)?),
-                Op::Jlz(label) => Op::Jlz(resolve(label)?),
-                Op::Jro(src) => Op::Jro(src),
-            };
+        let exec = |text| Node::exec_with_text(text, &Layout::default()).unwrap();
 
-            Ok(Instruction {
-                op,
-                src_line: instr.src_line,
-            })
-        })
-        .try_collect()
-}
+        let mut nodes = Nodes::from([
+            (sender, exec("MOV 5 UP")),
+            (inside, exec("MOV DOWN ACC")),
+            (outside, exec("MOV DOWN ACC")),
+        ]);
 
-fn expect_label<'txt>(
-    tokens: &mut impl Iterator<Item = &'txt str>,
-    line: u8,
-) -> Result<&'txt str, ParseErr> {
-    let Some(label) = tokens.next() else {
-        return Err(ParseErr {
-            problem: ParseProblem::NotEnoughArgs,
-            line,
-        });
-    };
+        for _ in 0..4 {
+            for start in [sender, outside] {
+                nodes.extend(step_execution(&nodes, start, torus).unwrap().0);
+            }
+        }
+
+        let acc = |node_loc| match &nodes[&node_loc] {
+            Node::Exec(exec_node) => exec_node.registers().unwrap().0,
+            _ => unreachable!(),
+        };
+
+        // the one value goes to one reader, not both
+        assert_eq!(acc(inside), Num::from(5));
+        assert_eq!(acc(outside), Num::ZERO);
+    }
+
+    #[test]
+    fn exec_template() {
+        let layout = Layout::default();
+
+        let template = parse_template("## title\n\n", &layout).unwrap();
+        assert_eq!(template.as_str(), "## TITLE\n");
+
+        let too_long = "#".repeat(layout.line_length + 1);
+        let too_tall = "\n".repeat(layout.lines + 1);
+
+        assert!(matches!(
+            parse_template(&too_long, &layout),
+            Err(TextOverflow::LineTooLong)
+        ));
+        assert!(matches!(
+            parse_template(&too_tall, &layout),
+            Err(TextOverflow::TooManyLines)
+        ));
+
+        let empty_spot = NodeCoord::at(100, 100);
+
+        let send = |state: State, mods, key| {
+            let input = Input {
+                mods,
+                pressed: Some(key),
+                window_dimensions: (1024, 1024),
+                mouse_wheel_move: 0.0,
+                mouse_position: Vector2::zero(),
+                clicked: false,
+                drag: None,
+                clipboard: String::new(),
+            };
+
+            match update(state, input) {
+                Update::Update { new, .. } => new,
+                Update::Exit => panic!("did not request exit"),
+            }
+        };
 
-    Ok(label)
-}
+        let new_text = |state: State| {
+            let state = State {
+                model: Model {
+                    nodes: Nodes::new(),
+                    highlighted_node: empty_spot,
+                    ..state.model
+                },
+                ..state
+            };
 
-fn expect_src<'txt>(
-    tokens: &mut impl Iterator<Item = &'txt str>,
-    line: u8,
-) -> Result<Src, ParseErr> {
-    let Some(arg) = tokens.next() else {
-        return Err(ParseErr {
-            problem: ParseProblem::NotEnoughArgs,
-            line,
-        });
-    };
+            let state = send(state, Modifiers::Shift, Key::Char('E'));
 
-    match arg {
-        "ACC" => Ok(Src::Acc),
-        "UP" => Ok(Src::Dir(Dir::Up)),
-        "DOWN" => Ok(Src::Dir(Dir::Down)),
-        "LEFT" => Ok(Src::Dir(Dir::Left)),
-        "RIGHT" => Ok(Src::Dir(Dir::Right)),
-        "NIL" => Ok(Src::Nil),
-        other => {
-            if let Ok(num) = other.parse() {
-                Ok(Src::Imm(num))
-            } else {
-                Err(ParseErr {
-                    problem: ParseProblem::InvalidSrc,
-                    line,
-                })
+            match &state.model.nodes[&empty_spot] {
+                Node::Exec(exec_node) => exec_node.text,
+                _ => unreachable!(),
             }
-        }
+        };
+
+        // nothing changes unless a template is given
+        assert_eq!(new_text(init()).as_str(), "");
+
+        let state = State {
+            exec_template: template,
+            ..init()
+        };
+        assert_eq!(new_text(state).as_str(), template.as_str());
+
+        // starting a new workspace keeps the template, and what's shown
+        let state = State {
+            exec_template: template,
+            ..init()
+        };
+        let state = send(state, Modifiers::None, Key::F(4));
+        let state = send(state, Modifiers::None, Key::F(3));
+        assert!(!state.show_grid && state.show_stats);
+
+        let state = send(state, Modifiers::Ctrl, Key::Char('N'));
+        let state = send(state, Modifiers::None, Key::Enter);
+        assert!(state.model.nodes.is_empty());
+        assert!(!state.show_grid && state.show_stats);
+        assert_eq!(new_text(state).as_str(), template.as_str());
     }
-}
 
-fn expect_dst<'txt>(
-    tokens: &mut impl Iterator<Item = &'txt str>,
-    line: u8,
-) -> Result<Dst, ParseErr> {
-    let Some(arg) = tokens.next() else {
-        return Err(ParseErr {
-            problem: ParseProblem::NotEnoughArgs,
-            line,
-        });
-    };
+    #[test]
+    fn error_columns() {
+        let error = |text: &str| {
+            let err = parse_node_text(&NodeText::from(text).unwrap()).unwrap_err();
+            (err.problem, err.line, err.column)
+        };
 
-    match arg {
-        "ACC" => Ok(Dst::Acc),
-        "UP" => Ok(Dst::Dir(Dir::Up)),
-        "DOWN" => Ok(Dst::Dir(Dir::Down)),
-        "LEFT" => Ok(Dst::Dir(Dir::Left)),
-        "RIGHT" => Ok(Dst::Dir(Dir::Right)),
-        "NIL" => Ok(Dst::Nil),
-        _ => Err(ParseErr {
-            problem: ParseProblem::InvalidDst,
-            line,
-        }),
+        assert!(matches!(
+            error("MOV ACC BADDST"),
+            (ParseProblem::InvalidDst, 0, Some(8))
+        ));
+        assert!(matches!(
+            error("NOP\n  L: MOV BADSRC ACC"),
+            (ParseProblem::InvalidSrc, 1, Some(9))
+        ));
+        assert!(matches!(
+            error("JMP NOWHERE"),
+            (ParseProblem::UndefinedLabel, 0, Some(4))
+        ));
+        assert!(matches!(
+            error("ADD 1 2"),
+            (ParseProblem::TooManyArgs, 0, Some(6))
+        ));
+
+        // a missing argument has no token to point at
+        assert!(matches!(
+            error("MOV ACC"),
+            (ParseProblem::NotEnoughArgs, 0, None)
+        ));
     }
-}
 
-#[derive(Debug)]
-enum ImportErr {
-    InvalidToml,
-    InvalidCoord,
-    NodeTextDoesntFit,
-    InvalidRhs,
-    DuplicateCoord,
-    InvalidHighlightRhs,
-    IntOutOfRange,
-    NotAnInt,
-}
+    #[test]
+    fn move_to_origin() {
+        let exec = || Node::exec_with_text("NOP", &Layout::default()).unwrap();
+
+        let model = |locked| Model {
+            nodes: Nodes::from([
+                (NodeCoord::at(3, 5), exec()),
+                (NodeCoord::at(4, 5), exec()),
+                (NodeCoord::at(3, 7), exec()),
+            ]),
+            highlighted_node: NodeCoord::at(4, 5),
+            locked,
+            dirty: false,
+            ..init().model
+        };
 
-use toml::{Table, Value};
+        let (mods, key) = Action::MoveToOrigin.default_binding();
 
-const HIGHLIGHTED_NODE_KEY: &'static str = "highlighted";
+        let moved = press(model(HashSet::new()), mods, key);
 
-fn parse_toml(toml: &str) -> Result<(Nodes, NodeCoord), ImportErr> {
-    let table: Table = match toml::from_str(toml) {
-        Ok(table) => table,
-        Err(_) => return Err(ImportErr::InvalidToml),
-    };
+        let mut coords: Vec<_> = moved.nodes.keys().copied().collect();
+        coords.sort();
 
-    let mut nodes = Nodes::new();
-    let mut highlighted = None;
+        // still next to each other, with the corner on the origin
+        assert_eq!(
+            coords,
+            [
+                NodeCoord::at(0, 0),
+                NodeCoord::at(1, 0),
+                NodeCoord::at(0, 2)
+            ]
+        );
+        assert_eq!(moved.highlighted_node, NodeCoord::at(1, 0));
+        assert!(moved.dirty);
 
-    for (key, value) in table {
-        if &key == HIGHLIGHTED_NODE_KEY {
-            if let Value::String(coord) = value {
-                highlighted = Some(parse_coord(&coord)?);
-            } else {
-                return Err(ImportErr::InvalidHighlightRhs);
-            }
-        } else {
-            let (node_loc, node) = parse_node(&key, value)?;
+        // already there
+        let again = press(moved, mods, key);
+        assert_eq!(again.nodes.len(), 3);
+        assert!(again.nodes.contains_key(&NodeCoord::at(0, 2)));
 
-            if nodes.try_insert(node_loc, node).is_err() {
-                return Err(ImportErr::DuplicateCoord);
-            };
+        let locked = press(model(HashSet::from([NodeCoord::at(3, 7)])), mods, key);
+
+        assert!(locked.nodes.contains_key(&NodeCoord::at(3, 7)));
+        assert!(!locked.dirty);
+    }
+
+    #[test]
+    fn repeat_seconds() {
+        assert_eq!(parse_repeat_seconds("0.25"), Some(0.25));
+        assert_eq!(parse_repeat_seconds("2"), Some(2.0));
+
+        // any of these would repeat every frame, or never make sense as a time
+        for invalid in ["0", "-0.5", "NaN", "inf", "", "fast"] {
+            assert_eq!(parse_repeat_seconds(invalid), None, "{invalid:?}");
         }
     }
 
-    Ok((nodes, highlighted.unwrap_or(NodeCoord::at(0, 0))))
-}
+    #[test]
+    fn sav_swp_gizmos() {
+        let node_loc = NodeCoord::at(0, 0);
+
+        let mut nodes = Nodes::from([(
+            node_loc,
+            Node::exec_with_text(
+                "MOV 5 ACC\nSAV\nMOV 9 ACC\nSWP\nMOV -100 ACC\nSWP",
+                &Layout::default(),
+            )
+            .unwrap(),
+        )]);
 
-fn parse_node(key: &str, value: Value) -> Result<(NodeCoord, Node), ImportErr> {
-    let node_loc = parse_coord(key)?;
+        let exec = |nodes: &Nodes| match &nodes[&node_loc] {
+            Node::Exec(exec_node) => exec_node.exec.clone(),
+            _ => unreachable!(),
+        };
 
-    let node = match value {
-        Value::String(text) => {
-            Node::exec_with_text(text.trim_end()).ok_or(ImportErr::NodeTextDoesntFit)?
-        }
+        assert_eq!(
+            gizmo_text(&exec(&nodes)),
+            ("0".to_string(), "(0)".to_string(), "EDIT")
+        );
 
-        Value::Array(arr) => {
-            let data = arr
-                .into_iter()
-                .map(|value| {
-                    if let Value::Integer(int) = value {
-                        int.try_into().map_err(|_| ImportErr::IntOutOfRange)
-                    } else {
-                        Err(ImportErr::NotAnInt)
-                    }
-                })
-                .try_collect()?;
+        // ACC and BAK after starting, then after each instruction
+        let expected = [
+            (0, 0, "0", "(0)"),
+            (5, 0, "5", "(0)"),
+            (5, 5, "5", "(5)"),
+            (9, 5, "9", "(5)"),
+            (5, 9, "5", "(9)"),
+            (-100, 9, "-100", "(9)"),
+            (9, -100, "9", "-100"),
+        ];
 
-            Node::Input(InputNode::with_data(data))
+        for (acc, bak, acc_text, bak_text) in expected {
+            nodes.extend(step_execution(&nodes, node_loc, None).unwrap().0);
+
+            let exec = exec(&nodes).unwrap();
+
+            assert_eq!((exec.acc.get(), exec.bak.get()), (acc, bak));
+            assert_eq!(
+                gizmo_text(&Some(exec)),
+                (acc_text.to_string(), bak_text.to_string(), "EXEC")
+            );
         }
+    }
 
-        _ => return Err(ImportErr::InvalidRhs),
-    };
+    #[test]
+    fn poke() {
+        assert_eq!(parse_poke("5 LEFT"), Some((Num::from(5), Dir::Left)));
+        assert_eq!(parse_poke(" -3 up "), Some((Num::from(-3), Dir::Up)));
+        assert_eq!(parse_poke("LEFT 5"), None);
+        assert_eq!(parse_poke("5"), None);
+        assert_eq!(parse_poke("5 LEFT 6"), None);
+
+        let node_loc = NodeCoord::at(0, 0);
+
+        let mut nodes = Nodes::from([(
+            node_loc,
+            Node::exec_with_text("MOV LEFT ACC", &Layout::default()).unwrap(),
+        )]);
+
+        // started, then stuck on the read with nobody on the left
+        for _ in 0..2 {
+            nodes.extend(step_execution(&nodes, node_loc, None).unwrap().0);
+        }
 
-    Ok((node_loc, node))
-}
+        let type_text = |model: Model, text: &str| {
+            text.chars().fold(model, |model, char| {
+                press(model, Modifiers::None, Key::Char(char))
+            })
+        };
 
-fn parse_coord(str: &str) -> Result<NodeCoord, ImportErr> {
-    let mut coords = str.split(',');
+        let (mods, key) = Action::Poke.default_binding();
 
-    let x = coords
-        .next()
-        .ok_or(ImportErr::InvalidCoord)?
-        .trim()
-        .parse()
-        .map_err(|_| ImportErr::InvalidCoord)?;
+        let model = Model {
+            nodes,
+            highlighted_node: node_loc,
+            ..init().model
+        };
 
-    let y = coords
-        .next()
-        .ok_or(ImportErr::InvalidCoord)?
-        .trim()
-        .parse()
-        .map_err(|_| ImportErr::InvalidCoord)?;
+        // it isn't reading from above
+        let model = press(
+            type_text(press(model, mods, key), "5 UP"),
+            Modifiers::None,
+            Key::Enter,
+        );
+        assert!(matches!(
+            model.prompt,
+            Some(Prompt::Poke { invalid: true, .. })
+        ));
+
+        let model = press(model, Modifiers::None, Key::Esc);
+        let model = press(
+            type_text(press(model, mods, key), "5 LEFT"),
+            Modifiers::None,
+            Key::Enter,
+        );
+        assert!(model.prompt.is_none());
 
-    Ok(NodeCoord::at(x, y))
-}
+        let mut nodes = model.nodes;
+        nodes.extend(step_execution(&nodes, node_loc, None).unwrap().0);
 
-fn fmt_coord(node_loc: &NodeCoord) -> String {
-    format!("{}, {}", node_loc.x, node_loc.y)
-}
+        let Node::Exec(exec_node) = &nodes[&node_loc] else {
+            unreachable!()
+        };
+        let exec = exec_node.exec.as_ref().unwrap();
 
-fn serialize_toml(nodes: &Nodes, highlighted_node: Option<NodeCoord>) -> String {
-    let mut toml = String::new();
+        assert_eq!(exec.acc, Num::from(5));
+        assert_eq!(exec.inbox, None);
+        assert_eq!(exec.io, NodeIO::None);
+    }
 
-    for (node_loc, node) in nodes {
-        let key = fmt_coord(node_loc);
+    #[test]
+    fn reset_one_node() {
+        let exec = || Node::exec_with_text("ADD 1", &Layout::default()).unwrap();
+        let (reset, other) = (NodeCoord::at(0, 0), NodeCoord::at(1, 0));
 
-        toml += &match node {
-            Node::Exec(exec_node) => {
-                format!("\"{}\" = \"\"\"\n{}\n\"\"\"\n\n", key, &exec_node.text)
-            }
-            Node::Input(input_node) => {
-                let mut fmt = format!("\"{}\" = [ ", key);
+        let mut nodes = Nodes::from([(reset, exec()), (other, exec())]);
 
-                for num in &input_node.data {
-                    fmt += &format!("{num}, ");
-                }
+        for _ in 0..3 {
+            nodes.extend(step_execution(&nodes, reset, None).unwrap().0);
+        }
 
-                fmt + "]\n\n"
-            }
+        let (mods, key) = Action::ResetNode.default_binding();
+
+        let model = press(
+            Model {
+                nodes,
+                highlighted_node: reset,
+                ..init().model
+            },
+            mods,
+            key,
+        );
+
+        let registers = |node_loc| match &model.nodes[&node_loc] {
+            Node::Exec(exec_node) => exec_node.registers(),
+            _ => unreachable!(),
+        };
+
+        // still running, from the top
+        assert_eq!(registers(reset), Some((Num::ZERO, Num::ZERO, 0)));
+        assert_eq!(registers(other), Some((Num::from(2), Num::ZERO, 0)));
+
+        // a stopped node has nothing to reset
+        let mut stopped = ExecNode {
+            text: NodeText::from("ADD 1").unwrap(),
+            ..ExecNode::empty()
         };
+        stopped.reparse();
+        stopped.reset_runtime();
+        assert!(stopped.exec.is_none());
     }
 
-    if let Some(highlighted) = highlighted_node {
-        toml += &format!("{HIGHLIGHTED_NODE_KEY} = \"{}\"", fmt_coord(&highlighted));
+    #[test]
+    fn io_label_fits() {
+        let layout = Layout::default();
+        let node_loc = NodeCoord::at(0, 0);
+
+        // (top left, bottom right)
+        let rect = |top_left: Vector2, bottom_right: Vector2| (top_left, bottom_right);
+        let grown = |(top_left, bottom_right): (Vector2, Vector2), by: f32| {
+            let by = Vector2::new(by, by);
+            (top_left - by, bottom_right + by)
+        };
+        let overlaps = |a: (Vector2, Vector2), b: (Vector2, Vector2)| {
+            a.0.x < b.1.x && b.0.x < a.1.x && a.0.y < b.1.y && b.0.y < a.1.y
+        };
+        let border = |node_loc: NodeCoord| {
+            grown(
+                rect(
+                    node_loc.top_left_corner(&layout),
+                    node_loc.bottom_right_corner(&layout),
+                ),
+                LINE_THICKNESS / 2.0,
+            )
+        };
+
+        for label in ["-999", "999", "0", "?"] {
+            for dir in Dir::ALL {
+                let indicator = node_loc.io_indicator(dir, &layout);
+                let arrow_center = indicator
+                    - dir
+                        .rotate_right()
+                        .normalized()
+                        .scale_by(1. / 3. * NODE_OUTSIDE_PADDING);
+
+                let (center, scale) = io_label_placement(arrow_center, dir, label);
+                let half = io_label_size(label, scale).scale_by(0.5);
+                let label_rect = rect(center - half, center + half);
+
+                let arrow_reach = Vector2::new(NODE_LINE_HEIGHT, NODE_LINE_HEIGHT);
+                let arrow = grown(
+                    rect(arrow_center - arrow_reach, arrow_center + arrow_reach),
+                    LINE_THICKNESS / 2.0,
+                );
+
+                assert!(!overlaps(label_rect, border(node_loc)), "{label} {dir:?}");
+                assert!(
+                    !overlaps(label_rect, border(node_loc.neighbor(dir))),
+                    "{label} {dir:?}"
+                );
+                assert!(!overlaps(label_rect, arrow), "{label} {dir:?}");
+            }
+        }
+
+        // the widest value fits without shrinking
+        assert_eq!(
+            io_label_placement(Vector2::new(0.0, 0.0), Dir::Left, "-999").1,
+            1.0
+        );
+        assert!(io_label_placement(Vector2::new(0.0, 0.0), Dir::Left, "-999999999").1 < 1.0);
     }
 
-    toml
-}
+    #[test]
+    fn move_selection() {
+        let exec = |text| Node::exec_with_text(text, &Layout::default()).unwrap();
+        let text = |model: &Model, node_loc| match &model.nodes[&node_loc] {
+            Node::Exec(exec_node) => exec_node.text.to_string(),
+            _ => unreachable!(),
+        };
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let row = [
+            (NodeCoord::at(0, 0), "LEFT"),
+            (NodeCoord::at(1, 0), "MIDDLE"),
+            (NodeCoord::at(2, 0), "RIGHT"),
+        ];
+
+        let model = Model {
+            nodes: row
+                .iter()
+                .map(|&(node_loc, text)| (node_loc, exec(text)))
+                .chain([(NodeCoord::at(4, 0), exec("IN THE WAY"))])
+                .collect(),
+            selection: row.iter().map(|&(node_loc, _)| node_loc).collect(),
+            highlighted_node: NodeCoord::at(1, 0),
+            ..init().model
+        };
+
+        // each node moves into the cell the one to its right just left
+        let moved = press(model, Modifiers::CtrlShift, Key::Arrow(Dir::Right));
+
+        assert_eq!(moved.nodes.len(), 4);
+        assert!(!moved.nodes.contains_key(&NodeCoord::at(0, 0)));
+        assert_eq!(text(&moved, NodeCoord::at(1, 0)), "LEFT");
+        assert_eq!(text(&moved, NodeCoord::at(2, 0)), "MIDDLE");
+        assert_eq!(text(&moved, NodeCoord::at(3, 0)), "RIGHT");
+        assert_eq!(moved.highlighted_node, NodeCoord::at(2, 0));
+        assert_eq!(
+            moved.selection,
+            HashSet::from([
+                NodeCoord::at(1, 0),
+                NodeCoord::at(2, 0),
+                NodeCoord::at(3, 0)
+            ])
+        );
+        assert!(moved.dirty);
+
+        // (4, 0) isn't selected, so nothing moves into it
+        let moved = Model {
+            dirty: false,
+            ..moved
+        };
+        let blocked = press(moved, Modifiers::CtrlShift, Key::Arrow(Dir::Right));
+
+        assert_eq!(text(&blocked, NodeCoord::at(3, 0)), "RIGHT");
+        assert_eq!(text(&blocked, NodeCoord::at(4, 0)), "IN THE WAY");
+        assert_eq!(blocked.highlighted_node, NodeCoord::at(2, 0));
+        assert!(!blocked.dirty);
+
+        // and nothing moves into a locked cell, even an empty one
+        let locked = Model {
+            locked: HashSet::from([NodeCoord::at(2, 1)]),
+            ..blocked
+        };
+        let locked = press(locked, Modifiers::CtrlShift, Key::Arrow(Dir::Down));
+
+        assert_eq!(text(&locked, NodeCoord::at(1, 0)), "LEFT");
+        assert_eq!(text(&locked, NodeCoord::at(2, 0)), "MIDDLE");
+        assert!(!locked.nodes.contains_key(&NodeCoord::at(2, 1)));
+        assert!(!locked.dirty);
+    }
 
     #[test]
-    fn navigation() {
-        let initial_state = init();
+    fn mirror_selection() {
+        let exec = |text| Node::exec_with_text(text, &Layout::default()).unwrap();
+        let text = |model: &Model, node_loc| match &model.nodes[&node_loc] {
+            Node::Exec(exec_node) => exec_node.text.to_string(),
+            _ => unreachable!(),
+        };
 
-        let move_direction = Dir::Down;
+        // an L: a column of three with a foot sticking out to the right
+        let l_shape = [
+            (NodeCoord::at(0, 0), "TOP"),
+            (NodeCoord::at(0, 1), "MIDDLE"),
+            (NodeCoord::at(0, 2), "CORNER"),
+            (NodeCoord::at(1, 2), "FOOT"),
+        ];
 
-        let arrow_key = Key::Arrow(move_direction);
+        let model = |extra: Option<NodeCoord>| Model {
+            nodes: l_shape
+                .iter()
+                .map(|&(node_loc, text)| (node_loc, exec(text)))
+                .chain(extra.map(|node_loc| (node_loc, exec("IN THE WAY"))))
+                .collect(),
+            selection: l_shape.iter().map(|&(node_loc, _)| node_loc).collect(),
+            highlighted_node: NodeCoord::at(1, 2),
+            ..init().model
+        };
 
-        let initial_highlighted_node = initial_state.model.highlighted_node;
+        let (mods, key) = Action::MirrorSelection.default_binding();
 
-        let input = Input {
-            mods: Modifiers::Ctrl,
-            pressed: Some(arrow_key),
-            window_dimensions: (1024, 1024),
-            mouse_wheel_move: 0.0,
-            clipboard: String::new(),
+        let mirrored = press(model(None), mods, key);
+
+        assert_eq!(mirrored.nodes.len(), 4);
+        assert_eq!(text(&mirrored, NodeCoord::at(1, 0)), "TOP");
+        assert_eq!(text(&mirrored, NodeCoord::at(1, 1)), "MIDDLE");
+        assert_eq!(text(&mirrored, NodeCoord::at(1, 2)), "CORNER");
+        assert_eq!(text(&mirrored, NodeCoord::at(0, 2)), "FOOT");
+        assert_eq!(mirrored.highlighted_node, NodeCoord::at(0, 2));
+        assert!(mirrored.selection.contains(&NodeCoord::at(1, 0)));
+        assert!(!mirrored.selection.contains(&NodeCoord::at(0, 0)));
+
+        // mirroring it again puts it back
+        let twice = press(mirrored, mods, key);
+        for (node_loc, expected) in l_shape {
+            assert_eq!(text(&twice, node_loc), expected);
+        }
+
+        // (1, 0) isn't selected, so the top can't go there
+        let blocked = press(model(Some(NodeCoord::at(1, 0))), mods, key);
+        assert_eq!(text(&blocked, NodeCoord::at(0, 0)), "TOP");
+        assert_eq!(text(&blocked, NodeCoord::at(1, 0)), "IN THE WAY");
+    }
+
+    #[test]
+    fn dangling_io() {
+        let exec = |text| Node::exec_with_text(text, &Layout::default()).unwrap();
+
+        let nodes = Nodes::from([
+            (
+                NodeCoord::at(0, 0),
+                exec("MOV UP ACC\nMOV ACC DOWN\nMOV ANY LEFT"),
+            ),
+            (NodeCoord::at(0, 1), exec("MOV UP RIGHT")),
+            // broken code isn't looked at, its error says enough
+            (NodeCoord::at(5, 5), exec("MOV UP BADDST")),
+        ]);
+
+        assert_eq!(
+            super::dangling_io(&nodes, None),
+            [
+                (NodeCoord::at(0, 0), Dir::Up),
+                (NodeCoord::at(0, 0), Dir::Left),
+                (NodeCoord::at(0, 1), Dir::Right),
+            ]
+        );
+
+        // wrapped around, up from the top is the bottom node,
+        // but the column the other way round is still empty
+        let torus = Some(Torus::spanning(NodeCoord::at(0, 0), NodeCoord::at(1, 1)));
+
+        assert_eq!(
+            super::dangling_io(&nodes, torus),
+            [
+                (NodeCoord::at(0, 0), Dir::Left),
+                (NodeCoord::at(0, 1), Dir::Right),
+            ]
+        );
+
+        // it's only drawn, and only when asked for
+        let model = Model {
+            nodes,
+            ..init().model
         };
+        assert!(!model.show_dangling_io);
 
-        let update = update(initial_state, input);
+        let (mods, key) = Action::ToggleDanglingIo.default_binding();
+        assert!(press(model, mods, key).show_dangling_io);
+    }
 
-        let (new_state, output) = match update {
-            Update::Exit => panic!("did not request exit"),
-            Update::Update { new, output } => (new, output),
+    #[test]
+    fn recovery_source() {
+        let model = Model {
+            nodes: Nodes::from([(NodeCoord::at(0, 0), Node::empty_exec())]),
+            ..init().model
         };
 
-        assert_eq!(output, Output { clipboard: None });
+        // a workspace that was never saved has nowhere to check against
+        assert_eq!(super::recovery_source(&recovery_toml(&model)), None);
 
-        let expected_highlighted_node = initial_highlighted_node.neighbor(move_direction);
+        let path = temp_path("recovery-source.toml");
+        let model = Model {
+            current_path: Some(path.clone()),
+            ..model
+        };
 
-        assert_eq!(new_state.model.highlighted_node, expected_highlighted_node)
+        let toml = recovery_toml(&model);
+        assert_eq!(super::recovery_source(&toml), Some(path.clone()));
+        assert_eq!(parse_puzzle(&toml, None).unwrap().nodes.len(), 1);
+
+        // a file that's missing can't have been saved since
+        let recovery = path.with_extension("recovery.toml");
+        std::fs::write(&recovery, &toml).unwrap();
+        assert!(!saved_since(&path, &recovery));
+
+        // the times are set rather than waited for, since some file systems only keep whole seconds
+        let set_modified = |path: &Path, modified| {
+            std::fs::File::options()
+                .write(true)
+                .open(path)
+                .unwrap()
+                .set_modified(modified)
+                .unwrap();
+        };
+        let now = std::time::SystemTime::now();
+        let hour = std::time::Duration::from_secs(60 * 60);
+
+        std::fs::write(&path, "").unwrap();
+        set_modified(&path, now - hour);
+        set_modified(&recovery, now);
+        assert!(!saved_since(&path, &recovery));
+
+        set_modified(&recovery, now - 2 * hour);
+        assert!(saved_since(&path, &recovery));
+
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(&recovery);
+    }
+
+    #[test]
+    fn to_json_schema() {
+        let input = NodeCoord::at(0, 0);
+        let relay = NodeCoord::at(0, 1);
+
+        let mut nodes = Nodes::from([
+            (
+                input,
+                Node::Input(InputNode::from_data_str("3, -4").unwrap()),
+            ),
+            (
+                relay,
+                Node::exec_with_text("MOV UP DOWN\n# \"HI\"", &Layout::default()).unwrap(),
+            ),
+            (NodeCoord::at(0, 2), Node::empty_output()),
+            (NodeCoord::at(1, 0), Node::random(7)),
+        ]);
+
+        nodes
+            .get_mut(&input)
+            .unwrap()
+            .set_name(Some(NodeName::from(r#"A"B\C"#).unwrap()));
+
+        let model = Model {
+            nodes,
+            highlighted_node: relay,
+            ..init().model
+        };
+
+        let model = press(model, Modifiers::None, Key::Tab);
+        let model = press(model, Modifiers::None, Key::Tab);
+
+        // scripts read this, so any change to it has to be on purpose
+        assert_eq!(
+            to_json(&model),
+            r#"{
+  "highlighted": { "x": 0, "y": 1 },
+  "nodes": [
+    { "x": 0, "y": 0, "name": "A\"B\\C", "kind": "input", "data": [3, -4], "dir": "down", "index": 1 },
+    { "x": 1, "y": 0, "name": null, "kind": "random", "seed": 7, "state": 1892583 },
+    { "x": 0, "y": 1, "name": null, "kind": "exec", "text": "MOV UP DOWN\n# \"HI\"", "runtime": { "acc": 0, "bak": 0, "ip": 0, "io": { "sending": "down", "value": 3 }, "halted": false } },
+    { "x": 0, "y": 2, "name": null, "kind": "output", "received": [], "expected": [] }
+  ]
+}
+"#
+        );
     }
 }
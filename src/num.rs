@@ -0,0 +1,231 @@
+use std::{
+    fmt::{self, Display},
+    ops::{Add, Neg, Sub},
+    str::FromStr,
+};
+
+/// A value as the TIS-100 sees it, between -999 and 999.
+/// Arithmetic saturates at either end of that range instead of overflowing.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub struct Num(i16);
+
+/// what happens to a result that falls outside the range
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Arithmetic {
+    /// clamps to the nearest end, like the original game
+    #[default]
+    Saturating,
+    /// carries on from the other end, so `999 + 1` is `-999`
+    Wrapping,
+}
+
+#[derive(Debug)]
+pub enum NumErr {
+    OutOfRange,
+    NotANumber,
+}
+
+impl Num {
+    pub const MIN: Num = Num(-999);
+    pub const MAX: Num = Num(999);
+    pub const ZERO: Num = Num(0);
+
+    fn saturating(value: i32) -> Num {
+        Num::saturating_flagged(value).0
+    }
+
+    /// also says whether the value had to be clamped into range
+    fn saturating_flagged(value: i32) -> (Num, bool) {
+        let clamped = value.clamp(Num::MIN.0 as i32, Num::MAX.0 as i32);
+
+        (Num(clamped as i16), clamped != value)
+    }
+
+    fn wrapping(value: i32) -> Num {
+        let (min, max) = (Num::MIN.0 as i32, Num::MAX.0 as i32);
+
+        Num(((value - min).rem_euclid(max - min + 1) + min) as i16)
+    }
+
+    fn in_range(value: i32, arithmetic: Arithmetic) -> Num {
+        match arithmetic {
+            Arithmetic::Saturating => Num::saturating(value),
+            Arithmetic::Wrapping => Num::wrapping(value),
+        }
+    }
+
+    pub fn get(self) -> i16 {
+        self.0
+    }
+
+    /// the same as `+`, along with whether the sum saturated
+    pub fn add_checked(self, other: Num) -> (Num, bool) {
+        Num::saturating_flagged(self.0 as i32 + other.0 as i32)
+    }
+
+    /// the same as `-`, along with whether the difference saturated
+    pub fn sub_checked(self, other: Num) -> (Num, bool) {
+        Num::saturating_flagged(self.0 as i32 - other.0 as i32)
+    }
+
+    /// `+` is the saturating version of this
+    pub fn add_with(self, other: Num, arithmetic: Arithmetic) -> Num {
+        Num::in_range(self.0 as i32 + other.0 as i32, arithmetic)
+    }
+
+    /// `-` is the saturating version of this
+    pub fn sub_with(self, other: Num, arithmetic: Arithmetic) -> Num {
+        Num::in_range(self.0 as i32 - other.0 as i32, arithmetic)
+    }
+}
+
+impl Add for Num {
+    type Output = Num;
+
+    fn add(self, other: Num) -> Num {
+        self.add_checked(other).0
+    }
+}
+
+impl Sub for Num {
+    type Output = Num;
+
+    fn sub(self, other: Num) -> Num {
+        self.sub_checked(other).0
+    }
+}
+
+impl Neg for Num {
+    type Output = Num;
+
+    // the range is symmetric, so this can't actually saturate
+    fn neg(self) -> Num {
+        Num::saturating(-(self.0 as i32))
+    }
+}
+
+/// every `i8` is in range, so this is handy for small constants
+impl From<i8> for Num {
+    fn from(value: i8) -> Num {
+        Num(value as i16)
+    }
+}
+
+impl TryFrom<i64> for Num {
+    type Error = NumErr;
+
+    fn try_from(value: i64) -> Result<Num, NumErr> {
+        if (Num::MIN.0 as i64..=Num::MAX.0 as i64).contains(&value) {
+            Ok(Num(value as i16))
+        } else {
+            Err(NumErr::OutOfRange)
+        }
+    }
+}
+
+impl FromStr for Num {
+    type Err = NumErr;
+
+    fn from_str(str: &str) -> Result<Num, NumErr> {
+        let value: i64 = str.parse().map_err(|_| NumErr::NotANumber)?;
+
+        value.try_into()
+    }
+}
+
+/// always the plain integer, whatever the format flags say:
+/// no `+` and no `-0`, so what's drawn is exactly what gets saved and parsed back.
+/// width and alignment still apply
+impl Display for Num {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&self.0.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saturating_arithmetic() {
+        assert_eq!(Num::MAX + Num::MAX, Num::MAX);
+        assert_eq!(Num::MIN + Num::MIN, Num::MIN);
+        assert_eq!(Num::MIN - Num::MAX, Num::MIN);
+        assert_eq!(Num::MAX - Num::MIN, Num::MAX);
+        assert_eq!(-Num::MIN, Num::MAX);
+        assert_eq!(-Num::MAX, Num::MIN);
+        assert_eq!(-Num::ZERO, Num::ZERO);
+
+        // right at the edges
+        assert_eq!(Num(998) + Num::from(1), Num::MAX);
+        assert_eq!(Num(998) + Num::from(2), Num::MAX);
+        assert_eq!(Num(-998) - Num::from(2), Num::MIN);
+        assert_eq!(Num::MAX + Num::from(-1), Num(998));
+    }
+
+    #[test]
+    fn saturation_flag() {
+        assert_eq!(Num(998).add_checked(Num::from(1)), (Num::MAX, false));
+        assert_eq!(Num(998).add_checked(Num::from(2)), (Num::MAX, true));
+        assert_eq!(Num::MAX.add_checked(Num::MAX), (Num::MAX, true));
+        assert_eq!(Num::MIN.add_checked(Num::MAX), (Num::ZERO, false));
+
+        assert_eq!(Num(-998).sub_checked(Num::from(1)), (Num::MIN, false));
+        assert_eq!(Num(-998).sub_checked(Num::from(2)), (Num::MIN, true));
+        assert_eq!(Num::MAX.sub_checked(Num::MIN), (Num::MAX, true));
+        assert_eq!(Num::ZERO.sub_checked(Num::MAX), (Num::MIN, false));
+
+        // the operators give the same values, without the flag
+        assert_eq!(
+            Num::MAX + Num::from(1),
+            Num::MAX.add_checked(Num::from(1)).0
+        );
+        assert_eq!(
+            Num::MIN - Num::from(1),
+            Num::MIN.sub_checked(Num::from(1)).0
+        );
+    }
+
+    #[test]
+    fn wrapping_arithmetic() {
+        use Arithmetic::{Saturating, Wrapping};
+
+        assert_eq!(Num::MAX.add_with(Num::from(1), Saturating), Num::MAX);
+        assert_eq!(Num::MAX.add_with(Num::from(1), Wrapping), Num::MIN);
+        assert_eq!(Num::MIN.sub_with(Num::from(1), Saturating), Num::MIN);
+        assert_eq!(Num::MIN.sub_with(Num::from(1), Wrapping), Num::MAX);
+
+        assert_eq!(Num::MAX.add_with(Num::MAX, Wrapping), Num(-1));
+        assert_eq!(Num::MIN.sub_with(Num::MAX, Wrapping), Num(1));
+        assert_eq!(Num(500).add_with(Num(400), Wrapping), Num(900));
+
+        // saturating is what the operators do
+        assert_eq!(Num::MAX.add_with(Num::MAX, Saturating), Num::MAX + Num::MAX);
+    }
+
+    #[test]
+    fn range() {
+        assert_eq!("999".parse::<Num>().unwrap(), Num::MAX);
+        assert_eq!("-999".parse::<Num>().unwrap(), Num::MIN);
+        assert!(matches!("1000".parse::<Num>(), Err(NumErr::OutOfRange)));
+        assert!(matches!("-1000".parse::<Num>(), Err(NumErr::OutOfRange)));
+        assert!(matches!("ACC".parse::<Num>(), Err(NumErr::NotANumber)));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Num::ZERO.to_string(), "0");
+        assert_eq!(Num::from(-1).to_string(), "-1");
+        assert_eq!(Num::MAX.to_string(), "999");
+        assert_eq!(Num::MIN.to_string(), "-999");
+
+        assert_eq!((-Num::ZERO).to_string(), "0");
+        assert_eq!("-0".parse::<Num>().unwrap().to_string(), "0");
+        assert_eq!(format!("{:+}", Num::from(5)), "5");
+        assert_eq!(format!("{:>4}", Num::from(-5)), "  -5");
+
+        for num in [Num::ZERO, Num::from(-1), Num::MAX, Num::MIN] {
+            assert_eq!(num.to_string().parse::<Num>().unwrap(), num);
+        }
+    }
+}